@@ -0,0 +1,52 @@
+#!/usr/bin/env bash
+# kittentts-lock.sh — Pin KittenTTS packages and model files
+# Usage: ./scripts/kittentts-lock.sh [--bundle DIR]
+#
+# Writes scripts/lock/requirements.lock (every package pinned with its hashes)
+# and scripts/lock/models.sha256 (checksums of the model files). Commit both
+# so they ship with the app; until they exist, release builds warn and the
+# KittenTTS setup installs from the unpinned requirements.txt. With --bundle,
+# also fills DIR with the wheels and model files for offline installs; point
+# the KittenTTS setup at DIR to install without network access.
+
+set -euo pipefail
+cd "$(git rev-parse --show-toplevel)/scripts"
+
+BUNDLE=""
+if [ "${1:-}" = "--bundle" ]; then
+    BUNDLE="$(mkdir -p "$2" && cd "$2" && pwd)"
+fi
+
+WORK="$(mktemp -d)"
+trap 'rm -rf "$WORK"' EXIT
+
+python3 -m venv "$WORK/venv"
+"$WORK/venv/bin/pip" install --quiet pip-tools
+
+mkdir -p lock
+echo "Locking requirements.txt..."
+"$WORK/venv/bin/pip-compile" --quiet --generate-hashes --allow-unsafe \
+    --output-file lock/requirements.lock requirements.txt
+
+echo "Fetching the model..."
+"$WORK/venv/bin/pip" install --quiet --require-hashes --no-deps -r lock/requirements.lock
+HF_HOME="$WORK/models" "$WORK/venv/bin/python" -c "from kittentts import KittenTTS; KittenTTS()"
+
+# The loader reads the snapshot paths, found through refs/main when offline;
+# sha256sum follows the snapshots' blob symlinks
+(cd "$WORK/models" && find hub \( -path '*/snapshots/*' -o -path '*/refs/*' \) \
+    \( -type f -o -type l \) | sort | xargs sha256sum) > lock/models.sha256
+echo "Wrote lock/requirements.lock and lock/models.sha256"
+
+if [ -n "$BUNDLE" ]; then
+    echo "Bundling into $BUNDLE..."
+    "$WORK/venv/bin/pip" download --quiet --require-hashes --no-deps \
+        -r lock/requirements.lock -d "$BUNDLE/wheels"
+    cp lock/requirements.lock lock/models.sha256 "$BUNDLE/"
+    MANIFEST="$PWD/lock/models.sha256"
+    (cd "$WORK/models" && while read -r _ path; do
+        mkdir -p "$BUNDLE/models/$(dirname "$path")"
+        cp -L "$path" "$BUNDLE/models/$path"
+    done < "$MANIFEST")
+    echo "Bundle ready: $BUNDLE"
+fi
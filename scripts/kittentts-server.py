@@ -79,6 +79,12 @@ def tts():
         return f"TTS generation error: {e}", 500
 
 
+@app.route("/api/health", methods=["GET"])
+def health():
+    # The model is loaded before the server starts listening
+    return jsonify({"status": "ok"})
+
+
 @app.route("/api/voices", methods=["GET"])
 def voices():
     return jsonify(
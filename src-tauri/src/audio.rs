@@ -0,0 +1,683 @@
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+
+use futures_util::future::BoxFuture;
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as SymphoniaError,
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    error::Error,
+    tts_provider::{TtsOptions, TtsProvider, TtsVoice},
+    tts_servers::DepCheck,
+};
+
+/// Mono PCM audio with samples in [-1.0, 1.0].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pcm {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl Pcm {
+    pub fn silence(sample_rate: u32, ms: u32) -> Self {
+        let len = (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Self {
+            sample_rate,
+            samples: vec![0.0; len],
+        }
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        (self.samples.len() as u64 * 1000 / self.sample_rate as u64) as u32
+    }
+
+    /// Windowed-sinc resample. When the rate goes down the filter cutoff
+    /// drops to the new Nyquist frequency, so content above it is removed
+    /// instead of aliasing into the audible band.
+    pub fn resampled(&self, sample_rate: u32) -> Pcm {
+        // Kernel half-width in zero crossings of the sinc
+        const ZERO_CROSSINGS: f64 = 16.0;
+
+        if sample_rate == self.sample_rate || self.samples.is_empty() {
+            return Pcm {
+                sample_rate,
+                samples: self.samples.clone(),
+            };
+        }
+        let ratio = self.sample_rate as f64 / sample_rate as f64;
+        // Cutoff as a fraction of the source Nyquist frequency
+        let cutoff = (1.0 / ratio).min(1.0);
+        let half_width = ZERO_CROSSINGS / cutoff;
+        let len = (self.samples.len() as f64 / ratio).round() as usize;
+        let last = self.samples.len() - 1;
+        let samples = (0..len)
+            .map(|i| {
+                let pos = i as f64 * ratio;
+                let first = (pos - half_width).ceil().max(0.0) as usize;
+                let end = ((pos + half_width).floor() as usize).min(last);
+                let (mut sum, mut weights) = (0.0, 0.0);
+                for k in first..=end {
+                    let x = pos - k as f64;
+                    let t = std::f64::consts::PI * cutoff * x;
+                    let sinc = if t == 0.0 { 1.0 } else { t.sin() / t };
+                    let hann = 0.5 * (1.0 + (std::f64::consts::PI * x / half_width).cos());
+                    let weight = sinc * hann;
+                    sum += self.samples[k] as f64 * weight;
+                    weights += weight;
+                }
+                // Normalizing keeps a constant signal constant near the edges too
+                if weights == 0.0 {
+                    0.0
+                } else {
+                    (sum / weights) as f32
+                }
+            })
+            .collect();
+        Pcm {
+            sample_rate,
+            samples,
+        }
+    }
+
+    /// Appends `other`, resampling it to this clip's rate if needed.
+    pub fn append(&mut self, other: &Pcm) {
+        if other.sample_rate == self.sample_rate {
+            self.samples.extend_from_slice(&other.samples);
+        } else {
+            self.samples
+                .extend_from_slice(&other.resampled(self.sample_rate).samples);
+        }
+    }
+
+    /// Appends `other` overlapping the last `ms` of this clip, fading one
+    /// out while the other fades in.
+    pub fn append_crossfade(&mut self, other: &Pcm, ms: u32) {
+        let other = if other.sample_rate == self.sample_rate {
+            std::borrow::Cow::Borrowed(other)
+        } else {
+            std::borrow::Cow::Owned(other.resampled(self.sample_rate))
+        };
+        let len = (self.sample_rate as u64 * ms as u64 / 1000) as usize;
+        let len = len.min(self.samples.len()).min(other.samples.len());
+        let start = self.samples.len() - len;
+        for i in 0..len {
+            let t = (i + 1) as f32 / (len + 1) as f32;
+            self.samples[start + i] = self.samples[start + i] * (1.0 - t) + other.samples[i] * t;
+        }
+        self.samples.extend_from_slice(&other.samples[len..]);
+    }
+
+    pub fn append_silence(&mut self, ms: u32) {
+        let len = (self.sample_rate as u64 * ms as u64 / 1000) as usize;
+        self.samples.resize(self.samples.len() + len, 0.0);
+    }
+
+    /// Cuts leading and trailing audio quieter than `threshold_db` (RMS over
+    /// 10 ms frames), keeping `padding_ms` on either side of the speech. A
+    /// clip that is silent throughout is left alone.
+    pub fn trim_silence(&mut self, threshold_db: f32, padding_ms: u32) {
+        let frame = (self.sample_rate as usize / 100).max(1);
+        let threshold = 10f32.powf(threshold_db / 20.0);
+        let loud = |chunk: &[f32]| {
+            let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+            rms > threshold
+        };
+        let frames: Vec<bool> = self.samples.chunks(frame).map(loud).collect();
+        let (Some(first), Some(last)) = (
+            frames.iter().position(|&l| l),
+            frames.iter().rposition(|&l| l),
+        ) else {
+            return;
+        };
+        let padding = (self.sample_rate as u64 * padding_ms as u64 / 1000) as usize;
+        let start = (first * frame).saturating_sub(padding);
+        let end = ((last + 1) * frame + padding).min(self.samples.len());
+        self.samples.truncate(end);
+        self.samples.drain(..start);
+    }
+
+    /// Integrated loudness in LUFS as defined by ITU-R BS.1770: K-weighted
+    /// mean square over 400 ms blocks, gated at -70 LUFS and then 10 LU
+    /// below the ungated level. `None` for silence.
+    pub fn loudness_lufs(&self) -> Option<f32> {
+        if self.sample_rate == 0 || self.samples.is_empty() {
+            return None;
+        }
+        let rate = self.sample_rate as f64;
+        let (mut shelf, mut high_pass) = (Biquad::k_shelf(rate), Biquad::k_high_pass(rate));
+        let weighted: Vec<f64> = self
+            .samples
+            .iter()
+            .map(|&s| high_pass.process(shelf.process(s as f64)))
+            .collect();
+
+        // 400 ms blocks overlapping by 75%; a shorter clip is one block
+        let block = ((rate * 0.4) as usize).min(weighted.len());
+        let step = (block / 4).max(1);
+        let powers: Vec<f64> = (0..=(weighted.len() - block) / step)
+            .map(|i| {
+                let chunk = &weighted[i * step..i * step + block];
+                chunk.iter().map(|y| y * y).sum::<f64>() / block as f64
+            })
+            .collect();
+
+        let lufs = |power: f64| -0.691 + 10.0 * power.log10();
+        let gated_mean = |threshold: f64| {
+            let gated: Vec<f64> = powers
+                .iter()
+                .copied()
+                .filter(|&p| p > 0.0 && lufs(p) > threshold)
+                .collect();
+            (!gated.is_empty()).then(|| gated.iter().sum::<f64>() / gated.len() as f64)
+        };
+        let relative = lufs(gated_mean(-70.0)?) - 10.0;
+        gated_mean(relative).map(|p| lufs(p) as f32)
+    }
+
+    /// Scales the clip to `target_lufs`, lowering the gain if needed so no
+    /// sample goes above `ceiling_db` dBFS.
+    pub fn normalize_loudness(&mut self, target_lufs: f32, ceiling_db: f32) {
+        let Some(loudness) = self.loudness_lufs() else {
+            return;
+        };
+        let peak = self.samples.iter().fold(0f32, |m, s| m.max(s.abs()));
+        let ceiling = 10f32.powf(ceiling_db / 20.0);
+        let gain = 10f32
+            .powf((target_lufs - loudness) / 20.0)
+            .min(ceiling / peak);
+        for s in &mut self.samples {
+            *s *= gain;
+        }
+    }
+}
+
+/// Second-order IIR filter (direct form I), coefficients normalized by a0.
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    /// First stage of the K-weighting, a high shelf modelling the head.
+    /// The filters are specified for 48 kHz; designing them from their
+    /// analog parameters (as libebur128 does) keeps them right at any rate.
+    fn k_shelf(rate: f64) -> Self {
+        let k = (std::f64::consts::PI * 1_681.974_450_955_533 / rate).tan();
+        let q = 0.707_175_236_955_419_6;
+        let vh = 10f64.powf(3.999_843_853_973_347 / 20.0);
+        let vb = vh.powf(0.499_666_774_154_541_6);
+        Self::new(
+            [
+                vh + vb * k / q + k * k,
+                2.0 * (k * k - vh),
+                vh - vb * k / q + k * k,
+            ],
+            [
+                1.0 + k / q + k * k,
+                2.0 * (k * k - 1.0),
+                1.0 - k / q + k * k,
+            ],
+        )
+    }
+
+    /// Second stage of the K-weighting, a high pass around 38 Hz.
+    fn k_high_pass(rate: f64) -> Self {
+        let k = (std::f64::consts::PI * 38.135_470_876_024_44 / rate).tan();
+        let q = 0.500_327_037_323_877_3;
+        let a0 = 1.0 + k / q + k * k;
+        Self::new(
+            [a0, -2.0 * a0, a0],
+            [a0, 2.0 * (k * k - 1.0), 1.0 - k / q + k * k],
+        )
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// Decodes a WAV file of any bit depth and channel count into mono PCM.
+pub fn decode_wav(bytes: &[u8]) -> Result<Pcm, Error> {
+    let mut reader = WavReader::new(Cursor::new(bytes))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(Pcm {
+        sample_rate: spec.sample_rate,
+        samples,
+    })
+}
+
+/// Decodes WAV, MP3 or Ogg Vorbis into mono PCM. WAV goes through
+/// [`decode_wav`]; everything else is probed by symphonia.
+pub fn decode(bytes: &[u8]) -> Result<Pcm, Error> {
+    if bytes.starts_with(b"RIFF") {
+        return decode_wav(bytes);
+    }
+
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(bytes.to_vec())), Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &Hint::new(),
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or(SymphoniaError::Unsupported("no audio track"))?;
+    let track_id = track.id;
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame is skipped rather than failing the clip
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable audio frame: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let channels = spec.channels.count().max(1);
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+        samples.extend(
+            buf.samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+        );
+    }
+
+    Ok(Pcm {
+        sample_rate,
+        samples,
+    })
+}
+
+fn wav_spec(sample_rate: u32) -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Encodes PCM as a 16-bit mono WAV file in memory.
+pub fn encode_wav(pcm: &Pcm) -> Result<Vec<u8>, Error> {
+    let mut buf = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut buf, wav_spec(pcm.sample_rate))?;
+        for &s in &pcm.samples {
+            writer.write_sample(to_i16(s))?;
+        }
+        writer.finalize()?;
+    }
+    Ok(buf.into_inner())
+}
+
+/// How synthesized speech is evened out before it is played or exported,
+/// so clips from different providers match in level, rate and pauses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PostProcess {
+    pub enabled: bool,
+    /// Integrated loudness every clip is brought to
+    pub target_lufs: f32,
+    /// Leading and trailing audio below this level is cut
+    pub silence_threshold_db: f32,
+    /// Silence kept before and after the speech
+    pub padding_ms: u32,
+    /// Every clip is resampled to this rate
+    pub sample_rate: u32,
+}
+
+impl Default for PostProcess {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_lufs: -16.0,
+            silence_threshold_db: -50.0,
+            padding_ms: 30,
+            sample_rate: 24000,
+        }
+    }
+}
+
+/// Peaks are kept below this after loudness normalization.
+const PEAK_CEILING_DB: f32 = -1.0;
+
+impl PostProcess {
+    pub fn apply(&self, pcm: &Pcm) -> Pcm {
+        if !self.enabled {
+            return pcm.clone();
+        }
+        let mut pcm = pcm.clone();
+        pcm.trim_silence(self.silence_threshold_db, self.padding_ms);
+        let mut pcm = pcm.resampled(self.sample_rate);
+        pcm.normalize_loudness(self.target_lufs, PEAK_CEILING_DB);
+        pcm
+    }
+
+    /// Decodes `audio`, applies the pipeline and returns it as WAV.
+    pub fn process(&self, audio: &[u8]) -> Result<Vec<u8>, Error> {
+        encode_wav(&self.apply(&decode(audio)?))
+    }
+}
+
+const POST_PROCESS_FILE: &str = "audio.json";
+
+/// Post-processing settings, read from the app data dir on first use.
+#[derive(Default)]
+pub struct PostProcessState(Mutex<Option<PostProcess>>);
+
+impl PostProcessState {
+    pub fn get(&self, app: &AppHandle) -> PostProcess {
+        let mut guard = self.0.lock().unwrap();
+        guard
+            .get_or_insert_with(|| {
+                app.path()
+                    .app_data_dir()
+                    .ok()
+                    .and_then(|dir| std::fs::read(dir.join(POST_PROCESS_FILE)).ok())
+                    .and_then(|data| serde_json::from_slice(&data).ok())
+                    .unwrap_or_default()
+            })
+            .clone()
+    }
+
+    fn set(&self, app: &AppHandle, settings: PostProcess) -> Result<(), Error> {
+        let dir = app.path().app_data_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let data = serde_json::to_vec_pretty(&settings).map_err(std::io::Error::from)?;
+        std::fs::write(dir.join(POST_PROCESS_FILE), data)?;
+        *self.0.lock().unwrap() = Some(settings);
+        Ok(())
+    }
+}
+
+/// Runs synthesized audio through the current post-processing settings.
+/// Audio that can't be decoded is passed through unchanged, so a format the
+/// pipeline doesn't know never silences narration.
+fn post_process(app: &AppHandle, audio: Vec<u8>) -> Vec<u8> {
+    let settings = app.state::<PostProcessState>().get(app);
+    if !settings.enabled {
+        return audio;
+    }
+    match settings.process(&audio) {
+        Ok(processed) => processed,
+        Err(e) => {
+            log::warn!("Skipping audio post-processing: {}", e);
+            audio
+        }
+    }
+}
+
+/// Post-processes everything the wrapped provider synthesizes. The TTS
+/// cache holds the providers' original audio, so changed settings apply to
+/// cached clips too.
+pub struct PostProcessed(pub Arc<dyn TtsProvider>);
+
+impl TtsProvider for PostProcessed {
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    fn supports_ssml(&self) -> bool {
+        self.0.supports_ssml()
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        self.0.list_voices(app, options)
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let audio = self.0.synthesize(app, text, voice, options).await?;
+            Ok(post_process(app, audio))
+        })
+    }
+
+    fn speak<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        self.0.speak(app, text, voice, options)
+    }
+
+    fn stop<'a>(&'a self, app: &'a AppHandle) -> BoxFuture<'a, Result<(), Error>> {
+        self.0.stop(app)
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        self.0.health(app, options)
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn audio_post_process_get(
+    app: AppHandle,
+    state: tauri::State<'_, PostProcessState>,
+) -> PostProcess {
+    state.get(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn audio_post_process_set(
+    settings: PostProcess,
+    app: AppHandle,
+    state: tauri::State<'_, PostProcessState>,
+) -> Result<(), Error> {
+    state.set(&app, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_roundtrip() {
+        let pcm = Pcm {
+            sample_rate: 16000,
+            samples: vec![0.0, 0.5, -0.5, 0.25],
+        };
+        let decoded = decode_wav(&encode_wav(&pcm).unwrap()).unwrap();
+        assert_eq!(decoded.sample_rate, 16000);
+        for (a, b) in pcm.samples.iter().zip(&decoded.samples) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn append_resamples_and_pads() {
+        let mut pcm = Pcm::silence(24000, 100);
+        pcm.append(&Pcm::silence(12000, 100));
+        pcm.append_silence(50);
+        assert_eq!(pcm.duration_ms(), 250);
+    }
+
+    #[test]
+    fn crossfade_overlaps() {
+        let mut pcm = Pcm {
+            sample_rate: 1000,
+            samples: vec![1.0; 100],
+        };
+        pcm.append_crossfade(
+            &Pcm {
+                sample_rate: 1000,
+                samples: vec![0.0; 100],
+            },
+            9,
+        );
+        assert_eq!(pcm.duration_ms(), 191);
+        assert_eq!(pcm.samples[90], 1.0);
+        assert!((pcm.samples[91] - 0.9).abs() < 1e-6);
+        assert!((pcm.samples[95] - 0.5).abs() < 1e-6);
+        assert_eq!(pcm.samples[100], 0.0);
+    }
+
+    fn sine(sample_rate: u32, freq: f32, amplitude: f32, ms: u32) -> Pcm {
+        let len = (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Pcm {
+            sample_rate,
+            samples: (0..len)
+                .map(|i| {
+                    let t = i as f32 / sample_rate as f32;
+                    amplitude * (2.0 * std::f32::consts::PI * freq * t).sin()
+                })
+                .collect(),
+        }
+    }
+
+    fn rms(pcm: &Pcm) -> f32 {
+        (pcm.samples.iter().map(|s| s * s).sum::<f32>() / pcm.samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn downsampling_filters_instead_of_aliasing() {
+        // 10 kHz is above the 8 kHz Nyquist frequency of 16 kHz audio
+        let high = sine(48000, 10000.0, 0.5, 200).resampled(16000);
+        assert_eq!(high.duration_ms(), 200);
+        assert!(rms(&high) < 0.02, "aliased: {}", rms(&high));
+
+        let low = sine(48000, 1000.0, 0.5, 200).resampled(16000);
+        assert!((rms(&low) - 0.5 / 2f32.sqrt()).abs() < 0.02);
+    }
+
+    #[test]
+    fn trims_silence_with_padding() {
+        let mut pcm = Pcm::silence(1000, 500);
+        pcm.append(&sine(1000, 100.0, 0.5, 200));
+        pcm.append_silence(300);
+        pcm.trim_silence(-50.0, 20);
+        assert_eq!(pcm.duration_ms(), 240);
+
+        let mut silent = Pcm::silence(1000, 100);
+        silent.trim_silence(-50.0, 20);
+        assert_eq!(silent.duration_ms(), 100);
+    }
+
+    #[test]
+    fn loudness_of_reference_sine() {
+        // BS.1770: a 1 kHz sine with 0 dBFS peak on one channel is -3.01 LUFS
+        let lufs = sine(48000, 1000.0, 1.0, 2000).loudness_lufs().unwrap();
+        assert!((lufs + 3.01).abs() < 0.1, "{}", lufs);
+        let lufs = sine(24000, 1000.0, 0.1, 2000).loudness_lufs().unwrap();
+        assert!((lufs + 23.01).abs() < 0.2, "{}", lufs);
+        assert_eq!(Pcm::silence(24000, 1000).loudness_lufs(), None);
+    }
+
+    #[test]
+    fn normalizes_to_target_below_ceiling() {
+        let mut pcm = sine(24000, 1000.0, 0.05, 1000);
+        pcm.normalize_loudness(-16.0, -1.0);
+        assert!((pcm.loudness_lufs().unwrap() + 16.0).abs() < 0.1);
+
+        // Reaching -2 LUFS would need a peak above the ceiling
+        let mut pcm = sine(24000, 1000.0, 0.05, 1000);
+        pcm.normalize_loudness(-2.0, -1.0);
+        let peak = pcm.samples.iter().fold(0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 10f32.powf(-1.0 / 20.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn post_process_pipeline() {
+        let mut pcm = Pcm::silence(16000, 400);
+        pcm.append(&sine(16000, 440.0, 0.02, 500));
+        pcm.append_silence(400);
+        let settings = PostProcess::default();
+        let out = decode(&settings.process(&encode_wav(&pcm).unwrap()).unwrap()).unwrap();
+        assert_eq!(out.sample_rate, 24000);
+        assert!((out.duration_ms() as i32 - 560).abs() <= 10);
+        assert!((out.loudness_lufs().unwrap() + 16.0).abs() < 0.3);
+
+        let disabled = PostProcess {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.apply(&pcm), pcm);
+    }
+}
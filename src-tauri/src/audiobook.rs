@@ -0,0 +1,666 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use log::info;
+use pgn_reader::{BufferedReader, Nag, RawComment, RawHeader, SanPlus, Skip, Visitor};
+use serde::{Deserialize, Serialize};
+use shakmaty::{fen::Fen, Chess, Color, EnPassantMode, Outcome, Position};
+use specta::Type;
+use tauri::Manager;
+
+use crate::{
+    audio::{decode, encode_wav, Pcm},
+    captions::{CaptionTrack, Chapter, Cue},
+    db::{get_db_game, Outcome as DbOutcome},
+    engine::parse_fen_to_position,
+    error::Error,
+    narration::{clean_comment, narrate_move, outcome_to_spoken, vocab},
+    opening::get_opening_from_fen,
+    progress::update_progress,
+    tts_provider::{TtsOptions, TtsProviders},
+    AppState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GamePhase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+impl GamePhase {
+    /// Phase of a mainline position. Known book positions are the opening;
+    /// once the pieces (not counting pawns and kings) are down to 26 points
+    /// of material or less it is an endgame.
+    fn of(pos: &Chess, fen: &str) -> Self {
+        let material = pos.board().material().map(|p| {
+            p.knight as u32 * 3 + p.bishop as u32 * 3 + p.rook as u32 * 5 + p.queen as u32 * 9
+        });
+        if material.white + material.black <= 26 {
+            GamePhase::Endgame
+        } else if get_opening_from_fen(fen).is_ok() {
+            GamePhase::Opening
+        } else {
+            GamePhase::Middlegame
+        }
+    }
+
+    pub fn title(&self, lang: &str) -> &'static str {
+        let vocab = vocab(lang);
+        match self {
+            GamePhase::Opening => vocab.opening,
+            GamePhase::Middlegame => vocab.middlegame,
+            GamePhase::Endgame => vocab.endgame,
+        }
+    }
+}
+
+/// One spoken unit of a game script.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Intro(String),
+    Move {
+        ply: u32,
+        san: String,
+        /// Position after the move, `None` if the move is illegal
+        fen: Option<String>,
+        text: String,
+    },
+    Comment(String),
+    VariationStart,
+    VariationEnd,
+    /// The mainline entered a new phase with the next move
+    Phase(GamePhase),
+    Result(String),
+}
+
+struct PendingMove {
+    san: SanPlus,
+    ply: u32,
+    fen: Option<String>,
+    nags: Vec<Nag>,
+    comments: Vec<String>,
+}
+
+/// Turns a PGN game into a linear sequence of narration segments, walking
+/// variations depth-first in the order they appear.
+struct ScriptBuilder {
+    lang: String,
+    include_variations: bool,
+    headers: Vec<(String, String)>,
+    segments: Vec<Segment>,
+    pending: Option<PendingMove>,
+    ply: u32,
+    ply_stack: Vec<u32>,
+    /// Position after the last move and the one before it, so a variation
+    /// can replay from the right place
+    pos: Option<Chess>,
+    prev_pos: Option<Chess>,
+    pos_stack: Vec<(Option<Chess>, Option<Chess>)>,
+    phase: Option<GamePhase>,
+}
+
+impl ScriptBuilder {
+    fn new(lang: &str, include_variations: bool) -> Self {
+        Self {
+            lang: lang.to_string(),
+            include_variations,
+            headers: Vec::new(),
+            segments: Vec::new(),
+            pending: None,
+            ply: 0,
+            ply_stack: Vec::new(),
+            pos: Some(Chess::default()),
+            prev_pos: None,
+            pos_stack: Vec::new(),
+            phase: None,
+        }
+    }
+
+    fn header_value(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty() && !v.starts_with('?'))
+    }
+
+    fn intro(&self) -> String {
+        let mut parts = Vec::new();
+        if let (Some(white), Some(black)) = (self.header_value("White"), self.header_value("Black"))
+        {
+            parts.push(format!("{} versus {}.", white, black));
+        }
+        if let Some(event) = self.header_value("Event") {
+            parts.push(format!("{}.", event));
+        }
+        // Dates are "YYYY.MM.DD" with unknown parts as "??"; the year is enough
+        if let Some(year) = self
+            .header_value("Date")
+            .and_then(|d| d.split('.').next())
+            .filter(|y| y.chars().all(|c| c.is_ascii_digit()))
+        {
+            parts.push(format!("{}.", year));
+        }
+        parts.join(" ")
+    }
+
+    fn flush(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            let text = narrate_move(
+                Some(&pending.san),
+                &pending.nags,
+                &pending.comments.join(" "),
+                pending.ply,
+                &[],
+                &self.lang,
+            );
+            self.segments.push(Segment::Move {
+                ply: pending.ply,
+                san: pending.san.to_string(),
+                fen: pending.fen,
+                text,
+            });
+        }
+    }
+}
+
+impl Visitor for ScriptBuilder {
+    type Result = Vec<Segment>;
+
+    fn header(&mut self, key: &[u8], value: RawHeader<'_>) {
+        let key = String::from_utf8_lossy(key).to_string();
+        let value = value.decode_utf8_lossy().into_owned();
+        if key == "FEN" {
+            if let Ok(fen) = value.parse::<Fen>() {
+                let setup = fen.as_setup();
+                self.ply = (setup.fullmoves.get() - 1) * 2 + u32::from(setup.turn == Color::Black);
+            }
+            self.pos = parse_fen_to_position(&value).ok();
+        }
+        self.headers.push((key, value));
+    }
+
+    fn end_headers(&mut self) -> Skip {
+        let intro = self.intro();
+        if !intro.is_empty() {
+            self.segments.push(Segment::Intro(intro));
+        }
+        Skip(false)
+    }
+
+    fn san(&mut self, san: SanPlus) {
+        self.flush();
+        self.ply += 1;
+
+        let next = self.pos.as_ref().and_then(|pos| {
+            let m = san.san.to_move(pos).ok()?;
+            let mut next = pos.clone();
+            next.play_unchecked(&m);
+            Some(next)
+        });
+        self.prev_pos = std::mem::replace(&mut self.pos, next);
+        let fen = self
+            .pos
+            .as_ref()
+            .map(|pos| Fen::from_position(pos.clone(), EnPassantMode::Legal).to_string());
+
+        if let (true, Some(pos), Some(fen)) = (self.ply_stack.is_empty(), &self.pos, &fen) {
+            // Phases only move forward along the mainline, a transposition
+            // back into book doesn't reopen the opening
+            let phase = GamePhase::of(pos, fen);
+            if self.phase.map_or(true, |p| phase > p) {
+                self.phase = Some(phase);
+                self.segments.push(Segment::Phase(phase));
+            }
+        }
+
+        self.pending = Some(PendingMove {
+            san,
+            ply: self.ply,
+            fen,
+            nags: Vec::new(),
+            comments: Vec::new(),
+        });
+    }
+
+    fn nag(&mut self, nag: Nag) {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.nags.push(nag);
+        }
+    }
+
+    fn comment(&mut self, comment: RawComment<'_>) {
+        let comment = String::from_utf8_lossy(comment.as_bytes()).to_string();
+        match self.pending.as_mut() {
+            Some(pending) => pending.comments.push(comment),
+            None => {
+                // Comment before the first move of the game or a variation
+                let text = clean_comment(&comment, &self.lang);
+                if !text.is_empty() {
+                    self.segments.push(Segment::Comment(text));
+                }
+            }
+        }
+    }
+
+    fn begin_variation(&mut self) -> Skip {
+        if !self.include_variations {
+            return Skip(true);
+        }
+        self.flush();
+        // A variation replaces the move just played
+        self.ply_stack.push(self.ply);
+        self.ply = self.ply.saturating_sub(1);
+        self.pos_stack
+            .push((self.pos.clone(), self.prev_pos.clone()));
+        self.pos = self.prev_pos.clone();
+        self.segments.push(Segment::VariationStart);
+        Skip(false)
+    }
+
+    fn end_variation(&mut self) {
+        self.flush();
+        if let Some(ply) = self.ply_stack.pop() {
+            self.ply = ply;
+        }
+        if let Some((pos, prev_pos)) = self.pos_stack.pop() {
+            self.pos = pos;
+            self.prev_pos = prev_pos;
+        }
+        self.segments.push(Segment::VariationEnd);
+    }
+
+    fn outcome(&mut self, outcome: Option<Outcome>) {
+        self.flush();
+        let text = outcome_to_spoken(outcome, &self.lang);
+        if !text.is_empty() {
+            self.segments.push(Segment::Result(text));
+        }
+    }
+
+    fn end_game(&mut self) -> Self::Result {
+        self.flush();
+        std::mem::take(&mut self.segments)
+    }
+}
+
+pub fn build_script(
+    pgn: &str,
+    lang: &str,
+    include_variations: bool,
+) -> Result<Vec<Segment>, Error> {
+    let mut reader = BufferedReader::new(pgn.as_bytes());
+    let mut builder = ScriptBuilder::new(lang, include_variations);
+    Ok(reader.read_game(&mut builder)?.unwrap_or_default())
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AudiobookSource {
+    Pgn {
+        pgn: String,
+    },
+    Database {
+        file: PathBuf,
+        #[serde(rename = "gameId")]
+        game_id: i32,
+    },
+}
+
+/// Local TTS server used to render the clips.
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AudiobookVoice {
+    KittenTts { url: String, voice: String },
+    OpenTts { url: String, voice: String },
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AudiobookOptions {
+    pub voice: AudiobookVoice,
+    pub lang: String,
+    pub move_pause_ms: u32,
+    pub variation_pause_ms: u32,
+    pub include_variations: bool,
+    /// Also write SRT, WebVTT and JSON caption files next to the audio
+    #[serde(default)]
+    pub captions: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AudiobookSummary {
+    pub clips: u32,
+    pub duration_ms: u32,
+    pub captions: CaptionTrack,
+}
+
+async fn source_to_pgn(
+    source: AudiobookSource,
+    state: &tauri::State<'_, AppState>,
+) -> Result<String, Error> {
+    match source {
+        AudiobookSource::Pgn { pgn } => Ok(pgn),
+        AudiobookSource::Database { file, game_id } => {
+            let game = get_db_game(file, game_id, state.clone())
+                .await?
+                .ok_or_else(|| Error::GameNotFound(game_id.to_string()))?;
+            let result = match game.result {
+                DbOutcome::WhiteWin => "1-0",
+                DbOutcome::BlackWin => "0-1",
+                DbOutcome::Draw => "1/2-1/2",
+                DbOutcome::Unknown => "*",
+            };
+            let mut pgn = format!(
+                "[Event \"{}\"]\n[Date \"{}\"]\n[White \"{}\"]\n[Black \"{}\"]\n[Result \"{}\"]\n",
+                escape_tag(&game.event),
+                escape_tag(&game.date.unwrap_or_default()),
+                escape_tag(&game.white),
+                escape_tag(&game.black),
+                result
+            );
+            if game.fen != Fen::default().to_string() {
+                pgn.push_str(&format!("[FEN \"{}\"]\n[SetUp \"1\"]\n", game.fen));
+            }
+            pgn.push_str(&format!("\n{} {}\n", game.moves, result));
+            Ok(pgn)
+        }
+    }
+}
+
+/// Escapes a tag value for a PGN header, where `"` and `\` need a
+/// backslash.
+fn escape_tag(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+async fn synthesize(
+    app: &tauri::AppHandle,
+    voice: &AudiobookVoice,
+    lang: &str,
+    text: &str,
+) -> Result<Pcm, Error> {
+    let (provider, url, voice) = match voice {
+        AudiobookVoice::KittenTts { url, voice } => ("kittentts", url, voice),
+        AudiobookVoice::OpenTts { url, voice } => ("opentts", url, voice),
+    };
+    let options = TtsOptions {
+        language: Some(lang.to_string()),
+        server_url: Some(url.clone()),
+        ..Default::default()
+    };
+    let provider = app.state::<TtsProviders>().get(provider)?;
+    let audio = provider.synthesize(app, text, voice, &options).await?;
+    decode(&audio)
+}
+
+/// Renders a whole game as one narrated WAV file: header intro, every move
+/// with its comment, variations set off by longer pauses, and the result.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_audiobook(
+    id: String,
+    source: AudiobookSource,
+    options: AudiobookOptions,
+    dest: PathBuf,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<AudiobookSummary, Error> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    state
+        .tts_job_cancel_flags
+        .insert(id.clone(), cancel_flag.clone());
+
+    let result = render_audiobook(&id, source, &options, &dest, &cancel_flag, &app, &state).await;
+
+    state.tts_job_cancel_flags.remove(&id);
+    update_progress(&state.progress_state, &app, id, 100.0, true)?;
+    result
+}
+
+async fn render_audiobook(
+    id: &str,
+    source: AudiobookSource,
+    options: &AudiobookOptions,
+    dest: &Path,
+    cancel_flag: &AtomicBool,
+    app: &tauri::AppHandle,
+    state: &tauri::State<'_, AppState>,
+) -> Result<AudiobookSummary, Error> {
+    let pgn = source_to_pgn(source, state).await?;
+    let script = build_script(&pgn, &options.lang, options.include_variations)?;
+
+    let mut output: Option<Pcm> = None;
+    let mut clips = 0;
+    let mut track = CaptionTrack::default();
+    // Chapters start with the next spoken clip, not at the preceding pause
+    let mut next_chapter: Option<String> = None;
+
+    for (i, segment) in script.iter().enumerate() {
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Err(Error::ExportCancelled);
+        }
+        update_progress(
+            &state.progress_state,
+            app,
+            id.to_string(),
+            (i as f32 / script.len() as f32) * 100.0,
+            false,
+        )?;
+
+        let (text, pause_after) = match segment {
+            Segment::Intro(text) | Segment::Comment(text) => (text, options.move_pause_ms),
+            Segment::Move { text, .. } => (text, options.move_pause_ms),
+            Segment::Result(text) => (text, 0),
+            Segment::Phase(phase) => {
+                next_chapter = Some(phase.title(&options.lang).to_string());
+                continue;
+            }
+            Segment::VariationStart | Segment::VariationEnd => {
+                if let Some(output) = output.as_mut() {
+                    output.append_silence(options.variation_pause_ms);
+                }
+                if *segment == Segment::VariationStart {
+                    next_chapter = Some(variation_title(&script[i + 1..], &options.lang));
+                }
+                continue;
+            }
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        let clip = synthesize(app, &options.voice, &options.lang, text).await?;
+        let out = output.get_or_insert_with(|| Pcm::silence(clip.sample_rate, 0));
+        let start_ms = out.duration_ms();
+        out.append(&clip);
+        let end_ms = out.duration_ms();
+        out.append_silence(pause_after);
+        clips += 1;
+
+        if let Some(title) = next_chapter.take() {
+            track.chapters.push(Chapter { start_ms, title });
+        }
+        let (ply, san, fen) = match segment {
+            Segment::Move { ply, san, fen, .. } => (Some(*ply), Some(san.clone()), fen.clone()),
+            _ => (None, None, None),
+        };
+        track.cues.push(Cue {
+            start_ms,
+            end_ms,
+            text: text.clone(),
+            ply,
+            san,
+            fen,
+        });
+    }
+
+    let output = output.ok_or(Error::NoMovesFound)?;
+    std::fs::write(dest, encode_wav(&output)?)?;
+    if options.captions {
+        track.write_sidecars(dest, output.duration_ms())?;
+    }
+    info!(
+        "Exported audiobook to {} ({} clips, {} ms)",
+        dest.display(),
+        clips,
+        output.duration_ms()
+    );
+
+    Ok(AudiobookSummary {
+        clips,
+        duration_ms: output.duration_ms(),
+        captions: track,
+    })
+}
+
+/// Chapter title for a variation, named after its first move ("Variation 3... exd4").
+fn variation_title(rest: &[Segment], lang: &str) -> String {
+    let variation = vocab(lang).variation;
+    rest.iter()
+        .find_map(|s| match s {
+            Segment::Move { ply, san, .. } => Some(format!(
+                "{} {}{} {}",
+                variation,
+                ply.div_ceil(2),
+                if ply % 2 == 0 { "..." } else { "." },
+                san
+            )),
+            _ => None,
+        })
+        .unwrap_or_else(|| variation.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_tts_job(id: String, state: tauri::State<'_, AppState>) -> Result<(), Error> {
+    if let Some(flag) = state.tts_job_cancel_flags.get(&id) {
+        flag.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGN: &str = r#"[Event "Paris"]
+[Date "1858.??.??"]
+[White "Morphy"]
+[Black "Duke Karl / Count Isouard"]
+
+1. e4 e5 2. Nf3 d6 {Philidor Defense} 3. d4 Bg4? (3... exd4 {is normal}) 4. dxe5 1-0"#;
+
+    #[test]
+    fn script_with_variations() {
+        let script = build_script(PGN, "en", true).unwrap();
+        assert_eq!(
+            script[0],
+            Segment::Intro("Morphy versus Duke Karl / Count Isouard. Paris. 1858.".to_string())
+        );
+        assert_eq!(script[1], Segment::Phase(GamePhase::Opening));
+        assert_eq!(
+            script[5],
+            Segment::Move {
+                ply: 4,
+                san: "d6".to_string(),
+                fen: Some(
+                    "rnbqkbnr/ppp2ppp/3p4/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 3".to_string()
+                ),
+                text: "2, d6.  Philidor Defense".to_string()
+            }
+        );
+        assert!(matches!(
+            &script[7],
+            Segment::Move { ply: 6, text, .. } if text == "3, Bishop g4.  Mistake."
+        ));
+        assert_eq!(script[8], Segment::VariationStart);
+        assert_eq!(
+            script[9],
+            Segment::Move {
+                ply: 6,
+                san: "exd4".to_string(),
+                fen: Some(
+                    "rnbqkbnr/ppp2ppp/3p4/8/3pP3/5N2/PPP2PPP/RNBQKB1R w KQkq - 0 4".to_string()
+                ),
+                text: "3, e takes d4.  is normal".to_string()
+            }
+        );
+        assert_eq!(script[10], Segment::VariationEnd);
+        assert!(script[11..].iter().any(|s| matches!(
+            s,
+            Segment::Move {
+                ply: 7,
+                fen: Some(_),
+                ..
+            }
+        )));
+        assert_eq!(
+            script.last(),
+            Some(&Segment::Result("White wins.".to_string()))
+        );
+        assert_eq!(variation_title(&script[9..], "en"), "Variation 3... exd4");
+        assert_eq!(variation_title(&script[9..], "de"), "Variante 3... exd4");
+        assert_eq!(GamePhase::Middlegame.title("fr-FR"), "Milieu de partie");
+    }
+
+    #[test]
+    fn escaped_tags_roundtrip() {
+        let white = r#"Kasparov, "Gazza" \ G."#;
+        let pgn = format!(
+            "[White \"{}\"]\n[Black \"Deep Blue\"]\n\n1. e4 *",
+            escape_tag(white)
+        );
+        let script = build_script(&pgn, "en", false).unwrap();
+        assert_eq!(
+            script[0],
+            Segment::Intro(format!("{} versus Deep Blue.", white))
+        );
+    }
+
+    #[test]
+    fn script_without_variations() {
+        let script = build_script(PGN, "en", false).unwrap();
+        assert!(!script.contains(&Segment::VariationStart));
+        assert_eq!(
+            script
+                .iter()
+                .filter(|s| matches!(s, Segment::Move { .. }))
+                .count(),
+            7
+        );
+    }
+
+    #[test]
+    fn script_from_fen() {
+        let pgn = r#"[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40"]
+
+40... Kd7 41. e4 *"#;
+        let script = build_script(pgn, "en", true).unwrap();
+        assert_eq!(
+            script,
+            vec![
+                Segment::Phase(GamePhase::Endgame),
+                Segment::Move {
+                    ply: 80,
+                    san: "Kd7".to_string(),
+                    fen: Some("8/3k4/8/8/8/8/4P3/4K3 w - - 1 41".to_string()),
+                    text: "40, King d7.".to_string()
+                },
+                Segment::Move {
+                    ply: 81,
+                    san: "e4".to_string(),
+                    fen: Some("8/3k4/8/8/4P3/8/8/4K3 b - - 0 41".to_string()),
+                    text: "41, e4.".to_string()
+                },
+            ]
+        );
+    }
+}
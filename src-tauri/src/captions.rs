@@ -0,0 +1,178 @@
+use std::{fmt::Write, path::Path};
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::error::Error;
+
+/// One timed subtitle. Move cues also carry the move and resulting position
+/// so a player can sync the board to the audio.
+#[derive(Serialize, Debug, Clone, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Cue {
+    pub start_ms: u32,
+    pub end_ms: u32,
+    pub text: String,
+    pub ply: Option<u32>,
+    pub san: Option<String>,
+    pub fen: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub start_ms: u32,
+    pub title: String,
+}
+
+#[derive(Serialize, Debug, Clone, Type, Default, PartialEq)]
+pub struct CaptionTrack {
+    pub cues: Vec<Cue>,
+    pub chapters: Vec<Chapter>,
+}
+
+/// `HH:MM:SS<sep>mmm`; SRT separates milliseconds with a comma, WebVTT with a dot.
+fn timestamp(ms: u32, sep: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        sep,
+        ms % 1000
+    )
+}
+
+impl CaptionTrack {
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (i, cue) in self.cues.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(cue.start_ms, ','),
+                timestamp(cue.end_ms, ','),
+                cue.text
+            );
+        }
+        out
+    }
+
+    pub fn to_vtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for (i, cue) in self.cues.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(cue.start_ms, '.'),
+                timestamp(cue.end_ms, '.'),
+                cue.text
+            );
+        }
+        out
+    }
+
+    /// WebVTT chapter track: each chapter runs until the next one starts,
+    /// the last one until `total_ms`.
+    pub fn chapters_vtt(&self, total_ms: u32) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for (i, chapter) in self.chapters.iter().enumerate() {
+            let end = self
+                .chapters
+                .get(i + 1)
+                .map(|c| c.start_ms)
+                .unwrap_or(total_ms);
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(chapter.start_ms, '.'),
+                timestamp(end, '.'),
+                chapter.title
+            );
+        }
+        out
+    }
+
+    /// Writes `.srt`, `.vtt`, `.chapters.vtt` and `.json` files next to the
+    /// exported audio.
+    pub fn write_sidecars(&self, audio: &Path, total_ms: u32) -> Result<(), Error> {
+        std::fs::write(audio.with_extension("srt"), self.to_srt())?;
+        std::fs::write(audio.with_extension("vtt"), self.to_vtt())?;
+        std::fs::write(
+            audio.with_extension("chapters.vtt"),
+            self.chapters_vtt(total_ms),
+        )?;
+        let json = serde_json::to_string_pretty(self).unwrap_or_default();
+        std::fs::write(audio.with_extension("json"), json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> CaptionTrack {
+        CaptionTrack {
+            cues: vec![
+                Cue {
+                    start_ms: 0,
+                    end_ms: 1500,
+                    text: "Morphy versus Duke Karl.".to_string(),
+                    ply: None,
+                    san: None,
+                    fen: None,
+                },
+                Cue {
+                    start_ms: 2000,
+                    end_ms: 3725,
+                    text: "1, e4.".to_string(),
+                    ply: Some(1),
+                    san: Some("e4".to_string()),
+                    fen: Some(
+                        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string(),
+                    ),
+                },
+            ],
+            chapters: vec![
+                Chapter {
+                    start_ms: 2000,
+                    title: "Opening".to_string(),
+                },
+                Chapter {
+                    start_ms: 3_723_004,
+                    title: "Endgame".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp(0, ','), "00:00:00,000");
+        assert_eq!(timestamp(3_723_004, '.'), "01:02:03.004");
+    }
+
+    #[test]
+    fn srt_output() {
+        assert_eq!(
+            track().to_srt(),
+            "1\n00:00:00,000 --> 00:00:01,500\nMorphy versus Duke Karl.\n\n\
+             2\n00:00:02,000 --> 00:00:03,725\n1, e4.\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_output() {
+        let vtt = track().to_vtt();
+        assert!(vtt.starts_with("WEBVTT\n\n1\n00:00:00.000 --> 00:00:01.500\n"));
+        assert_eq!(
+            track().chapters_vtt(3_800_000),
+            "WEBVTT\n\n1\n00:00:02.000 --> 01:02:03.004\nOpening\n\n\
+             2\n01:02:03.004 --> 01:03:20.000\nEndgame\n\n"
+        );
+    }
+}
@@ -1,11 +1,10 @@
 use std::{
-    fmt::Display,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use derivative::Derivative;
@@ -18,6 +17,7 @@ use shakmaty::{
     Position, Role,
 };
 use specta::Type;
+use tauri::Manager;
 use tauri_specta::Event;
 use tokio::sync::Mutex;
 use vampirc_uci::{
@@ -29,9 +29,12 @@ use vampirc_uci::{
 use crate::{
     db::{is_position_in_db, GameQuery, PositionQueryJs},
     engine::{
-        parse_fen_and_apply_moves, BaseEngine, EngineLog, EngineOption, EngineReader, GoMode,
+        default_option, is_button, parse_fen_and_apply_moves, validate_options, BaseEngine,
+        EngineLog, EngineOption, EnginePoolConfig, EnginePoolStatus, EngineReader, GoMode,
+        OptionProblem,
     },
     error::Error,
+    narration::{narrate_eval, EvalNarrationOptions},
     progress::update_progress,
     AppState,
 };
@@ -60,10 +63,16 @@ pub struct EngineProcess {
     last_progress: f32,
     last_event_sent: Option<Instant>,
     options: EngineOptions,
+    /// Options as the session asked for them, before the pool's caps
+    requested: EngineOptions,
     go_mode: GoMode,
     running: bool,
     real_multipv: u16,
     start: Instant,
+    /// Slot in `AppState::engine_pool`
+    slot: u32,
+    /// Set when the session ends; its loop then returns the engine to the pool
+    released: bool,
 }
 
 impl EngineProcess {
@@ -81,23 +90,27 @@ impl EngineProcess {
                 last_progress: 0.0,
                 last_event_sent: None,
                 options: EngineOptions::default(),
+                requested: EngineOptions::default(),
                 real_multipv: 0,
                 go_mode: GoMode::Infinite,
                 running: false,
                 start: Instant::now(),
+                slot: 0,
+                released: false,
             },
             reader,
         ))
     }
 
-    async fn set_option<T>(&mut self, name: &str, value: T) -> Result<(), Error>
-    where
-        T: Display,
-    {
-        self.base.set_option(name, value).await
-    }
-
+    /// Validates the options against the engine's declared ones, leaving out
+    /// any it doesn't declare, and sends those that changed. Buttons are
+    /// pressed each time they are passed.
     async fn set_options(&mut self, options: EngineOptions) -> Result<(), Error> {
+        let extra_options = self.base.known_options(&options.extra_options)?;
+        let options = EngineOptions {
+            extra_options,
+            ..options
+        };
         let pos = parse_fen_and_apply_moves(&options.fen, &options.moves)?;
         let multipv = options
             .extra_options
@@ -108,9 +121,27 @@ impl EngineProcess {
 
         self.real_multipv = multipv.min(pos.legal_moves().len() as u16);
 
+        // A warm engine from the pool keeps whatever its last holder set, so
+        // options left out this time go back to the engine's defaults
+        let schema = self.base.option_schema();
+        let defaults: Vec<_> = self
+            .options
+            .extra_options
+            .iter()
+            .filter(|old| {
+                !options
+                    .extra_options
+                    .iter()
+                    .any(|o| o.name.eq_ignore_ascii_case(&old.name))
+            })
+            .filter_map(|old| default_option(schema, &old.name))
+            .collect();
+        for option in &defaults {
+            self.base.send_option(option).await?;
+        }
         for option in &options.extra_options {
             if !self.options.extra_options.contains(option) {
-                self.set_option(&option.name, &option.value).await?;
+                self.base.send_option(option).await?;
             }
         }
 
@@ -122,7 +153,11 @@ impl EngineProcess {
             self.set_position(&options.fen, &options.moves).await?;
         }
         self.last_depth = 0;
-        self.options = options.clone();
+        self.options = options;
+        let schema = self.base.option_schema();
+        self.options
+            .extra_options
+            .retain(|o| !is_button(schema, &o.name));
         self.best_moves.clear();
         self.last_best_moves.clear();
         Ok(())
@@ -136,6 +171,16 @@ impl EngineProcess {
     }
 
     async fn go(&mut self, mode: &GoMode) -> Result<(), Error> {
+        if let GoMode::Limits(limits) = mode {
+            if !limits.search_moves.is_empty() {
+                let pos = parse_fen_and_apply_moves(&self.options.fen, &self.options.moves)?;
+                for m in &limits.search_moves {
+                    UciMove::from_ascii(m.as_bytes())?.to_move(&pos)?;
+                }
+                // Only the allowed moves get lines
+                self.real_multipv = self.real_multipv.min(limits.search_moves.len() as u16);
+            }
+        }
         self.go_mode = mode.clone();
         self.base.go(mode).await?;
         self.running = true;
@@ -144,12 +189,42 @@ impl EngineProcess {
         Ok(())
     }
 
+    /// The opponent played the pondered move: the search carries on under
+    /// its normal limits, timed from now.
+    async fn ponder_hit(&mut self) -> Result<(), Error> {
+        self.base.send("ponderhit").await?;
+        if let GoMode::Limits(limits) = &mut self.go_mode {
+            limits.ponder = false;
+        }
+        self.start = Instant::now();
+        Ok(())
+    }
+
     async fn stop(&mut self) -> Result<(), Error> {
         self.base.stop().await?;
         self.running = false;
         Ok(())
     }
 
+    /// Readies the engine for its next lease. `ucinewgame` clears the hash and
+    /// waiting for `readyok` drains output left from the last search.
+    async fn reset(&mut self) -> Result<(), Error> {
+        self.base.stop().await?;
+        self.base.send("ucinewgame").await?;
+        self.base.send("isready").await?;
+        self.base.wait_for("readyok").await?;
+        self.options.fen.clear();
+        self.options.moves.clear();
+        self.requested = EngineOptions::default();
+        self.best_moves.clear();
+        self.last_best_moves.clear();
+        self.last_depth = 0;
+        self.last_progress = 0.0;
+        self.running = false;
+        self.released = false;
+        Ok(())
+    }
+
     async fn kill(&mut self) -> Result<(), Error> {
         self.base.quit().await?;
         self.running = false;
@@ -176,6 +251,13 @@ pub struct BestMoves {
     nps: u32,
 }
 
+impl BestMoves {
+    /// Spoken summary of the line for listening instead of looking.
+    pub fn narrate(&self, options: &EvalNarrationOptions) -> String {
+        narrate_eval(&self.score, &self.san_moves, options)
+    }
+}
+
 #[derive(Serialize, Debug, Clone, Type, Event)]
 #[serde(rename_all = "camelCase")]
 pub struct BestMovesPayload {
@@ -260,6 +342,113 @@ pub struct EngineOptions {
     pub extra_options: Vec<EngineOption>,
 }
 
+type SharedEngine = Arc<Mutex<EngineProcess>>;
+
+/// Leases an engine from the pool, reusing a warm one when there is one.
+/// `options` are cut down to fit the pool's thread and hash caps.
+async fn lease_engine(
+    state: &AppState,
+    path: PathBuf,
+    options: &mut Vec<EngineOption>,
+    holder: String,
+) -> Result<(SharedEngine, EngineReader), Error> {
+    let lease =
+        state
+            .engine_pool
+            .lock()
+            .unwrap()
+            .acquire(&path, options, holder, Instant::now())?;
+    shutdown_engines(lease.evicted).await;
+    *options = lease.options;
+
+    if let Some(process) = lease.process {
+        let reader = process.lock().await.base.take_reader();
+        if let Some(reader) = reader {
+            return Ok((process, reader));
+        }
+    }
+    match EngineProcess::new(path.clone()).await {
+        Ok((mut process, reader)) => {
+            process.slot = lease.slot;
+            let schema = EngineSchema::new(&path, process.base.option_schema().to_vec());
+            state.engine_schemas.insert(path, schema);
+            Ok((Arc::new(Mutex::new(process)), reader))
+        }
+        Err(e) => {
+            state.engine_pool.lock().unwrap().forget(lease.slot);
+            Err(e)
+        }
+    }
+}
+
+/// Hands an engine back to the pool, or shuts it down if it does not get
+/// ready again in time.
+async fn return_engine(state: &AppState, process: SharedEngine, reader: EngineReader) {
+    let (slot, reset) = {
+        let mut proc = process.lock().await;
+        proc.base.reader = Some(reader);
+        let reset = tokio::time::timeout(Duration::from_secs(5), proc.reset()).await;
+        if !matches!(reset, Ok(Ok(()))) {
+            warn!("Engine did not get ready for reuse, shutting it down");
+            proc.kill_sync();
+        }
+        (proc.slot, matches!(reset, Ok(Ok(()))))
+    };
+    let mut pool = state.engine_pool.lock().unwrap();
+    if reset {
+        pool.release(slot, process, Instant::now());
+    } else {
+        pool.forget(slot);
+    }
+}
+
+async fn shutdown_engines(processes: Vec<SharedEngine>) {
+    for process in processes {
+        if let Err(e) = process.lock().await.kill().await {
+            warn!("Failed to shut down engine: {:?}", e);
+        }
+    }
+}
+
+/// Ends a `get_best_moves` session; its loop returns the engine to the pool.
+async fn end_session(process: &SharedEngine) -> Result<(), Error> {
+    let mut process = process.lock().await;
+    process.released = true;
+    process.stop().await
+}
+
+/// Shuts down pooled engines that have been idle past the pool's timeout.
+pub async fn reap_idle_engines(app: tauri::AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(15));
+    loop {
+        interval.tick().await;
+        let state = app.state::<AppState>();
+        let idle = state.engine_pool.lock().unwrap().reap(Instant::now());
+        shutdown_engines(idle).await;
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn engine_pool_status(state: tauri::State<'_, AppState>) -> EnginePoolStatus {
+    state.engine_pool.lock().unwrap().status(Instant::now())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn engine_pool_configure(
+    config: EnginePoolConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), Error> {
+    let idle = {
+        let mut pool = state.engine_pool.lock().unwrap();
+        pool.configure(config);
+        pool.reap(Instant::now())
+    };
+    shutdown_engines(idle).await;
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn kill_engines(tab: String, state: tauri::State<'_, AppState>) -> Result<(), Error> {
@@ -268,14 +457,11 @@ pub async fn kill_engines(tab: String, state: tauri::State<'_, AppState>) -> Res
         .iter()
         .map(|x| x.key().clone())
         .collect();
-    for key in keys.clone() {
+    for key in keys {
         if key.0.starts_with(&tab) {
-            {
-                let process = state.engine_processes.get_mut(&key).unwrap();
-                let mut process = process.lock().await;
-                process.kill().await?;
+            if let Some((_, process)) = state.engine_processes.remove(&key) {
+                end_session(&process).await?;
             }
-            state.engine_processes.remove(&key);
         }
     }
     Ok(())
@@ -289,9 +475,8 @@ pub async fn kill_engine(
     state: tauri::State<'_, AppState>,
 ) -> Result<(), Error> {
     let key = (tab, engine);
-    if let Some(process) = state.engine_processes.get(&key) {
-        let mut process = process.lock().await;
-        process.kill().await?;
+    if let Some((_, process)) = state.engine_processes.remove(&key) {
+        end_session(&process).await?;
     }
     Ok(())
 }
@@ -310,6 +495,22 @@ pub async fn stop_engine(
     Ok(())
 }
 
+/// Tells a pondering session that the expected move was played. The caller
+/// should pass the same go mode without `ponder` from then on.
+#[tauri::command]
+#[specta::specta]
+pub async fn ponder_hit(
+    engine: String,
+    tab: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), Error> {
+    let key = (tab, engine);
+    if let Some(process) = state.engine_processes.get(&key).map(|p| p.clone()) {
+        process.lock().await.ponder_hit().await?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_engine_logs(
@@ -345,7 +546,7 @@ pub async fn get_best_moves(
         {
             let process = state.engine_processes.get_mut(&key).unwrap();
             let mut process = process.lock().await;
-            if options == process.options && go_mode == process.go_mode && process.running {
+            if options == process.requested && go_mode == process.go_mode && process.running {
                 return Ok(Some((
                     process.last_progress,
                     process.last_best_moves.clone(),
@@ -355,23 +556,75 @@ pub async fn get_best_moves(
         }
         // give time for engine to stop and process previous lines
         tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        {
-            let process = state.engine_processes.get_mut(&key).unwrap();
-            let mut process = process.lock().await;
-            process.set_options(options.clone()).await?;
-            process.go(&go_mode).await?;
-        }
+        let Some(process) = state.engine_processes.get(&key).map(|p| p.clone()) else {
+            return Ok(None);
+        };
+        let mut process = process.lock().await;
+        let (extra_options, evicted) = state
+            .engine_pool
+            .lock()
+            .unwrap()
+            .adjust(process.slot, &options.extra_options)?;
+        shutdown_engines(evicted).await;
+        process
+            .set_options(EngineOptions {
+                extra_options,
+                ..options.clone()
+            })
+            .await?;
+        process.requested = options;
+        process.go(&go_mode).await?;
         return Ok(None);
     }
 
-    let (mut process, mut reader) = EngineProcess::new(path).await?;
-    process.set_options(options.clone()).await?;
-    process.go(&go_mode).await?;
-
-    let process = Arc::new(Mutex::new(process));
+    let mut extra_options = options.extra_options.clone();
+    let (process, mut reader) =
+        lease_engine(&state, path, &mut extra_options, format!("{}/{}", tab, id)).await?;
+    let started: Result<(), Error> = async {
+        let mut proc = process.lock().await;
+        proc.set_options(EngineOptions {
+            extra_options,
+            ..options.clone()
+        })
+        .await?;
+        proc.requested = options.clone();
+        proc.go(&go_mode).await
+    }
+    .await;
+    if let Err(e) = started {
+        return_engine(&state, process, reader).await;
+        return Err(e);
+    }
 
     state.engine_processes.insert(key.clone(), process.clone());
 
+    let outcome =
+        stream_best_moves(app, process.clone(), &mut reader, id.clone(), tab.clone()).await;
+
+    state
+        .engine_processes
+        .remove_if(&key, |_, p| Arc::ptr_eq(p, &process));
+    if matches!(outcome, Ok(true)) {
+        return_engine(&state, process, reader).await;
+    } else {
+        info!("Engine process finished: tab: {}, engine: {}", tab, engine);
+        let slot = process.lock().await.slot;
+        state.engine_pool.lock().unwrap().forget(slot);
+    }
+    outcome?;
+    Ok(None)
+}
+
+/// Reads a session's engine output and emits its lines until the engine
+/// exits or the session ends. Returns whether the session was ended, in which
+/// case the engine can go back to the pool.
+async fn stream_best_moves(
+    app: tauri::AppHandle,
+    process: SharedEngine,
+    reader: &mut EngineReader,
+    id: String,
+    tab: String,
+) -> Result<bool, Error> {
     let lim = RateLimiter::direct(Quota::per_second(nonzero!(5u32)));
     let mut buffered_payload: Option<BestMovesPayload> = None;
     let tick_duration = Duration::from_millis(50);
@@ -379,6 +632,7 @@ pub async fn get_best_moves(
     let min_time_between = Duration::from_millis(500);
     let max_buffer_hold = Duration::from_millis(1000);
 
+    let mut released = false;
     loop {
         match tokio::time::timeout(tick_duration, reader.next_line()).await {
             Ok(Ok(Some(line))) => {
@@ -407,21 +661,11 @@ pub async fn get_best_moves(
                                             && cur_depth >= proc.last_depth
                                             && lim.check().is_ok()
                                         {
-                                            let progress = match proc.go_mode {
-                                                GoMode::Depth(depth) => {
-                                                    (cur_depth as f64 / depth as f64) * 100.0
-                                                }
-                                                GoMode::Time(time) => {
-                                                    (proc.start.elapsed().as_millis() as f64
-                                                        / time as f64)
-                                                        * 100.0
-                                                }
-                                                GoMode::Nodes(nodes) => {
-                                                    (cur_nodes as f64 / nodes as f64) * 100.0
-                                                }
-                                                GoMode::PlayersTime(_) => 99.99,
-                                                GoMode::Infinite => 99.99,
-                                            };
+                                            let progress = proc.go_mode.progress(
+                                                cur_depth,
+                                                cur_nodes,
+                                                proc.start.elapsed(),
+                                            );
                                             let payload = BestMovesPayload {
                                                 best_lines: proc.best_moves.clone(),
                                                 engine: id.clone(),
@@ -475,8 +719,16 @@ pub async fn get_best_moves(
                     _ => {}
                 }
                 proc.base.log_engine(&line);
+                if proc.released {
+                    released = true;
+                    break;
+                }
             }
             Err(_) => {
+                if process.lock().await.released {
+                    released = true;
+                    break;
+                }
                 // Tick timeout — flush buffered payload if enough time has passed
                 if let Some(payload) = buffered_payload.clone() {
                     let mut proc = process.lock().await;
@@ -498,9 +750,28 @@ pub async fn get_best_moves(
             }
         }
     }
-    info!("Engine process finished: tab: {}, engine: {}", tab, engine);
-    state.engine_processes.remove(&key);
-    Ok(None)
+    Ok(released)
+}
+
+/// Narrates the current top line of a running `get_best_moves` session, or
+/// returns nothing when the engine has not reported a line yet.
+#[tauri::command]
+#[specta::specta]
+pub async fn narrate_best_line(
+    id: String,
+    tab: String,
+    options: EvalNarrationOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, Error> {
+    let key = (tab, id);
+    let Some(process) = state.engine_processes.get(&key).map(|p| p.clone()) else {
+        return Ok(None);
+    };
+    let process = process.lock().await;
+    Ok(process
+        .last_best_moves
+        .first()
+        .map(|line| line.narrate(&options)))
 }
 
 #[derive(Serialize, Debug, Default, Type)]
@@ -546,9 +817,6 @@ pub async fn analyze_game(
         .insert(id.clone(), cancel_flag.clone());
 
     let path = PathBuf::from(&engine);
-    let mut analysis: Vec<MoveAnalysis> = Vec::new();
-
-    let (mut proc, mut reader) = EngineProcess::new(path).await?;
 
     let fen = Fen::from_ascii(options.fen.as_bytes())?;
     let setup = fen.as_setup().clone();
@@ -585,82 +853,105 @@ pub async fn analyze_game(
 
     let mut novelty_found = false;
 
-    for (i, (_, moves, _)) in fens.iter().enumerate() {
-        if cancel_flag.load(Ordering::SeqCst) {
-            proc.kill().await?;
-            state.analysis_cancel_flags.remove(&id);
-            return Err(Error::AnalysisCancelled);
-        }
+    let mut uci_options = uci_options;
+    let (process, mut reader) =
+        lease_engine(&state, path, &mut uci_options, format!("analysis {}", id)).await?;
+    let analysis: Result<Vec<MoveAnalysis>, Error> = async {
+        let mut proc = process.lock().await;
+        let mut analysis = Vec::new();
+        for (i, (_, moves, _)) in fens.iter().enumerate() {
+            if cancel_flag.load(Ordering::SeqCst) {
+                return Err(Error::AnalysisCancelled);
+            }
 
-        update_progress(
-            &state.progress_state,
-            &app,
-            id.clone(),
-            (i as f32 / fens.len() as f32) * 100.0,
-            false,
-        )?;
-
-        let mut extra_options = uci_options.clone();
-        if !extra_options.iter().any(|x| x.name == "MultiPV") {
-            extra_options.push(EngineOption {
-                name: "MultiPV".to_string(),
-                value: "2".to_string(),
-            });
-        } else {
-            extra_options.iter_mut().for_each(|x| {
-                if x.name == "MultiPV" {
-                    x.value = "2".to_string();
-                }
-            });
-        }
+            update_progress(
+                &state.progress_state,
+                &app,
+                id.clone(),
+                (i as f32 / fens.len() as f32) * 100.0,
+                false,
+            )?;
+
+            let mut extra_options = uci_options.clone();
+            let declares_multipv = proc
+                .base
+                .option_schema()
+                .iter()
+                .any(|o| matches!(o, UciOptionConfig::Spin { name, .. } if name == "MultiPV"));
+            if declares_multipv && !extra_options.iter().any(|x| x.name == "MultiPV") {
+                extra_options.push(EngineOption {
+                    name: "MultiPV".to_string(),
+                    value: "2".to_string(),
+                });
+            } else {
+                extra_options.iter_mut().for_each(|x| {
+                    if x.name == "MultiPV" {
+                        x.value = "2".to_string();
+                    }
+                });
+            }
 
-        proc.set_options(EngineOptions {
-            fen: options.fen.clone(),
-            moves: moves.clone(),
-            extra_options,
-        })
-        .await?;
+            proc.set_options(EngineOptions {
+                fen: options.fen.clone(),
+                moves: moves.clone(),
+                extra_options,
+            })
+            .await?;
 
-        proc.go(&go_mode).await?;
-
-        let mut current_analysis = MoveAnalysis::default();
-        while let Ok(Some(line)) = reader.next_line().await {
-            match parse_one(&normalize_uci_line(&line)) {
-                UciMessage::Info(attrs) => {
-                    match parse_uci_attrs(attrs, &proc.options.fen.parse()?, moves) {
-                        Ok(best_moves) => {
-                            let multipv = best_moves.multipv;
-                            let cur_depth = best_moves.depth;
-                            if multipv as usize == proc.best_moves.len() + 1 {
-                                proc.best_moves.push(best_moves);
-                                if multipv == proc.real_multipv {
-                                    if proc.best_moves.iter().all(|x| x.depth == cur_depth)
-                                        && cur_depth >= proc.last_depth
-                                    {
-                                        current_analysis.best = proc.best_moves.clone();
-                                        proc.last_depth = cur_depth;
+            proc.go(&go_mode).await?;
+
+            let mut current_analysis = MoveAnalysis::default();
+            while let Ok(Some(line)) = reader.next_line().await {
+                match parse_one(&normalize_uci_line(&line)) {
+                    UciMessage::Info(attrs) => {
+                        match parse_uci_attrs(attrs, &proc.options.fen.parse()?, moves) {
+                            Ok(best_moves) => {
+                                let multipv = best_moves.multipv;
+                                let cur_depth = best_moves.depth;
+                                if multipv as usize == proc.best_moves.len() + 1 {
+                                    proc.best_moves.push(best_moves);
+                                    if multipv == proc.real_multipv {
+                                        if proc.best_moves.iter().all(|x| x.depth == cur_depth)
+                                            && cur_depth >= proc.last_depth
+                                        {
+                                            current_analysis.best = proc.best_moves.clone();
+                                            proc.last_depth = cur_depth;
+                                        }
+                                        assert_eq!(
+                                            proc.best_moves.len(),
+                                            proc.real_multipv as usize
+                                        );
+                                        proc.best_moves.clear();
                                     }
-                                    assert_eq!(proc.best_moves.len(), proc.real_multipv as usize);
-                                    proc.best_moves.clear();
                                 }
                             }
+                            Err(e) => match e {
+                                Error::NoMovesFound => {}
+                                _ => {
+                                    warn!("Failed to parse info line: {}, error: {:?}", line, e);
+                                }
+                            },
                         }
-                        Err(e) => match e {
-                            Error::NoMovesFound => {}
-                            _ => {
-                                warn!("Failed to parse info line: {}, error: {:?}", line, e);
-                            }
-                        },
                     }
+                    UciMessage::BestMove { .. } => {
+                        break;
+                    }
+                    _ => {}
                 }
-                UciMessage::BestMove { .. } => {
-                    break;
-                }
-                _ => {}
             }
+            analysis.push(current_analysis);
         }
-        analysis.push(current_analysis);
+        Ok(analysis)
     }
+    .await;
+    return_engine(&state, process, reader).await;
+    let mut analysis = match analysis {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            state.analysis_cancel_flags.remove(&id);
+            return Err(e);
+        }
+    };
 
     if options.reversed {
         analysis.reverse();
@@ -844,7 +1135,74 @@ pub struct EngineConfig {
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_engine_config(path: PathBuf) -> Result<EngineConfig, Error> {
+pub async fn get_engine_config(
+    path: PathBuf,
+    state: tauri::State<'_, AppState>,
+) -> Result<EngineConfig, Error> {
+    let config = read_engine_config(path.clone()).await?;
+    let schema = EngineSchema::new(&path, config.options.clone());
+    state.engine_schemas.insert(path, schema);
+    Ok(config)
+}
+
+/// Options an engine declared, with the size and modification time its
+/// binary had then, so a replaced or updated binary is asked again.
+pub struct EngineSchema {
+    stamp: Option<(SystemTime, u64)>,
+    options: Vec<UciOptionConfig>,
+}
+
+impl EngineSchema {
+    fn new(path: &Path, options: Vec<UciOptionConfig>) -> Self {
+        Self {
+            stamp: binary_stamp(path),
+            options,
+        }
+    }
+
+    fn is_current(&self, path: &Path) -> bool {
+        self.stamp.is_some() && self.stamp == binary_stamp(path)
+    }
+}
+
+fn binary_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The options an engine declares, asking it only if no session has started
+/// this build of it yet.
+pub(crate) async fn engine_schema(
+    state: &AppState,
+    path: PathBuf,
+) -> Result<Vec<UciOptionConfig>, Error> {
+    if let Some(schema) = state.engine_schemas.get(&path) {
+        if schema.is_current(&path) {
+            return Ok(schema.options.clone());
+        }
+    }
+    let config = read_engine_config(path.clone()).await?;
+    let schema = EngineSchema::new(&path, config.options.clone());
+    state.engine_schemas.insert(path, schema);
+    Ok(config.options)
+}
+
+/// Checks options against what the engine at `path` declares, returning
+/// every problem found; none means the options can be used as they are.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_engine_options(
+    path: PathBuf,
+    options: Vec<EngineOption>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<OptionProblem>, Error> {
+    let schema = engine_schema(&state, path).await?;
+    Ok(validate_options(&schema, &options)
+        .err()
+        .map_or(Vec::new(), |p| p.0))
+}
+
+async fn read_engine_config(path: PathBuf) -> Result<EngineConfig, Error> {
     let mut base = BaseEngine::spawn(path).await?;
 
     base.send("uci").await?;
@@ -0,0 +1,902 @@
+//! Cloud synthesizers (ElevenLabs, Google Cloud TTS and Grok), called from
+//! the backend so requests can be rate limited and metered. Every request
+//! a provider answers with audio is counted in a usage ledger, which can also
+//! hold a monthly character budget per provider. Failed requests are not
+//! counted; their characters only stay reserved while they are in flight.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use log::warn;
+use nonzero_ext::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::{
+    error::Error,
+    fs::{JsonFile, JsonFileState},
+    secrets::secret,
+    tts_cache::{cache_lookup, cache_store, TtsCacheKey},
+    tts_provider::{TtsOptions, TtsProvider, TtsVoice},
+    tts_servers::{track_fetch, DepCheck, TtsFetchError},
+};
+
+const REQUEST_TIMEOUT_MS: u32 = 30_000;
+
+/// Providers listed in the usage report even before their first request
+const CLOUD_PROVIDERS: [&str; 3] = ["elevenlabs", "google", "grok"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Service {
+    ElevenLabs,
+    Google,
+    Grok,
+}
+
+/// One synthesis call, owned so it can run on a tracked task.
+struct SpeechRequest {
+    base_url: String,
+    key: String,
+    text: String,
+    voice: String,
+    language: String,
+    ssml: bool,
+}
+
+/// "en-US-WaveNet-D" -> "en-US", "cmn-CN-WaveNet-B" -> "cmn-CN"
+fn google_language(voice: &str) -> String {
+    voice.splitn(3, '-').take(2).collect::<Vec<_>>().join("-")
+}
+
+/// Sends `request`, turning failures into [`TtsFetchError`]s. Errors leave
+/// out the URL, which can carry the API key.
+async fn send(
+    request: reqwest::RequestBuilder,
+    url: &str,
+) -> Result<reqwest::Response, TtsFetchError> {
+    let response = request
+        .timeout(Duration::from_millis(REQUEST_TIMEOUT_MS as u64))
+        .send()
+        .await
+        .map_err(|e| TtsFetchError::from_reqwest(e.without_url(), url, REQUEST_TIMEOUT_MS))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TtsFetchError::Status {
+            status: status.as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(response)
+}
+
+fn invalid_response(service: Service, e: impl std::fmt::Display) -> TtsFetchError {
+    TtsFetchError::Network {
+        message: format!("Invalid {:?} response: {}", service, e),
+    }
+}
+
+impl Service {
+    fn speech_request(
+        self,
+        client: &reqwest::Client,
+        r: &SpeechRequest,
+    ) -> reqwest::RequestBuilder {
+        match self {
+            Service::ElevenLabs => {
+                let mut body = json!({
+                    "text": r.text,
+                    "model_id": "eleven_turbo_v2_5",
+                    "voice_settings": {
+                        "stability": 0.5,
+                        "similarity_boost": 0.75,
+                        "style": 0.0,
+                        "use_speaker_boost": true,
+                    },
+                });
+                // Guides the accent for non-English text
+                if r.language != "en" {
+                    body["language_code"] = json!(r.language);
+                }
+                client
+                    .post(format!("{}/v1/text-to-speech/{}", r.base_url, r.voice))
+                    .header("xi-api-key", &r.key)
+                    .json(&body)
+            }
+            Service::Google => {
+                let input = if r.ssml {
+                    json!({ "ssml": r.text })
+                } else {
+                    json!({ "text": r.text })
+                };
+                client
+                    .post(format!("{}/v1/text:synthesize", r.base_url))
+                    .query(&[("key", &r.key)])
+                    .json(&json!({
+                        "input": input,
+                        "voice": {
+                            "languageCode": google_language(&r.voice),
+                            "name": r.voice,
+                        },
+                        "audioConfig": { "audioEncoding": "MP3" },
+                    }))
+            }
+            Service::Grok => client
+                .post(format!("{}/v1/tts", r.base_url))
+                .bearer_auth(&r.key)
+                .json(&json!({
+                    "text": r.text,
+                    "voice_id": r.voice,
+                    "language": r.language,
+                })),
+        }
+    }
+
+    async fn synthesize(self, r: SpeechRequest) -> Result<Vec<u8>, TtsFetchError> {
+        let client = reqwest::Client::new();
+        let response = send(self.speech_request(&client, &r), &r.base_url).await?;
+        let body = response.bytes().await.map_err(|e| {
+            TtsFetchError::from_reqwest(e.without_url(), &r.base_url, REQUEST_TIMEOUT_MS)
+        })?;
+        match self {
+            // Google wraps the MP3 in JSON as base64
+            Service::Google => {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Synthesized {
+                    audio_content: String,
+                }
+                let synthesized: Synthesized =
+                    serde_json::from_slice(&body).map_err(|e| invalid_response(self, e))?;
+                base64::engine::general_purpose::STANDARD
+                    .decode(synthesized.audio_content)
+                    .map_err(|e| invalid_response(self, e))
+            }
+            Service::ElevenLabs | Service::Grok => Ok(body.to_vec()),
+        }
+    }
+
+    async fn voices(
+        self,
+        base_url: &str,
+        key: &str,
+        language: &str,
+    ) -> Result<Vec<TtsVoice>, TtsFetchError> {
+        let client = reqwest::Client::new();
+        match self {
+            Service::ElevenLabs => {
+                #[derive(Deserialize)]
+                struct Voices {
+                    voices: Vec<Voice>,
+                }
+                #[derive(Deserialize)]
+                struct Voice {
+                    voice_id: String,
+                    name: String,
+                    #[serde(default)]
+                    labels: BTreeMap<String, String>,
+                }
+                let request = client
+                    .get(format!("{}/v1/voices", base_url))
+                    .header("xi-api-key", key);
+                let voices: Voices = send(request, base_url)
+                    .await?
+                    .json()
+                    .await
+                    .map_err(|e| invalid_response(self, e.without_url()))?;
+                Ok(voices
+                    .voices
+                    .into_iter()
+                    .map(|mut v| TtsVoice {
+                        id: v.voice_id,
+                        name: v.name,
+                        // The multilingual models speak every language
+                        language: String::new(),
+                        gender: v.labels.remove("gender"),
+                    })
+                    .collect())
+            }
+            Service::Google => {
+                #[derive(Deserialize)]
+                struct Voices {
+                    #[serde(default)]
+                    voices: Vec<Voice>,
+                }
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Voice {
+                    name: String,
+                    language_codes: Vec<String>,
+                    ssml_gender: Option<String>,
+                }
+                let request = client
+                    .get(format!("{}/v1/voices", base_url))
+                    .query(&[("key", key), ("languageCode", language)]);
+                let voices: Voices = send(request, base_url)
+                    .await?
+                    .json()
+                    .await
+                    .map_err(|e| invalid_response(self, e.without_url()))?;
+                Ok(voices
+                    .voices
+                    .into_iter()
+                    .map(|v| TtsVoice {
+                        id: v.name.clone(),
+                        name: v.name,
+                        language: v.language_codes.into_iter().next().unwrap_or_default(),
+                        gender: v.ssml_gender.map(|g| g.to_lowercase()),
+                    })
+                    .collect())
+            }
+            // xAI has no voice list endpoint
+            Service::Grok => Ok([
+                ("sal", "Sal", "neutral"),
+                ("eve", "Eve", "female"),
+                ("ara", "Ara", "female"),
+                ("rex", "Rex", "male"),
+                ("leo", "Leo", "male"),
+            ]
+            .into_iter()
+            .map(|(id, name, gender)| TtsVoice {
+                id: id.to_string(),
+                name: name.to_string(),
+                language: String::new(),
+                gender: Some(gender.to_string()),
+            })
+            .collect()),
+        }
+    }
+}
+
+/// A cloud TTS API. Requests wait for the provider's rate limiter, and
+/// synthesized audio is cached so repeated lines aren't billed twice.
+pub struct CloudTts {
+    service: Service,
+    id: &'static str,
+    name: &'static str,
+    default_url: &'static str,
+    default_voice: &'static str,
+    limiter: DefaultDirectRateLimiter,
+}
+
+impl CloudTts {
+    pub fn elevenlabs() -> Self {
+        Self {
+            service: Service::ElevenLabs,
+            id: "elevenlabs",
+            name: "ElevenLabs",
+            default_url: "https://api.elevenlabs.io",
+            // Adam
+            default_voice: "pNInz6obpgDQGcFmaJgB",
+            limiter: RateLimiter::direct(Quota::per_second(nonzero!(2u32))),
+        }
+    }
+
+    pub fn google() -> Self {
+        Self {
+            service: Service::Google,
+            id: "google",
+            name: "Google Cloud TTS",
+            default_url: "https://texttospeech.googleapis.com",
+            default_voice: "en-US-WaveNet-D",
+            limiter: RateLimiter::direct(Quota::per_second(nonzero!(10u32))),
+        }
+    }
+
+    pub fn grok() -> Self {
+        Self {
+            service: Service::Grok,
+            id: "grok",
+            name: "Grok",
+            default_url: "https://api.x.ai",
+            default_voice: "sal",
+            limiter: RateLimiter::direct(Quota::per_second(nonzero!(2u32))),
+        }
+    }
+
+    fn base_url(&self, options: &TtsOptions) -> String {
+        options
+            .server_url
+            .as_deref()
+            .unwrap_or(self.default_url)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Read from the secrets store, under `tts.<provider id>`.
+    fn api_key(&self, app: &AppHandle) -> Result<String, Error> {
+        secret(app, &format!("tts.{}", self.id))?
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| Error::Tts(format!("No {} API key", self.name)))
+    }
+
+    /// Reserves `characters` against the budget before a request is sent, so
+    /// concurrent requests can't all slip under it. Refuses requests over a
+    /// blocking budget and announces the first one over a warning budget.
+    fn reserve_budget<'a>(
+        &self,
+        app: &'a AppHandle,
+        characters: u32,
+    ) -> Result<Reservation<'a>, Error> {
+        let ledger = app.state::<UsageLedgerState>();
+        let month = current_month();
+        let (check, used, budget) = ledger.with(app, |l| {
+            let budget = l.budget(self.id).map_or(0, |b| b.monthly_characters);
+            let used = l.usage(&month, self.id).characters;
+            (l.reserve(&month, self.id, characters), used, budget)
+        });
+        let event = TtsBudgetEvent {
+            provider: self.id.to_string(),
+            used,
+            budget,
+            blocked: check == BudgetCheck::Blocked,
+        };
+        match check {
+            BudgetCheck::Within | BudgetCheck::Warn { crossing: false } => {}
+            BudgetCheck::Warn { crossing: true } => {
+                warn!("{} is over its monthly budget", self.name);
+                let _ = event.emit(app);
+            }
+            BudgetCheck::Blocked => {
+                let _ = event.emit(app);
+                return Err(Error::TtsBudgetExceeded(self.id.to_string(), used, budget));
+            }
+        }
+        Ok(Reservation {
+            app,
+            provider: self.id,
+            characters,
+            committed: false,
+        })
+    }
+}
+
+/// Characters held against a provider's budget while its request is in
+/// flight. `commit` records them as used; otherwise they are handed back
+/// when the reservation is dropped.
+struct Reservation<'a> {
+    app: &'a AppHandle,
+    provider: &'static str,
+    characters: u32,
+    committed: bool,
+}
+
+impl Reservation<'_> {
+    fn commit(mut self) {
+        self.app
+            .state::<UsageLedgerState>()
+            .commit(self.app, self.provider, self.characters);
+        self.committed = true;
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.app
+                .state::<UsageLedgerState>()
+                .with(self.app, |l| l.release(self.provider, self.characters));
+        }
+    }
+}
+
+impl TtsProvider for CloudTts {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn supports_ssml(&self) -> bool {
+        self.service == Service::Google
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        Box::pin(async move {
+            if self.service == Service::Grok {
+                return Ok(self.service.voices("", "", "").await?);
+            }
+            let key = self.api_key(app)?;
+            self.limiter.until_ready().await;
+            Ok(self
+                .service
+                .voices(&self.base_url(options), &key, options.language())
+                .await?)
+        })
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let voice = if voice.is_empty() {
+                self.default_voice
+            } else {
+                voice
+            };
+            let key = TtsCacheKey::new(self.id, voice, options.language(), text);
+            if let Some(audio) = cache_lookup(app, &key) {
+                return Ok(audio);
+            }
+
+            let characters = text.chars().count() as u32;
+            let reservation = self.reserve_budget(app, characters)?;
+            let request = SpeechRequest {
+                base_url: self.base_url(options),
+                key: self.api_key(app)?,
+                text: text.to_string(),
+                voice: voice.to_string(),
+                language: options.language().to_string(),
+                ssml: options.ssml && self.supports_ssml(),
+            };
+            self.limiter.until_ready().await;
+            let audio = track_fetch(
+                app,
+                options.request_id.clone(),
+                self.service.synthesize(request),
+            )
+            .await?;
+
+            reservation.commit();
+            cache_store(app, &key, &audio);
+            Ok(audio)
+        })
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        Box::pin(async move {
+            if let Err(e) = self.api_key(app) {
+                return DepCheck {
+                    ok: false,
+                    label: format!("No {} API key", self.name),
+                    detail: e.to_string(),
+                    fix_hint: format!("Add your {} API key in Settings > Sound", self.name),
+                };
+            }
+            let month = current_month();
+            let ledger = app.state::<UsageLedgerState>();
+            let (usage, blocked) = ledger.with(app, |l| {
+                (
+                    l.usage(&month, self.id),
+                    l.check(&month, self.id, 1) == BudgetCheck::Blocked,
+                )
+            });
+            let detail = format!("{} characters used this month", usage.characters);
+            if blocked {
+                DepCheck {
+                    ok: false,
+                    label: format!("{} monthly budget used up", self.name),
+                    detail,
+                    fix_hint: "Raise the budget in Settings > Sound".to_string(),
+                }
+            } else {
+                DepCheck {
+                    ok: true,
+                    label: format!("{} API key set", self.name),
+                    detail,
+                    fix_hint: String::new(),
+                }
+            }
+        })
+    }
+}
+
+// --- Usage ledger ---
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetAction {
+    /// Refuse requests that would go over the budget
+    Block,
+    /// Go over, but tell the user once
+    Warn,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBudget {
+    pub monthly_characters: u32,
+    pub action: BudgetAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Type)]
+pub struct Usage {
+    pub characters: u32,
+    pub requests: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetCheck {
+    Within,
+    /// Over a budget that only warns; `crossing` for the request that goes
+    /// over first
+    Warn {
+        crossing: bool,
+    },
+    Blocked,
+}
+
+/// Characters and requests per provider and month, with the budgets.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct UsageLedger {
+    budgets: BTreeMap<String, UsageBudget>,
+    /// Keyed by month ("2026-10"), then provider
+    months: BTreeMap<String, BTreeMap<String, Usage>>,
+    /// Characters of requests in flight by provider, counted against the
+    /// budget until they are committed or released
+    #[serde(skip)]
+    reserved: BTreeMap<String, u32>,
+}
+
+impl UsageLedger {
+    pub fn usage(&self, month: &str, provider: &str) -> Usage {
+        self.months
+            .get(month)
+            .and_then(|m| m.get(provider))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn budget(&self, provider: &str) -> Option<&UsageBudget> {
+        self.budgets.get(provider)
+    }
+
+    pub fn set_budget(&mut self, provider: &str, budget: Option<UsageBudget>) {
+        match budget {
+            Some(budget) => self.budgets.insert(provider.to_string(), budget),
+            None => self.budgets.remove(provider),
+        };
+    }
+
+    /// Whether `characters` more fit in the provider's budget this month.
+    pub fn check(&self, month: &str, provider: &str, characters: u32) -> BudgetCheck {
+        let Some(budget) = self.budget(provider) else {
+            return BudgetCheck::Within;
+        };
+        let used = self
+            .usage(month, provider)
+            .characters
+            .saturating_add(self.reserved.get(provider).copied().unwrap_or(0));
+        if used.saturating_add(characters) <= budget.monthly_characters {
+            return BudgetCheck::Within;
+        }
+        match budget.action {
+            BudgetAction::Block => BudgetCheck::Blocked,
+            BudgetAction::Warn => BudgetCheck::Warn {
+                crossing: used <= budget.monthly_characters,
+            },
+        }
+    }
+
+    /// Checks `characters` against the budget and, unless blocked, holds
+    /// them for a request about to be sent.
+    pub fn reserve(&mut self, month: &str, provider: &str, characters: u32) -> BudgetCheck {
+        let check = self.check(month, provider, characters);
+        if check != BudgetCheck::Blocked {
+            let reserved = self.reserved.entry(provider.to_string()).or_default();
+            *reserved = reserved.saturating_add(characters);
+        }
+        check
+    }
+
+    pub fn release(&mut self, provider: &str, characters: u32) {
+        if let Some(reserved) = self.reserved.get_mut(provider) {
+            *reserved = reserved.saturating_sub(characters);
+            if *reserved == 0 {
+                self.reserved.remove(provider);
+            }
+        }
+    }
+
+    pub fn record(&mut self, month: &str, provider: &str, characters: u32) {
+        let usage = self
+            .months
+            .entry(month.to_string())
+            .or_default()
+            .entry(provider.to_string())
+            .or_default();
+        usage.characters = usage.characters.saturating_add(characters);
+        usage.requests = usage.requests.saturating_add(1);
+    }
+
+    fn providers(&self, month: &str) -> BTreeSet<String> {
+        let mut providers: BTreeSet<String> =
+            CLOUD_PROVIDERS.iter().map(|p| p.to_string()).collect();
+        providers.extend(self.budgets.keys().cloned());
+        if let Some(usage) = self.months.get(month) {
+            providers.extend(usage.keys().cloned());
+        }
+        providers
+    }
+}
+
+fn current_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+impl JsonFile for UsageLedger {
+    const FILE: &'static str = "tts-usage.json";
+}
+
+pub type UsageLedgerState = JsonFileState<UsageLedger>;
+
+impl UsageLedgerState {
+    /// Records a reserved request as used.
+    fn commit(&self, app: &AppHandle, provider: &str, characters: u32) {
+        let month = current_month();
+        let result = self.update(app, |l| {
+            l.release(provider, characters);
+            l.record(&month, provider, characters);
+        });
+        if let Err(e) = result {
+            warn!("Failed to save TTS usage: {}", e);
+        }
+    }
+}
+
+/// Sent when a provider goes over its monthly budget, or a request is
+/// refused because of it.
+#[derive(Serialize, Debug, Clone, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsBudgetEvent {
+    pub provider: String,
+    pub used: u32,
+    pub budget: u32,
+    pub blocked: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct ProviderUsage {
+    pub provider: String,
+    pub usage: Usage,
+    pub budget: Option<UsageBudget>,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct UsageReport {
+    pub month: String,
+    pub providers: Vec<ProviderUsage>,
+}
+
+/// Usage per provider for `month` ("2026-10"), by default the current one.
+#[tauri::command]
+#[specta::specta]
+pub fn tts_usage(
+    month: Option<String>,
+    app: AppHandle,
+    state: tauri::State<'_, UsageLedgerState>,
+) -> UsageReport {
+    let month = month.unwrap_or_else(current_month);
+    let providers = state.with(&app, |l| {
+        l.providers(&month)
+            .into_iter()
+            .map(|provider| ProviderUsage {
+                usage: l.usage(&month, &provider),
+                budget: l.budget(&provider).cloned(),
+                provider,
+            })
+            .collect()
+    });
+    UsageReport { month, providers }
+}
+
+/// Sets or, with no budget, removes a provider's monthly budget.
+#[tauri::command]
+#[specta::specta]
+pub fn tts_usage_set_budget(
+    provider: String,
+    budget: Option<UsageBudget>,
+    app: AppHandle,
+    state: tauri::State<'_, UsageLedgerState>,
+) -> Result<(), Error> {
+    state.update(&app, |l| l.set_budget(&provider, budget))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Bytes,
+        http::{HeaderMap, StatusCode, Uri},
+    };
+    use std::sync::{Arc, Mutex};
+
+    struct Recorded {
+        uri: String,
+        headers: HeaderMap,
+        body: Bytes,
+    }
+
+    /// Serves `body` with `status` for every request and records them.
+    fn mock_server(status: u16, body: &'static [u8]) -> (String, Arc<Mutex<Vec<Recorded>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = axum::Router::new().fallback(
+            move |uri: Uri, headers: HeaderMap, request_body: Bytes| {
+                recorded.lock().unwrap().push(Recorded {
+                    uri: uri.to_string(),
+                    headers,
+                    body: request_body,
+                });
+                async move { (StatusCode::from_u16(status).unwrap(), body) }
+            },
+        );
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        (url, requests)
+    }
+
+    fn speech(base_url: &str, voice: &str, language: &str) -> SpeechRequest {
+        SpeechRequest {
+            base_url: base_url.to_string(),
+            key: "secret".to_string(),
+            text: "Knight to f3.".to_string(),
+            voice: voice.to_string(),
+            language: language.to_string(),
+            ssml: false,
+        }
+    }
+
+    fn json_body(recorded: &Recorded) -> serde_json::Value {
+        serde_json::from_slice(&recorded.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn elevenlabs_request() {
+        let (url, requests) = mock_server(200, b"mp3 data");
+        let audio = Service::ElevenLabs
+            .synthesize(speech(&url, "adam", "fr"))
+            .await
+            .unwrap();
+        assert_eq!(audio, b"mp3 data");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].uri, "/v1/text-to-speech/adam");
+        assert_eq!(requests[0].headers["xi-api-key"], "secret");
+        let body = json_body(&requests[0]);
+        assert_eq!(body["text"], "Knight to f3.");
+        assert_eq!(body["language_code"], "fr");
+    }
+
+    #[tokio::test]
+    async fn google_decodes_audio_content() {
+        let (url, requests) = mock_server(200, br#"{"audioContent":"bXAzIGRhdGE="}"#);
+        let mut request = speech(&url, "cmn-CN-WaveNet-B", "zh");
+        request.ssml = true;
+        let audio = Service::Google.synthesize(request).await.unwrap();
+        assert_eq!(audio, b"mp3 data");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].uri, "/v1/text:synthesize?key=secret");
+        let body = json_body(&requests[0]);
+        assert_eq!(body["voice"]["languageCode"], "cmn-CN");
+        assert_eq!(body["input"]["ssml"], "Knight to f3.");
+    }
+
+    #[tokio::test]
+    async fn grok_reports_status() {
+        let (url, requests) = mock_server(429, b"slow down");
+        let result = Service::Grok.synthesize(speech(&url, "eve", "en")).await;
+        assert!(matches!(
+            result,
+            Err(TtsFetchError::Status { status: 429, ref body }) if body == "slow down"
+        ));
+        assert_eq!(
+            requests.lock().unwrap()[0].headers["authorization"],
+            "Bearer secret"
+        );
+    }
+
+    #[test]
+    fn budgets_block_or_warn() {
+        let mut ledger = UsageLedger::default();
+        ledger.record("2026-10", "elevenlabs", 900);
+        assert_eq!(
+            ledger.check("2026-10", "elevenlabs", 200),
+            BudgetCheck::Within
+        );
+
+        ledger.set_budget(
+            "elevenlabs",
+            Some(UsageBudget {
+                monthly_characters: 1000,
+                action: BudgetAction::Block,
+            }),
+        );
+        assert_eq!(
+            ledger.check("2026-10", "elevenlabs", 100),
+            BudgetCheck::Within
+        );
+        assert_eq!(
+            ledger.check("2026-10", "elevenlabs", 101),
+            BudgetCheck::Blocked
+        );
+        // A new month starts from zero
+        assert_eq!(
+            ledger.check("2026-11", "elevenlabs", 200),
+            BudgetCheck::Within
+        );
+
+        ledger.set_budget(
+            "elevenlabs",
+            Some(UsageBudget {
+                monthly_characters: 1000,
+                action: BudgetAction::Warn,
+            }),
+        );
+        assert_eq!(
+            ledger.check("2026-10", "elevenlabs", 200),
+            BudgetCheck::Warn { crossing: true }
+        );
+        ledger.record("2026-10", "elevenlabs", 200);
+        assert_eq!(
+            ledger.check("2026-10", "elevenlabs", 1),
+            BudgetCheck::Warn { crossing: false }
+        );
+        assert_eq!(
+            ledger.usage("2026-10", "elevenlabs"),
+            Usage {
+                characters: 1100,
+                requests: 2
+            }
+        );
+    }
+
+    #[test]
+    fn reservations_count_against_the_budget() {
+        let mut ledger = UsageLedger::default();
+        ledger.set_budget(
+            "google",
+            Some(UsageBudget {
+                monthly_characters: 1000,
+                action: BudgetAction::Block,
+            }),
+        );
+        // Two requests in flight at once can't both take the last 600
+        assert_eq!(
+            ledger.reserve("2026-10", "google", 600),
+            BudgetCheck::Within
+        );
+        assert_eq!(
+            ledger.reserve("2026-10", "google", 600),
+            BudgetCheck::Blocked
+        );
+
+        // A failed request hands its characters back
+        ledger.release("google", 600);
+        assert_eq!(
+            ledger.reserve("2026-10", "google", 600),
+            BudgetCheck::Within
+        );
+        ledger.release("google", 600);
+        ledger.record("2026-10", "google", 600);
+        assert_eq!(
+            ledger.reserve("2026-10", "google", 600),
+            BudgetCheck::Blocked
+        );
+        assert!(ledger.reserved.is_empty());
+    }
+}
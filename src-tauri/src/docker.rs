@@ -0,0 +1,667 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::error::Error;
+
+#[cfg(unix)]
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+#[cfg(windows)]
+const DEFAULT_SOCKET: &str = r"\\.\pipe\docker_engine";
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+enum Body<'a> {
+    Empty,
+    Json(Value),
+    /// Streamed from disk, for image tarballs
+    File(&'a Path),
+}
+
+/// An HTTP/1.1 response read incrementally off the socket.
+struct Response {
+    status: u16,
+    reader: BufReader<Box<dyn Connection>>,
+    chunked: bool,
+    content_length: Option<usize>,
+    done: bool,
+}
+
+impl Response {
+    /// The next piece of the body, `None` once it's exhausted.
+    async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        if !self.chunked {
+            self.done = true;
+            let mut body = Vec::new();
+            match self.content_length {
+                Some(len) => {
+                    body.resize(len, 0);
+                    self.reader.read_exact(&mut body).await?;
+                }
+                None => {
+                    self.reader.read_to_end(&mut body).await?;
+                }
+            }
+            return Ok(Some(body));
+        }
+
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await?;
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| Error::Docker(format!("Bad chunk size: {:?}", line)))?;
+        if size == 0 {
+            self.done = true;
+            return Ok(None);
+        }
+        let mut chunk = vec![0; size];
+        self.reader.read_exact(&mut chunk).await?;
+        let mut crlf = [0; 2];
+        self.reader.read_exact(&mut crlf).await?;
+        Ok(Some(chunk))
+    }
+
+    async fn bytes(mut self) -> Result<Vec<u8>, Error> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn json<T: for<'de> Deserialize<'de>>(self) -> Result<T, Error> {
+        let body = self.bytes().await?;
+        serde_json::from_slice(&body).map_err(|e| Error::Docker(format!("Bad response: {}", e)))
+    }
+
+    /// Calls `f` for each line of a streamed JSON-lines body.
+    async fn for_each_line(
+        mut self,
+        mut f: impl FnMut(&[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        while let Some(chunk) = self.next_chunk().await? {
+            buf.extend_from_slice(&chunk);
+            while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buf.drain(..=pos).collect();
+                if !line.trim_ascii().is_empty() {
+                    f(line.trim_ascii())?;
+                }
+            }
+        }
+        if !buf.trim_ascii().is_empty() {
+            f(buf.trim_ascii())?;
+        }
+        Ok(())
+    }
+
+    /// Turns 4xx/5xx responses into errors carrying Docker's message.
+    async fn check(self) -> Result<Self, Error> {
+        if self.status < 400 {
+            return Ok(self);
+        }
+        let status = self.status;
+        let body = self.bytes().await?;
+        let message = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|v| v["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| String::from_utf8_lossy(&body).trim().to_string());
+        Err(Error::Docker(format!("{} ({})", message, status)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+pub struct ProgressDetail {
+    #[serde(default)]
+    pub current: u64,
+    #[serde(default)]
+    pub total: u64,
+}
+
+/// One line of `POST /images/create` output.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullEvent {
+    #[serde(default)]
+    pub status: String,
+    pub id: Option<String>,
+    pub progress_detail: Option<ProgressDetail>,
+    pub error: Option<String>,
+}
+
+/// Aggregates per-layer byte counts of a pull into one percentage.
+#[derive(Debug, Default)]
+pub struct PullProgress {
+    layers: HashMap<String, ProgressDetail>,
+}
+
+impl PullProgress {
+    pub fn update(&mut self, event: &PullEvent) {
+        let Some(id) = &event.id else { return };
+        match &event.progress_detail {
+            Some(detail) if detail.total > 0 => {
+                self.layers.insert(id.clone(), detail.clone());
+            }
+            _ if event.status == "Download complete" || event.status == "Pull complete" => {
+                if let Some(layer) = self.layers.get_mut(id) {
+                    layer.current = layer.total;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn percent(&self) -> f32 {
+        let (current, total) = self.layers.values().fold((0, 0), |(c, t), l| {
+            (c + l.current.min(l.total), t + l.total)
+        });
+        if total == 0 {
+            0.0
+        } else {
+            current as f32 / total as f32 * 100.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerInfo {
+    pub image: String,
+    pub status: String,
+    pub running: bool,
+    pub health: Option<String>,
+    /// Host ports bound to each `port/proto` of the container
+    pub port_bindings: HashMap<String, Vec<u16>>,
+}
+
+impl ContainerInfo {
+    fn from_json(v: &Value) -> Self {
+        let state = &v["State"];
+        let port_bindings = v["HostConfig"]["PortBindings"]
+            .as_object()
+            .map(|bindings| {
+                bindings
+                    .iter()
+                    .map(|(port, hosts)| {
+                        let hosts = hosts
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|h| h["HostPort"].as_str()?.parse().ok())
+                            .collect();
+                        (port.clone(), hosts)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            image: v["Config"]["Image"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            status: state["Status"].as_str().unwrap_or_default().to_string(),
+            running: state["Running"].as_bool().unwrap_or(false),
+            health: state["Health"]["Status"].as_str().map(str::to_string),
+            port_bindings,
+        }
+    }
+}
+
+/// Splits `name[:tag]` into name and tag. A `:` before the last `/` is a
+/// registry port, not a tag.
+fn split_image(image: &str) -> (&str, &str) {
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
+/// Percent-encodes a query string value.
+fn query_escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// A minimal Docker Engine API client talking to the local daemon socket
+/// (a named pipe on Windows).
+pub struct Docker {
+    socket: PathBuf,
+}
+
+impl Docker {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: socket.into(),
+        }
+    }
+
+    /// Honors `DOCKER_HOST` when it points at a local socket or pipe.
+    pub fn from_env() -> Self {
+        match std::env::var("DOCKER_HOST") {
+            Ok(host) if host.starts_with("unix://") => Self::new(&host["unix://".len()..]),
+            Ok(host) if host.starts_with("npipe://") => {
+                Self::new(host["npipe://".len()..].replace('/', "\\"))
+            }
+            _ => Self::new(DEFAULT_SOCKET),
+        }
+    }
+
+    async fn connect(&self) -> Result<Box<dyn Connection>, Error> {
+        #[cfg(unix)]
+        let conn = tokio::net::UnixStream::connect(&self.socket).await?;
+        #[cfg(windows)]
+        let conn = tokio::net::windows::named_pipe::ClientOptions::new().open(&self.socket)?;
+        Ok(Box::new(conn))
+    }
+
+    async fn request(&self, method: &str, path: &str, body: Body<'_>) -> Result<Response, Error> {
+        let mut conn = self.connect().await?;
+
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\n",
+            method, path
+        );
+        match &body {
+            Body::Empty => head.push_str("Content-Length: 0\r\n\r\n"),
+            Body::Json(value) => {
+                let json = value.to_string();
+                head.push_str(&format!(
+                    "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    json.len(),
+                    json
+                ));
+            }
+            Body::File(path) => {
+                let len = tokio::fs::metadata(path).await?.len();
+                head.push_str(&format!(
+                    "Content-Type: application/x-tar\r\nContent-Length: {}\r\n\r\n",
+                    len
+                ));
+            }
+        }
+        conn.write_all(head.as_bytes()).await?;
+        if let Body::File(path) = body {
+            let mut file = tokio::fs::File::open(path).await?;
+            tokio::io::copy(&mut file, &mut conn).await?;
+        }
+        conn.flush().await?;
+
+        let mut reader = BufReader::new(conn);
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let status = line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| Error::Docker(format!("Bad status line: {:?}", line)))?;
+
+        let mut chunked = false;
+        let mut content_length = None;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                let value = value.trim();
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+                    "content-length" => content_length = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Response {
+            status,
+            reader,
+            chunked,
+            content_length,
+            done: false,
+        })
+    }
+
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.request("GET", "/_ping", Body::Empty)
+            .await?
+            .check()
+            .await?;
+        Ok(())
+    }
+
+    /// The image id, if the image is present locally.
+    pub async fn image_id(&self, image: &str) -> Result<Option<String>, Error> {
+        let response = self
+            .request("GET", &format!("/images/{}/json", image), Body::Empty)
+            .await?;
+        if response.status == 404 {
+            return Ok(None);
+        }
+        let info: Value = response.check().await?.json().await?;
+        Ok(Some(info["Id"].as_str().unwrap_or_default().to_string()))
+    }
+
+    pub async fn pull(
+        &self,
+        image: &str,
+        mut on_event: impl FnMut(&PullEvent),
+    ) -> Result<(), Error> {
+        let (name, tag) = split_image(image);
+        let path = format!(
+            "/images/create?fromImage={}&tag={}",
+            query_escape(name),
+            query_escape(tag)
+        );
+        self.request("POST", &path, Body::Empty)
+            .await?
+            .check()
+            .await?
+            .for_each_line(|line| {
+                let event: PullEvent = serde_json::from_slice(line)
+                    .map_err(|e| Error::Docker(format!("Bad pull event: {}", e)))?;
+                if let Some(error) = &event.error {
+                    return Err(Error::Docker(error.clone()));
+                }
+                on_event(&event);
+                Ok(())
+            })
+            .await
+    }
+
+    /// Loads an image from a `docker save` tarball.
+    pub async fn load(&self, tarball: &Path) -> Result<(), Error> {
+        self.request("POST", "/images/load?quiet=1", Body::File(tarball))
+            .await?
+            .check()
+            .await?
+            .for_each_line(|line| match serde_json::from_slice::<Value>(line) {
+                Ok(v) if v["error"].is_string() => Err(Error::Docker(
+                    v["error"].as_str().unwrap_or_default().to_string(),
+                )),
+                _ => Ok(()),
+            })
+            .await
+    }
+
+    pub async fn inspect_container(&self, name: &str) -> Result<Option<ContainerInfo>, Error> {
+        let response = self
+            .request("GET", &format!("/containers/{}/json", name), Body::Empty)
+            .await?;
+        if response.status == 404 {
+            return Ok(None);
+        }
+        let info: Value = response.check().await?.json().await?;
+        Ok(Some(ContainerInfo::from_json(&info)))
+    }
+
+    /// Creates a container publishing `container_port` (e.g. "5500/tcp") on
+    /// `host_port`.
+    pub async fn create_container(
+        &self,
+        name: &str,
+        image: &str,
+        container_port: &str,
+        host_port: u16,
+    ) -> Result<(), Error> {
+        let body = json!({
+            "Image": image,
+            "ExposedPorts": { container_port: {} },
+            "HostConfig": {
+                "PortBindings": { container_port: [{ "HostPort": host_port.to_string() }] }
+            }
+        });
+        self.request(
+            "POST",
+            &format!("/containers/create?name={}", name),
+            Body::Json(body),
+        )
+        .await?
+        .check()
+        .await?;
+        Ok(())
+    }
+
+    /// Starting a running container is not an error (304).
+    pub async fn start_container(&self, name: &str) -> Result<(), Error> {
+        self.request("POST", &format!("/containers/{}/start", name), Body::Empty)
+            .await?
+            .check()
+            .await?;
+        Ok(())
+    }
+
+    /// Stopping a stopped container is not an error (304).
+    pub async fn stop_container(&self, name: &str) -> Result<(), Error> {
+        self.request("POST", &format!("/containers/{}/stop", name), Body::Empty)
+            .await?
+            .check()
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_container(&self, name: &str) -> Result<(), Error> {
+        self.request(
+            "DELETE",
+            &format!("/containers/{}?force=true", name),
+            Body::Empty,
+        )
+        .await?
+        .check()
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::UnixListener;
+
+    type Handler = fn(&str, &str, &str) -> (u16, Vec<&'static str>);
+
+    /// Serves canned responses on a temporary socket. Multi-part responses
+    /// are sent chunked, like Docker's streaming endpoints.
+    async fn fake_docker(handler: Handler) -> (tempfile::TempDir, Docker, Arc<Mutex<Vec<String>>>) {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("docker.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).await.unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).await.unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).await.unwrap();
+                let body = String::from_utf8(body).unwrap();
+
+                let mut parts = request_line.split_whitespace();
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                log.lock().unwrap().push(format!("{} {}", method, path));
+                let (status, parts) = handler(method, path, &body);
+
+                let mut stream = reader.into_inner();
+                if parts.len() == 1 {
+                    let head = format!(
+                        "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                        status,
+                        parts[0].len()
+                    );
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    stream.write_all(parts[0].as_bytes()).await.unwrap();
+                } else {
+                    let head = format!(
+                        "HTTP/1.1 {} X\r\nTransfer-Encoding: chunked\r\n\r\n",
+                        status
+                    );
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    for part in parts {
+                        let chunk = format!("{:x}\r\n{}\r\n", part.len(), part);
+                        stream.write_all(chunk.as_bytes()).await.unwrap();
+                    }
+                    stream.write_all(b"0\r\n\r\n").await.unwrap();
+                }
+            }
+        });
+        let docker = Docker::new(&socket);
+        (dir, docker, requests)
+    }
+
+    #[tokio::test]
+    async fn ping_and_images() {
+        let (_dir, docker, _) = fake_docker(|_, path, _| match path {
+            "/_ping" => (200, vec!["OK"]),
+            "/images/synesthesiam/opentts:en/json" => (200, vec![r#"{"Id":"sha256:abc"}"#]),
+            _ => (404, vec![r#"{"message":"No such image"}"#]),
+        })
+        .await;
+        docker.ping().await.unwrap();
+        assert_eq!(
+            docker.image_id("synesthesiam/opentts:en").await.unwrap(),
+            Some("sha256:abc".to_string())
+        );
+        assert_eq!(
+            docker.image_id("synesthesiam/opentts:de").await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn pull_streams_progress() {
+        let (_dir, docker, requests) = fake_docker(|_, _, _| {
+            (
+                200,
+                vec![
+                    "{\"status\":\"Pulling from synesthesiam/opentts\",\"id\":\"en\"}\n{\"status\":\"Downloading\",\"id\":\"a\",",
+                    "\"progressDetail\":{\"current\":50,\"total\":100}}\n",
+                    "{\"status\":\"Downloading\",\"id\":\"b\",\"progressDetail\":{\"current\":0,\"total\":300}}\n",
+                    "{\"status\":\"Download complete\",\"id\":\"a\"}\n",
+                ],
+            )
+        })
+        .await;
+        let mut progress = PullProgress::default();
+        let mut events = 0;
+        docker
+            .pull("synesthesiam/opentts:en", |event| {
+                progress.update(event);
+                events += 1;
+            })
+            .await
+            .unwrap();
+        assert_eq!(events, 4);
+        assert_eq!(progress.percent(), 25.0);
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            "POST /images/create?fromImage=synesthesiam%2Fopentts&tag=en"
+        );
+    }
+
+    #[test]
+    fn image_names_with_registry_ports() {
+        assert_eq!(
+            split_image("synesthesiam/opentts:en"),
+            ("synesthesiam/opentts", "en")
+        );
+        assert_eq!(
+            split_image("registry:5000/img"),
+            ("registry:5000/img", "latest")
+        );
+        assert_eq!(
+            split_image("registry:5000/img:v2"),
+            ("registry:5000/img", "v2")
+        );
+        assert_eq!(split_image("opentts"), ("opentts", "latest"));
+        assert_eq!(query_escape("registry:5000/img"), "registry%3A5000%2Fimg");
+    }
+
+    #[tokio::test]
+    async fn pull_error_line() {
+        let (_dir, docker, _) = fake_docker(|_, _, _| {
+            (
+                200,
+                vec![
+                    "{\"status\":\"Pulling\"}\n",
+                    "{\"error\":\"manifest unknown\"}\n",
+                ],
+            )
+        })
+        .await;
+        let err = docker.pull("synesthesiam/opentts:xx", |_| {}).await;
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Docker error: manifest unknown"
+        );
+    }
+
+    #[tokio::test]
+    async fn container_lifecycle() {
+        let (_dir, docker, requests) = fake_docker(|method, path, body| match (method, path) {
+            ("GET", "/containers/opentts/json") => (
+                200,
+                vec![
+                    r#"{"Config":{"Image":"synesthesiam/opentts:en"},"State":{"Status":"running","Running":true,"Health":{"Status":"healthy"}},"HostConfig":{"PortBindings":{"5500/tcp":[{"HostIp":"","HostPort":"5501"}]}}}"#,
+                ],
+            ),
+            ("POST", "/containers/create?name=opentts") => {
+                assert!(body.contains(r#""HostPort":"5500""#));
+                (201, vec![r#"{"Id":"123"}"#])
+            }
+            ("POST", "/containers/opentts/start") => (304, vec![""]),
+            ("POST", "/containers/missing/stop") => (404, vec![r#"{"message":"No such container: missing"}"#]),
+            _ => (404, vec![r#"{"message":"not found"}"#]),
+        })
+        .await;
+
+        let info = docker.inspect_container("opentts").await.unwrap().unwrap();
+        assert!(info.running);
+        assert_eq!(info.health.as_deref(), Some("healthy"));
+        assert_eq!(info.port_bindings["5500/tcp"], vec![5501]);
+        assert_eq!(docker.inspect_container("other").await.unwrap(), None);
+
+        docker
+            .create_container("opentts", "synesthesiam/opentts:en", "5500/tcp", 5500)
+            .await
+            .unwrap();
+        docker.start_container("opentts").await.unwrap();
+        assert_eq!(
+            docker
+                .stop_container("missing")
+                .await
+                .unwrap_err()
+                .to_string(),
+            "Docker error: No such container: missing (404)"
+        );
+        assert_eq!(requests.lock().unwrap().len(), 5);
+    }
+}
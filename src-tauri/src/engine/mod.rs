@@ -1,7 +1,11 @@
+mod options;
+mod pool;
 mod process;
 mod types;
 mod uci;
 
+pub use options::{default_option, is_button, validate_options, OptionProblem, OptionProblems};
+pub use pool::{EnginePool, EnginePoolConfig, EnginePoolStatus, PooledEngine};
 pub use process::{BaseEngine, EngineLog, EngineReader};
 pub use types::*;
 pub use uci::*;
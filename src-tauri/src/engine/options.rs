@@ -0,0 +1,405 @@
+use std::fmt;
+
+use serde::Serialize;
+use specta::Type;
+use vampirc_uci::UciOptionConfig;
+
+use super::types::EngineOption;
+
+/// Something wrong with one option, checked against what the engine declared
+/// in its `uci` reply.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OptionProblem {
+    /// The engine has no option by this name
+    Unknown {
+        name: String,
+        /// A declared option with a similar name
+        suggestion: Option<String>,
+    },
+    NotABoolean {
+        name: String,
+        value: String,
+    },
+    NotAnInteger {
+        name: String,
+        value: String,
+    },
+    OutOfRange {
+        name: String,
+        value: i64,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    NotAChoice {
+        name: String,
+        value: String,
+        choices: Vec<String>,
+    },
+}
+
+impl fmt::Display for OptionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionProblem::Unknown { name, suggestion } => {
+                write!(f, "unknown option \"{}\"", name)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean \"{}\"?)", suggestion)?;
+                }
+                Ok(())
+            }
+            OptionProblem::NotABoolean { name, value } => {
+                write!(f, "{}: \"{}\" is not true or false", name, value)
+            }
+            OptionProblem::NotAnInteger { name, value } => {
+                write!(f, "{}: \"{}\" is not a whole number", name, value)
+            }
+            OptionProblem::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => match (min, max) {
+                (Some(min), Some(max)) => {
+                    write!(f, "{}: {} is not between {} and {}", name, value, min, max)
+                }
+                (Some(min), None) => write!(f, "{}: {} is below {}", name, value, min),
+                (None, Some(max)) => write!(f, "{}: {} is above {}", name, value, max),
+                (None, None) => write!(f, "{}: {} is out of range", name, value),
+            },
+            OptionProblem::NotAChoice {
+                name,
+                value,
+                choices,
+            } => write!(
+                f,
+                "{}: \"{}\" is not one of {}",
+                name,
+                value,
+                choices.join(", ")
+            ),
+        }
+    }
+}
+
+/// Every problem found in one set of options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionProblems(pub Vec<OptionProblem>);
+
+impl fmt::Display for OptionProblems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, problem) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn option_name(config: &UciOptionConfig) -> &str {
+    match config {
+        UciOptionConfig::Check { name, .. }
+        | UciOptionConfig::Spin { name, .. }
+        | UciOptionConfig::Combo { name, .. }
+        | UciOptionConfig::Button { name }
+        | UciOptionConfig::String { name, .. } => name,
+    }
+}
+
+fn find_option<'a>(schema: &'a [UciOptionConfig], name: &str) -> Option<&'a UciOptionConfig> {
+    // Option names are case-insensitive in UCI
+    schema
+        .iter()
+        .find(|c| option_name(c).eq_ignore_ascii_case(name.trim()))
+}
+
+pub fn is_button(schema: &[UciOptionConfig], name: &str) -> bool {
+    matches!(
+        find_option(schema, name),
+        Some(UciOptionConfig::Button { .. })
+    )
+}
+
+/// The option `name` set back to the engine's declared default. Buttons and
+/// options without a default have nothing to go back to.
+pub fn default_option(schema: &[UciOptionConfig], name: &str) -> Option<EngineOption> {
+    let config = find_option(schema, name)?;
+    let value = match config {
+        UciOptionConfig::Check { default, .. } => default.map(|d| d.to_string()),
+        UciOptionConfig::Spin { default, .. } => default.map(|d| d.to_string()),
+        UciOptionConfig::Combo { default, .. } => default.clone(),
+        UciOptionConfig::String { default, .. } => Some(default.clone().unwrap_or_default()),
+        UciOptionConfig::Button { .. } => None,
+    }?;
+    Some(EngineOption {
+        name: option_name(config).to_string(),
+        value,
+    })
+}
+
+/// Checks `options` against the engine's declared options and coerces them
+/// to its spelling: names and combo values take the declared case, booleans
+/// become `true`/`false` and spins plain integers. Button values are ignored.
+pub fn validate_options(
+    schema: &[UciOptionConfig],
+    options: &[EngineOption],
+) -> Result<Vec<EngineOption>, OptionProblems> {
+    let mut valid = Vec::new();
+    let mut problems = Vec::new();
+    for option in options {
+        let Some(config) = find_option(schema, &option.name) else {
+            problems.push(OptionProblem::Unknown {
+                name: option.name.clone(),
+                suggestion: closest_name(schema, &option.name),
+            });
+            continue;
+        };
+        match coerce(config, &option.value) {
+            Ok(value) => valid.push(EngineOption {
+                name: option_name(config).to_string(),
+                value,
+            }),
+            Err(problem) => problems.push(problem),
+        }
+    }
+    if problems.is_empty() {
+        Ok(valid)
+    } else {
+        Err(OptionProblems(problems))
+    }
+}
+
+/// Like [`validate_options`], but options the engine doesn't declare are
+/// left out rather than rejected and their names returned. Saved settings can
+/// outlive an engine upgrade that dropped an option, which shouldn't stop the
+/// engine from running.
+pub fn validate_known_options(
+    schema: &[UciOptionConfig],
+    options: &[EngineOption],
+) -> Result<(Vec<EngineOption>, Vec<String>), OptionProblems> {
+    let (known, unknown): (Vec<_>, Vec<_>) = options
+        .iter()
+        .cloned()
+        .partition(|o| find_option(schema, &o.name).is_some());
+    let valid = validate_options(schema, &known)?;
+    Ok((valid, unknown.into_iter().map(|o| o.name).collect()))
+}
+
+fn coerce(config: &UciOptionConfig, value: &str) -> Result<String, OptionProblem> {
+    let trimmed = value.trim();
+    match config {
+        UciOptionConfig::Check { name, .. } => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "1" | "on" | "yes" => Ok("true".to_string()),
+            "false" | "0" | "off" | "no" => Ok("false".to_string()),
+            _ => Err(OptionProblem::NotABoolean {
+                name: name.clone(),
+                value: value.to_string(),
+            }),
+        },
+        UciOptionConfig::Spin { name, min, max, .. } => {
+            // Number inputs may hand over "256.0"
+            let n = trimmed.parse::<i64>().ok().or_else(|| {
+                trimmed
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|f| f.is_finite() && f.fract() == 0.0)
+                    .map(|f| f as i64)
+            });
+            let Some(n) = n else {
+                return Err(OptionProblem::NotAnInteger {
+                    name: name.clone(),
+                    value: value.to_string(),
+                });
+            };
+            if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                return Err(OptionProblem::OutOfRange {
+                    name: name.clone(),
+                    value: n,
+                    min: *min,
+                    max: *max,
+                });
+            }
+            Ok(n.to_string())
+        }
+        UciOptionConfig::Combo { name, var, .. } => var
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(trimmed))
+            .cloned()
+            .ok_or_else(|| OptionProblem::NotAChoice {
+                name: name.clone(),
+                value: value.to_string(),
+                choices: var.clone(),
+            }),
+        UciOptionConfig::Button { .. } => Ok(String::new()),
+        UciOptionConfig::String { .. } => Ok(value.to_string()),
+    }
+}
+
+/// The declared name nearest to a misspelled one, if any is close enough.
+fn closest_name(schema: &[UciOptionConfig], name: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    schema
+        .iter()
+        .map(option_name)
+        .map(|candidate| {
+            (
+                edit_distance(&name, &candidate.to_ascii_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= (name.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<UciOptionConfig> {
+        vec![
+            UciOptionConfig::Spin {
+                name: "Threads".to_string(),
+                default: Some(1),
+                min: Some(1),
+                max: Some(1024),
+            },
+            UciOptionConfig::Check {
+                name: "UCI_ShowWDL".to_string(),
+                default: Some(false),
+            },
+            UciOptionConfig::Combo {
+                name: "Style".to_string(),
+                default: Some("Normal".to_string()),
+                var: vec!["Solid".to_string(), "Normal".to_string()],
+            },
+            UciOptionConfig::Button {
+                name: "Clear Hash".to_string(),
+            },
+            UciOptionConfig::String {
+                name: "SyzygyPath".to_string(),
+                default: None,
+            },
+        ]
+    }
+
+    fn opt(name: &str, value: &str) -> EngineOption {
+        EngineOption {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn coerces_to_the_declared_spelling() {
+        let valid = validate_options(
+            &schema(),
+            &[
+                opt("threads", "8.0"),
+                opt("uci_showwdl", "on"),
+                opt("Style", "solid"),
+                opt("Clear Hash", "whatever"),
+                opt("SyzygyPath", " /tb "),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            valid,
+            vec![
+                opt("Threads", "8"),
+                opt("UCI_ShowWDL", "true"),
+                opt("Style", "Solid"),
+                opt("Clear Hash", ""),
+                opt("SyzygyPath", " /tb "),
+            ]
+        );
+        assert!(is_button(&schema(), "clear hash"));
+        assert_eq!(
+            default_option(&schema(), "threads"),
+            Some(opt("Threads", "1"))
+        );
+        assert_eq!(
+            default_option(&schema(), "SyzygyPath"),
+            Some(opt("SyzygyPath", ""))
+        );
+        assert_eq!(default_option(&schema(), "Clear Hash"), None);
+    }
+
+    #[test]
+    fn runs_skip_undeclared_options() {
+        let (valid, unknown) =
+            validate_known_options(&schema(), &[opt("Contempt", "24"), opt("threads", "2")])
+                .unwrap();
+        assert_eq!(valid, vec![opt("Threads", "2")]);
+        assert_eq!(unknown, vec!["Contempt".to_string()]);
+        assert!(validate_known_options(&schema(), &[opt("Threads", "0")]).is_err());
+    }
+
+    #[test]
+    fn lists_every_problem() {
+        let problems = validate_options(
+            &schema(),
+            &[
+                opt("Thread", "2"),
+                opt("Threads", "0"),
+                opt("UCI_ShowWDL", "maybe"),
+                opt("Style", "Wild"),
+                opt("Contempt", "x"),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            problems.0,
+            vec![
+                OptionProblem::Unknown {
+                    name: "Thread".to_string(),
+                    suggestion: Some("Threads".to_string()),
+                },
+                OptionProblem::OutOfRange {
+                    name: "Threads".to_string(),
+                    value: 0,
+                    min: Some(1),
+                    max: Some(1024),
+                },
+                OptionProblem::NotABoolean {
+                    name: "UCI_ShowWDL".to_string(),
+                    value: "maybe".to_string(),
+                },
+                OptionProblem::NotAChoice {
+                    name: "Style".to_string(),
+                    value: "Wild".to_string(),
+                    choices: vec!["Solid".to_string(), "Normal".to_string()],
+                },
+                OptionProblem::Unknown {
+                    name: "Contempt".to_string(),
+                    suggestion: None,
+                },
+            ]
+        );
+        assert_eq!(
+            problems.0[1].to_string(),
+            "Threads: 0 is not between 1 and 1024"
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::error::Error;
+
+use super::types::EngineOption;
+
+/// Threads an engine uses when no `Threads` option is given
+const DEFAULT_THREADS: u32 = 1;
+/// Hash in MB an engine uses when no `Hash` option is given (Stockfish's default)
+const DEFAULT_HASH_MB: u32 = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EnginePoolConfig {
+    /// Running engines, leased and idle together
+    pub max_engines: u32,
+    /// Sum of the `Threads` option over running engines
+    pub max_threads: u32,
+    /// Sum of the `Hash` option over running engines
+    pub max_hash_mb: u32,
+    /// Idle engines are shut down after this long
+    pub idle_timeout_secs: u32,
+}
+
+impl Default for EnginePoolConfig {
+    fn default() -> Self {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get() as u32);
+        Self {
+            max_engines: 4,
+            max_threads: cpus,
+            max_hash_mb: (crate::memory_size() / 2).max(DEFAULT_HASH_MB),
+            idle_timeout_secs: 300,
+        }
+    }
+}
+
+/// One running engine, as shown by `engine_pool_status`.
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PooledEngine {
+    pub slot: u32,
+    pub path: PathBuf,
+    pub options: Vec<EngineOption>,
+    pub threads: u32,
+    pub hash_mb: u32,
+    /// Who is using the engine, `None` while idle
+    pub holder: Option<String>,
+    pub idle_secs: Option<u32>,
+    /// Times the engine has been leased
+    pub leases: u32,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EnginePoolStatus {
+    pub config: EnginePoolConfig,
+    pub engines: Vec<PooledEngine>,
+    pub threads_in_use: u32,
+    pub hash_in_use_mb: u32,
+}
+
+/// A slot handed out by [`EnginePool::acquire`].
+pub struct Lease<P> {
+    pub slot: u32,
+    /// A warm engine to reuse; `None` means the caller spawns one
+    pub process: Option<P>,
+    /// The requested options with `Threads` and `Hash` cut down to what is left
+    pub options: Vec<EngineOption>,
+    /// Idle engines shut out to make room, for the caller to stop
+    pub evicted: Vec<P>,
+}
+
+struct Entry<P> {
+    slot: u32,
+    path: PathBuf,
+    options: Vec<EngineOption>,
+    threads: u32,
+    hash_mb: u32,
+    holder: Option<String>,
+    /// Held by the pool while idle
+    process: Option<P>,
+    since: Instant,
+    leases: u32,
+}
+
+/// Keeps count of running engines so tabs share processes instead of each
+/// starting their own. Leased engines belong to their holder; the pool keeps
+/// idle ones until they are leased again, evicted or reaped.
+pub struct EnginePool<P> {
+    config: Option<EnginePoolConfig>,
+    entries: Vec<Entry<P>>,
+    next_slot: u32,
+}
+
+impl<P> Default for EnginePool<P> {
+    fn default() -> Self {
+        Self {
+            config: None,
+            entries: Vec::new(),
+            next_slot: 0,
+        }
+    }
+}
+
+fn normalized(options: &[EngineOption]) -> Vec<EngineOption> {
+    let mut options = options.to_vec();
+    options.sort_by(|a, b| a.name.cmp(&b.name));
+    options
+}
+
+fn option_value(options: &[EngineOption], name: &str) -> Option<u32> {
+    options
+        .iter()
+        .find(|o| o.name.eq_ignore_ascii_case(name))
+        .and_then(|o| o.value.trim().parse().ok())
+}
+
+fn set_option_value(options: &mut [EngineOption], name: &str, value: u32) {
+    if let Some(o) = options
+        .iter_mut()
+        .find(|o| o.name.eq_ignore_ascii_case(name))
+    {
+        o.value = value.to_string();
+    }
+}
+
+impl<P> EnginePool<P> {
+    /// Defaults are resolved on first use, as they ask the OS for the memory size.
+    pub fn config(&mut self) -> &EnginePoolConfig {
+        self.config.get_or_insert_with(EnginePoolConfig::default)
+    }
+
+    pub fn configure(&mut self, config: EnginePoolConfig) {
+        self.config = Some(config);
+    }
+
+    /// Leases an engine for `path`. An idle engine with the same options is
+    /// preferred, then any idle engine for the path, since changing options
+    /// on a warm engine is cheaper than starting another. Otherwise a new slot
+    /// is reserved, evicting the least recently used idle engines when full.
+    pub fn acquire(
+        &mut self,
+        path: &Path,
+        options: &[EngineOption],
+        holder: String,
+        now: Instant,
+    ) -> Result<Lease<P>, Error> {
+        let requested = normalized(options);
+        let max_engines = self.config().max_engines.max(1);
+        let mut evicted = Vec::new();
+
+        let idle_for_path = |e: &&Entry<P>| e.process.is_some() && e.path == path;
+        let reuse = self
+            .entries
+            .iter()
+            .filter(idle_for_path)
+            .find(|e| e.options == requested)
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .filter(idle_for_path)
+                    .max_by_key(|e| e.since)
+            })
+            .map(|e| e.slot);
+
+        // Fail before evicting anything if leased engines leave no room
+        self.check_room(reuse)?;
+
+        let slot = match reuse {
+            Some(slot) => slot,
+            None => {
+                while self.entries.len() as u32 >= max_engines {
+                    match self.evict_lru(None) {
+                        Some(process) => evicted.push(process),
+                        None => {
+                            return Err(Error::EnginePoolFull(format!(
+                                "all {} engines are running",
+                                max_engines
+                            )))
+                        }
+                    }
+                }
+                let slot = self.next_slot;
+                self.next_slot += 1;
+                self.entries.push(Entry {
+                    slot,
+                    path: path.to_path_buf(),
+                    options: Vec::new(),
+                    threads: 0,
+                    hash_mb: 0,
+                    holder: None,
+                    process: None,
+                    since: now,
+                    leases: 0,
+                });
+                slot
+            }
+        };
+
+        let (options, more) = match self.adjust(slot, options) {
+            Ok(adjusted) => adjusted,
+            Err(e) => {
+                if reuse.is_none() {
+                    self.forget(slot);
+                }
+                return Err(e);
+            }
+        };
+        evicted.extend(more);
+        let entry = self.entry_mut(slot).expect("slot was just reserved");
+        entry.holder = Some(holder);
+        entry.leases += 1;
+        Ok(Lease {
+            slot,
+            process: entry.process.take(),
+            options,
+            evicted,
+        })
+    }
+
+    /// Fits `slot` under the thread and hash caps, evicting idle engines
+    /// first and then cutting the options down to what is left. Called again
+    /// whenever a leased engine's options change. Fails, evicting nothing,
+    /// when the other leased engines use up a cap.
+    pub fn adjust(
+        &mut self,
+        slot: u32,
+        options: &[EngineOption],
+    ) -> Result<(Vec<EngineOption>, Vec<P>), Error> {
+        self.check_room(Some(slot))?;
+        let (max_threads, max_hash_mb) = {
+            let config = self.config();
+            (config.max_threads.max(1), config.max_hash_mb.max(1))
+        };
+        let want_threads = option_value(options, "Threads")
+            .unwrap_or(DEFAULT_THREADS)
+            .max(1);
+        let want_hash = option_value(options, "Hash")
+            .unwrap_or(DEFAULT_HASH_MB)
+            .max(1);
+
+        let mut evicted = Vec::new();
+        let others = |pool: &Self| {
+            pool.entries
+                .iter()
+                .filter(|e| e.slot != slot)
+                .fold((0, 0), |(t, h), e| (t + e.threads, h + e.hash_mb))
+        };
+        loop {
+            let (threads, hash) = others(self);
+            if threads + want_threads <= max_threads && hash + want_hash <= max_hash_mb {
+                break;
+            }
+            match self.evict_lru(Some(slot)) {
+                Some(process) => evicted.push(process),
+                None => break,
+            }
+        }
+
+        let (threads, hash) = others(self);
+        // Every idle engine could be evicted, so at least one of each is left
+        let threads = want_threads.min(max_threads.saturating_sub(threads));
+        let hash = want_hash.min(max_hash_mb.saturating_sub(hash));
+        let mut fitted = options.to_vec();
+        set_option_value(&mut fitted, "Threads", threads);
+        set_option_value(&mut fitted, "Hash", hash);
+
+        if let Some(entry) = self.entry_mut(slot) {
+            entry.options = normalized(options);
+            entry.threads = threads;
+            entry.hash_mb = hash;
+        }
+        Ok((fitted, evicted))
+    }
+
+    /// Whether the leased engines other than `slot` leave at least one thread
+    /// and one MB of hash. Idle engines don't count, as they can be evicted.
+    fn check_room(&mut self, slot: Option<u32>) -> Result<(), Error> {
+        let (max_threads, max_hash_mb) = {
+            let config = self.config();
+            (config.max_threads.max(1), config.max_hash_mb.max(1))
+        };
+        let (threads, hash) = self
+            .entries
+            .iter()
+            .filter(|e| e.process.is_none() && Some(e.slot) != slot)
+            .fold((0, 0), |(t, h), e| (t + e.threads, h + e.hash_mb));
+        if threads >= max_threads {
+            return Err(Error::EnginePoolFull(format!(
+                "all {} threads are in use",
+                max_threads
+            )));
+        }
+        if hash >= max_hash_mb {
+            return Err(Error::EnginePoolFull(format!(
+                "all {} MB of hash are in use",
+                max_hash_mb
+            )));
+        }
+        Ok(())
+    }
+
+    /// Hands a leased engine back to wait for the next lease.
+    pub fn release(&mut self, slot: u32, process: P, now: Instant) {
+        if let Some(entry) = self.entry_mut(slot) {
+            entry.holder = None;
+            entry.process = Some(process);
+            entry.since = now;
+        }
+    }
+
+    /// Drops a slot whose engine exited or failed to start.
+    pub fn forget(&mut self, slot: u32) {
+        self.entries.retain(|e| e.slot != slot);
+    }
+
+    /// Takes out idle engines that have waited longer than the idle timeout.
+    pub fn reap(&mut self, now: Instant) -> Vec<P> {
+        let timeout = Duration::from_secs(self.config().idle_timeout_secs as u64);
+        self.take_idle(|e| now.saturating_duration_since(e.since) >= timeout)
+    }
+
+    /// Takes out every idle engine, e.g. on exit.
+    pub fn drain_idle(&mut self) -> Vec<P> {
+        self.take_idle(|_| true)
+    }
+
+    pub fn status(&mut self, now: Instant) -> EnginePoolStatus {
+        let config = self.config().clone();
+        let engines: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| PooledEngine {
+                slot: e.slot,
+                path: e.path.clone(),
+                options: e.options.clone(),
+                threads: e.threads,
+                hash_mb: e.hash_mb,
+                holder: e.holder.clone(),
+                idle_secs: e
+                    .process
+                    .as_ref()
+                    .map(|_| now.saturating_duration_since(e.since).as_secs() as u32),
+                leases: e.leases,
+            })
+            .collect();
+        EnginePoolStatus {
+            config,
+            threads_in_use: engines.iter().map(|e| e.threads).sum(),
+            hash_in_use_mb: engines.iter().map(|e| e.hash_mb).sum(),
+            engines,
+        }
+    }
+
+    fn entry_mut(&mut self, slot: u32) -> Option<&mut Entry<P>> {
+        self.entries.iter_mut().find(|e| e.slot == slot)
+    }
+
+    fn evict_lru(&mut self, keep: Option<u32>) -> Option<P> {
+        let i = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.process.is_some() && Some(e.slot) != keep)
+            .min_by_key(|(_, e)| e.since)
+            .map(|(i, _)| i)?;
+        self.entries.remove(i).process
+    }
+
+    fn take_idle(&mut self, mut pred: impl FnMut(&Entry<P>) -> bool) -> Vec<P> {
+        let mut taken = Vec::new();
+        let mut i = 0;
+        while i < self.entries.len() {
+            if self.entries[i].process.is_some() && pred(&self.entries[i]) {
+                taken.extend(self.entries.remove(i).process);
+            } else {
+                i += 1;
+            }
+        }
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opt(name: &str, value: u32) -> EngineOption {
+        EngineOption {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn pool(max_engines: u32, max_threads: u32, max_hash_mb: u32) -> EnginePool<&'static str> {
+        let mut pool = EnginePool::default();
+        pool.configure(EnginePoolConfig {
+            max_engines,
+            max_threads,
+            max_hash_mb,
+            idle_timeout_secs: 60,
+        });
+        pool
+    }
+
+    #[test]
+    fn reuses_idle_engines_for_the_same_path() {
+        let mut pool = pool(4, 8, 1024);
+        let now = Instant::now();
+        let sf = Path::new("/engines/sf");
+        let options = [opt("Threads", 2), opt("Hash", 256)];
+
+        let first = pool.acquire(sf, &options, "a".into(), now).unwrap();
+        assert!(first.process.is_none());
+        pool.release(first.slot, "warm", now);
+
+        let again = pool.acquire(sf, &options, "b".into(), now).unwrap();
+        assert_eq!((again.slot, again.process), (first.slot, Some("warm")));
+        pool.release(again.slot, "warm", now);
+
+        // Other options still take the warm engine rather than a new one
+        let other = pool
+            .acquire(sf, &[opt("Hash", 128)], "c".into(), now)
+            .unwrap();
+        assert_eq!(other.process, Some("warm"));
+
+        let lc0 = pool
+            .acquire(Path::new("/engines/lc0"), &[], "d".into(), now)
+            .unwrap();
+        assert!(lc0.process.is_none());
+        assert_eq!(pool.status(now).engines.len(), 2);
+    }
+
+    #[test]
+    fn caps_engines_threads_and_hash() {
+        let mut pool = pool(2, 6, 1000);
+        let now = Instant::now();
+        let sf = Path::new("/engines/sf");
+        let big = [opt("Threads", 4), opt("Hash", 600)];
+
+        let a = pool.acquire(sf, &big, "a".into(), now).unwrap();
+        assert_eq!(a.options, big);
+        let b = pool.acquire(sf, &big, "b".into(), now).unwrap();
+        assert_eq!(b.options, vec![opt("Threads", 2), opt("Hash", 400)]);
+        assert!(matches!(
+            pool.acquire(sf, &big, "c".into(), now),
+            Err(Error::EnginePoolFull(_))
+        ));
+
+        // An idle engine is evicted to make room
+        pool.release(a.slot, "idle", now);
+        let c = pool
+            .acquire(Path::new("/engines/lc0"), &big, "c".into(), now)
+            .unwrap();
+        assert_eq!(c.evicted, vec!["idle"]);
+        assert_eq!(c.options, big);
+    }
+
+    #[test]
+    fn fails_when_leased_engines_use_up_a_cap() {
+        let mut pool = pool(4, 4, 1000);
+        let now = Instant::now();
+        let sf = Path::new("/engines/sf");
+
+        let a = pool
+            .acquire(sf, &[opt("Threads", 4), opt("Hash", 100)], "a".into(), now)
+            .unwrap();
+        let err = pool
+            .acquire(sf, &[opt("Threads", 1)], "b".into(), now)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Engine pool is full: all 4 threads are in use"
+        );
+        // The failed lease holds no slot
+        assert_eq!(pool.status(now).engines.len(), 1);
+
+        let (fitted, _) = pool.adjust(a.slot, &[opt("Threads", 2)]).unwrap();
+        assert_eq!(fitted, vec![opt("Threads", 2)]);
+        let big_hash = [opt("Threads", 1), opt("Hash", 900)];
+        let b = pool.acquire(sf, &big_hash, "b".into(), now).unwrap();
+        assert_eq!(b.options, big_hash);
+        let (fitted, _) = pool
+            .adjust(a.slot, &[opt("Threads", 2), opt("Hash", 200)])
+            .unwrap();
+        assert_eq!(fitted, vec![opt("Threads", 2), opt("Hash", 100)]);
+
+        let err = pool.acquire(sf, &[], "c".into(), now).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Engine pool is full: all 1000 MB of hash are in use"
+        );
+    }
+
+    #[test]
+    fn reaps_engines_idle_past_the_timeout() {
+        let mut pool = pool(4, 8, 1024);
+        let start = Instant::now();
+        let sf = Path::new("/engines/sf");
+        let a = pool.acquire(sf, &[], "a".into(), start).unwrap();
+        let b = pool.acquire(sf, &[], "b".into(), start).unwrap();
+        pool.release(a.slot, "a", start);
+
+        assert!(pool.reap(start + Duration::from_secs(30)).is_empty());
+        assert_eq!(pool.reap(start + Duration::from_secs(60)), vec!["a"]);
+
+        let status = pool.status(start);
+        assert_eq!(status.engines.len(), 1);
+        assert_eq!(status.engines[0].slot, b.slot);
+        assert_eq!(status.engines[0].holder.as_deref(), Some("b"));
+    }
+}
@@ -1,17 +1,20 @@
 use std::{fmt::Display, path::PathBuf, process::Stdio};
 
-use log::error;
+use log::{error, warn};
 use serde::Serialize;
 use specta::Type;
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
     process::{Child, ChildStdin, ChildStdout, Command},
 };
-use vampirc_uci::UciMessage;
+use vampirc_uci::{UciMessage, UciOptionConfig};
 
 use crate::error::Error;
 
-use super::types::GoMode;
+use super::{
+    options::{is_button, validate_known_options},
+    types::{EngineOption, GoMode},
+};
 
 #[cfg(target_os = "windows")]
 pub const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -31,6 +34,8 @@ pub struct BaseEngine {
     #[allow(dead_code)]
     child: Child,
     logs: Vec<EngineLog>,
+    /// Options the engine declared during `init_uci`
+    options: Vec<UciOptionConfig>,
 }
 
 impl BaseEngine {
@@ -65,6 +70,7 @@ impl BaseEngine {
             reader: Some(reader),
             child,
             logs: Vec::new(),
+            options: Vec::new(),
         })
     }
 
@@ -88,9 +94,27 @@ impl BaseEngine {
         self.logs.push(EngineLog::Engine(line.to_string()));
     }
 
+    pub fn option_schema(&self) -> &[UciOptionConfig] {
+        &self.options
+    }
+
     pub async fn init_uci(&mut self) -> Result<(), Error> {
         self.send("uci").await?;
-        self.wait_for("uciok").await?;
+        loop {
+            let line = {
+                let reader = self.reader.as_mut().ok_or(Error::EngineDisconnected)?;
+                reader.next_line().await?
+            };
+            let Some(line) = line else {
+                return Err(Error::EngineDisconnected);
+            };
+            self.logs.push(EngineLog::Engine(line.clone()));
+            match vampirc_uci::parse_one(&line) {
+                UciMessage::Option(option) => self.options.push(option),
+                UciMessage::UciOk => break,
+                _ => {}
+            }
+        }
         self.send("isready").await?;
         self.wait_for("readyok").await?;
         Ok(())
@@ -127,6 +151,39 @@ impl BaseEngine {
         self.send(&cmd).await
     }
 
+    /// Sends one option that has been through `validate_options`; buttons
+    /// are pressed.
+    pub async fn send_option(&mut self, option: &EngineOption) -> Result<(), Error> {
+        if is_button(&self.options, &option.name) {
+            self.send(&format!("setoption name {}", option.name)).await
+        } else {
+            self.set_option(&option.name, &option.value).await
+        }
+    }
+
+    /// Validates options for a run against the engine's declared ones.
+    /// Options it doesn't declare are left out with a warning.
+    pub fn known_options(&self, options: &[EngineOption]) -> Result<Vec<EngineOption>, Error> {
+        let (valid, unknown) =
+            validate_known_options(&self.options, options).map_err(Error::InvalidEngineOptions)?;
+        if !unknown.is_empty() {
+            warn!(
+                "Ignoring options the engine does not declare: {}",
+                unknown.join(", ")
+            );
+        }
+        Ok(valid)
+    }
+
+    /// Validates options against the engine's declared ones and sends them.
+    pub async fn apply_options(&mut self, options: &[EngineOption]) -> Result<(), Error> {
+        let options = self.known_options(options)?;
+        for option in &options {
+            self.send_option(option).await?;
+        }
+        Ok(())
+    }
+
     pub async fn set_position(&mut self, fen: &str, moves: &[String]) -> Result<(), Error> {
         let cmd = if moves.is_empty() {
             format!("position fen {}", fen)
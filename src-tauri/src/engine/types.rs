@@ -1,3 +1,5 @@
+use std::{fmt::Write, time::Duration};
+
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
@@ -14,6 +16,9 @@ pub enum GoMode {
     Depth(u32),
     Time(u32),
     Nodes(u32),
+    /// Search for a mate in this many moves
+    Mate(u32),
+    Limits(GoLimits),
     Infinite,
 }
 
@@ -23,15 +28,91 @@ impl GoMode {
             GoMode::Depth(d) => format!("go depth {}", d),
             GoMode::Time(t) => format!("go movetime {}", t),
             GoMode::Nodes(n) => format!("go nodes {}", n),
-            GoMode::PlayersTime(pt) => {
-                format!(
-                    "go wtime {} btime {} winc {} binc {}",
-                    pt.white, pt.black, pt.winc, pt.binc
-                )
-            }
+            GoMode::PlayersTime(pt) => format!("go {}", pt.to_uci_args()),
+            GoMode::Mate(n) => format!("go mate {}", n),
+            GoMode::Limits(limits) => limits.to_uci_string(),
             GoMode::Infinite => "go infinite".to_string(),
         }
     }
+
+    /// Estimated progress of the search in percent. Searches that only end
+    /// on `bestmove` without a known limit (infinite, mate, clock or ponder)
+    /// stay at 99.99.
+    pub fn progress(&self, depth: u32, nodes: u32, elapsed: Duration) -> f64 {
+        let fraction = |done: f64, limit: u32| done / limit.max(1) as f64 * 100.0;
+        let elapsed = elapsed.as_millis() as f64;
+        match self {
+            GoMode::Depth(limit) => fraction(depth as f64, *limit),
+            GoMode::Time(limit) => fraction(elapsed, *limit),
+            GoMode::Nodes(limit) => fraction(nodes as f64, *limit),
+            // The search stops at whichever limit comes first
+            GoMode::Limits(limits) if !limits.ponder => [
+                limits.depth.map(|l| fraction(depth as f64, l)),
+                limits.nodes.map(|l| fraction(nodes as f64, l)),
+                limits.movetime.map(|l| fraction(elapsed, l)),
+            ]
+            .into_iter()
+            .flatten()
+            .reduce(f64::max)
+            .unwrap_or(99.99),
+            GoMode::Limits(_) | GoMode::PlayersTime(_) | GoMode::Mate(_) | GoMode::Infinite => {
+                99.99
+            }
+        }
+    }
+}
+
+/// Any mix of limits for one search; the engine stops at the first one it
+/// reaches, and searches until stopped when none is set.
+#[derive(Deserialize, Serialize, Debug, Clone, Type, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GoLimits {
+    #[specta(optional)]
+    pub depth: Option<u32>,
+    #[specta(optional)]
+    pub nodes: Option<u32>,
+    /// Milliseconds
+    #[specta(optional)]
+    pub movetime: Option<u32>,
+    #[specta(optional)]
+    pub mate: Option<u32>,
+    #[specta(optional)]
+    pub clock: Option<PlayersTime>,
+    /// Only consider these moves, in UCI notation
+    pub search_moves: Vec<String>,
+    /// Think on the opponent's time until `ponderhit` or `stop`
+    pub ponder: bool,
+}
+
+impl GoLimits {
+    pub fn to_uci_string(&self) -> String {
+        let mut cmd = "go".to_string();
+        if self.ponder {
+            cmd.push_str(" ponder");
+        }
+        if let Some(clock) = &self.clock {
+            write!(cmd, " {}", clock.to_uci_args()).unwrap();
+        }
+        let limits = [
+            ("depth", self.depth),
+            ("nodes", self.nodes),
+            ("mate", self.mate),
+            ("movetime", self.movetime),
+        ];
+        for (name, value) in limits {
+            if let Some(value) = value {
+                write!(cmd, " {} {}", name, value).unwrap();
+            }
+        }
+        if self.clock.is_none() && limits.iter().all(|(_, v)| v.is_none()) {
+            cmd.push_str(" infinite");
+        }
+        // Engines read every token after searchmoves as a move, so it goes last
+        if !self.search_moves.is_empty() {
+            write!(cmd, " searchmoves {}", self.search_moves.join(" ")).unwrap();
+        }
+        cmd
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Type, PartialEq, Eq)]
@@ -40,6 +121,10 @@ pub struct PlayersTime {
     pub black: u32,
     pub winc: u32,
     pub binc: u32,
+    /// Moves until the next time control, for classical games
+    #[serde(default)]
+    #[specta(optional)]
+    pub movestogo: Option<u32>,
 }
 
 impl PlayersTime {
@@ -49,6 +134,76 @@ impl PlayersTime {
             black,
             winc,
             binc,
+            movestogo: None,
+        }
+    }
+
+    fn to_uci_args(&self) -> String {
+        let mut args = format!(
+            "wtime {} btime {} winc {} binc {}",
+            self.white, self.black, self.winc, self.binc
+        );
+        if let Some(moves) = self.movestogo {
+            write!(args, " movestogo {}", moves).unwrap();
         }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_limits_to_uci() {
+        let limits = GoLimits {
+            depth: Some(20),
+            nodes: Some(1_000_000),
+            movetime: Some(5000),
+            search_moves: vec!["e2e4".to_string(), "d2d4".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            GoMode::Limits(limits).to_uci_string(),
+            "go depth 20 nodes 1000000 movetime 5000 searchmoves e2e4 d2d4"
+        );
+
+        let mut clock = PlayersTime::new(60000, 55000, 0, 0);
+        clock.movestogo = Some(12);
+        let ponder = GoLimits {
+            clock: Some(clock),
+            ponder: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            GoMode::Limits(ponder).to_uci_string(),
+            "go ponder wtime 60000 btime 55000 winc 0 binc 0 movestogo 12"
+        );
+        assert_eq!(
+            GoMode::Limits(GoLimits::default()).to_uci_string(),
+            "go infinite"
+        );
+        assert_eq!(GoMode::Mate(3).to_uci_string(), "go mate 3");
+    }
+
+    #[test]
+    fn progress_follows_the_nearest_limit() {
+        let limits = GoMode::Limits(GoLimits {
+            depth: Some(20),
+            movetime: Some(10000),
+            ..Default::default()
+        });
+        assert_eq!(limits.progress(5, 0, Duration::from_secs(5)), 50.0);
+        assert_eq!(limits.progress(15, 0, Duration::from_secs(1)), 75.0);
+        assert_eq!(GoMode::Mate(5).progress(30, 0, Duration::ZERO), 99.99);
+    }
+
+    #[test]
+    fn old_modes_still_deserialize() {
+        let mode: GoMode = serde_json::from_str(
+            r#"{"t":"PlayersTime","c":{"white":1,"black":2,"winc":3,"binc":4}}"#,
+        )
+        .unwrap();
+        assert_eq!(mode, GoMode::PlayersTime(PlayersTime::new(1, 2, 3, 4)));
     }
 }
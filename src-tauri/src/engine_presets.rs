@@ -0,0 +1,137 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::{
+    chess::engine_schema,
+    engine::{validate_options, EngineOption},
+    error::Error,
+    fs::{JsonFile, JsonFileState},
+    AppState,
+};
+
+/// A named set of UCI options for one engine, e.g. "Blitz" or "Deep".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct EnginePreset {
+    pub name: String,
+    pub options: Vec<EngineOption>,
+}
+
+/// Presets by engine path.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EnginePresets(BTreeMap<String, Vec<EnginePreset>>);
+
+impl EnginePresets {
+    fn list(&self, engine: &str) -> Vec<EnginePreset> {
+        self.0.get(engine).cloned().unwrap_or_default()
+    }
+
+    /// Adds the preset, replacing one of the same name.
+    fn save(&mut self, engine: &str, preset: EnginePreset) {
+        let presets = self.0.entry(engine.to_string()).or_default();
+        match presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+    }
+
+    fn delete(&mut self, engine: &str, name: &str) -> bool {
+        let Some(presets) = self.0.get_mut(engine) else {
+            return false;
+        };
+        let before = presets.len();
+        presets.retain(|p| p.name != name);
+        let deleted = presets.len() != before;
+        if presets.is_empty() {
+            self.0.remove(engine);
+        }
+        deleted
+    }
+}
+
+impl JsonFile for EnginePresets {
+    const FILE: &'static str = "engine-presets.json";
+}
+
+pub type EnginePresetsState = JsonFileState<EnginePresets>;
+
+#[tauri::command]
+#[specta::specta]
+pub fn engine_presets_list(
+    path: PathBuf,
+    app: AppHandle,
+    presets: tauri::State<'_, EnginePresetsState>,
+) -> Vec<EnginePreset> {
+    presets.with(&app, |p| p.list(&path.to_string_lossy()))
+}
+
+/// Saves a preset for the engine at `path`. The options are checked against
+/// the engine's declared options first and stored in its spelling.
+#[tauri::command]
+#[specta::specta]
+pub async fn engine_preset_save(
+    path: PathBuf,
+    name: String,
+    options: Vec<EngineOption>,
+    app: AppHandle,
+    presets: tauri::State<'_, EnginePresetsState>,
+    state: tauri::State<'_, AppState>,
+) -> Result<EnginePreset, Error> {
+    let schema = engine_schema(&state, path.clone()).await?;
+    let options = validate_options(&schema, &options).map_err(Error::InvalidEngineOptions)?;
+    let preset = EnginePreset {
+        name: name.trim().to_string(),
+        options,
+    };
+    presets.update(&app, |p| p.save(&path.to_string_lossy(), preset.clone()))?;
+    Ok(preset)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn engine_preset_delete(
+    path: PathBuf,
+    name: String,
+    app: AppHandle,
+    presets: tauri::State<'_, EnginePresetsState>,
+) -> Result<(), Error> {
+    if presets.update(&app, |p| p.delete(&path.to_string_lossy(), &name))? {
+        Ok(())
+    } else {
+        Err(Error::PresetNotFound(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, threads: &str) -> EnginePreset {
+        EnginePreset {
+            name: name.to_string(),
+            options: vec![EngineOption {
+                name: "Threads".to_string(),
+                value: threads.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn presets_are_kept_per_engine() {
+        let mut presets = EnginePresets::default();
+        presets.save("/engines/sf", preset("Blitz", "2"));
+        presets.save("/engines/sf", preset("Deep", "8"));
+        presets.save("/engines/sf", preset("Blitz", "4"));
+        presets.save("/engines/lc0", preset("Blitz", "1"));
+
+        assert_eq!(
+            presets.list("/engines/sf"),
+            vec![preset("Blitz", "4"), preset("Deep", "8")]
+        );
+        assert!(presets.delete("/engines/lc0", "Blitz"));
+        assert!(!presets.delete("/engines/lc0", "Blitz"));
+        assert!(presets.list("/engines/lc0").is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use shakmaty::Chess;
 use specta::Type;
 
+use crate::engine::OptionProblems;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -48,6 +50,15 @@ pub enum Error {
     #[error(transparent)]
     SystemTime(#[from] std::time::SystemTimeError),
 
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
+
+    #[error(transparent)]
+    AudioDecode(#[from] symphonia::core::errors::Error),
+
+    #[error(transparent)]
+    TtsFetch(#[from] crate::tts_servers::TtsFetchError),
+
     #[error("No stdin")]
     NoStdin,
 
@@ -90,8 +101,59 @@ pub enum Error {
     #[error("Engine disconnected")]
     EngineDisconnected,
 
+    #[error("Engine pool is full: {0}")]
+    EnginePoolFull(String),
+
+    #[error("Invalid engine options: {0}")]
+    InvalidEngineOptions(OptionProblems),
+
+    #[error("No option preset named {0}")]
+    PresetNotFound(String),
+
     #[error("Analysis cancelled")]
     AnalysisCancelled,
+
+    #[error("Export cancelled")]
+    ExportCancelled,
+
+    #[error("Precache cancelled")]
+    PrecacheCancelled,
+
+    #[error("TTS error: {0}")]
+    Tts(String),
+
+    #[error("Unknown TTS provider: {0}")]
+    TtsProviderNotFound(String),
+
+    #[error("TTS provider {0} does not support {1}")]
+    TtsUnsupported(String, &'static str),
+
+    #[error("Monthly budget for {0} used up ({1} of {2} characters)")]
+    TtsBudgetExceeded(String, u32, u32),
+
+    #[error("Secrets store is locked")]
+    SecretsLocked,
+
+    #[error("Secrets store: {0}")]
+    Secrets(String),
+
+    #[error("Secret {0} is only available to the backend")]
+    SecretNotReadable(String),
+
+    #[error("Invalid lexicon entry: {0}")]
+    InvalidLexiconEntry(String),
+
+    #[error("Invalid square, file or rank: {0}")]
+    InvalidSquare(String),
+
+    #[error("No legal move matches \"{0}\"")]
+    NoMatchingMove(String),
+
+    #[error("Ambiguous move, could be {0}")]
+    AmbiguousMove(String),
+
+    #[error("Docker error: {0}")]
+    Docker(String),
 }
 
 impl serde::Serialize for Error {
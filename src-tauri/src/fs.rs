@@ -2,11 +2,14 @@ use std::{
     fs::create_dir_all,
     io::Cursor,
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
 use log::info;
 use reqwest::{header::HeaderMap, Client};
+use serde::{de::DeserializeOwned, Serialize};
 use specta::Type;
+use tauri::{AppHandle, Manager};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -15,8 +18,11 @@ use futures_util::StreamExt;
 
 use crate::error::Error;
 use crate::progress::update_progress;
+use crate::secrets::bearer_token;
 use crate::AppState;
 
+/// Downloads `url` to `path`, unpacking archives. With `auth_secret` the
+/// request carries the bearer token stored under that name.
 #[tauri::command]
 #[specta::specta]
 pub async fn download_file(
@@ -25,7 +31,7 @@ pub async fn download_file(
     path: PathBuf,
     app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
-    token: Option<String>,
+    auth_secret: Option<String>,
     finalize: Option<bool>,
     total_size: Option<u32>,
 ) -> Result<(), Error> {
@@ -35,7 +41,8 @@ pub async fn download_file(
 
     let mut req = client.get(&url);
     // add Bearer if token is present
-    if let Some(token) = token {
+    if let Some(name) = auth_secret {
+        let token = bearer_token(&app, &name, &url)?;
         let mut header_map = HeaderMap::new();
         header_map.insert("Authorization", format!("Bearer {token}").parse().unwrap());
         req = req.headers(header_map);
@@ -152,3 +159,50 @@ pub async fn get_file_metadata(path: String) -> Result<FileMetadata, Error> {
         last_modified: last_modified.as_secs() as u32,
     })
 }
+
+/// Settings or records kept as one JSON file in the app data dir.
+pub trait JsonFile: Default + Serialize + DeserializeOwned {
+    const FILE: &'static str;
+}
+
+/// A [`JsonFile`], read on first use and written back after every change.
+#[derive(Default)]
+pub struct JsonFileState<T>(Mutex<Option<T>>);
+
+impl<T: JsonFile> JsonFileState<T> {
+    pub fn with<R>(&self, app: &AppHandle, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut guard = self.0.lock().unwrap();
+        f(guard.get_or_insert_with(|| Self::load(app)))
+    }
+
+    /// Applies `f` and saves the result before anyone else can change it.
+    pub fn update<R>(&self, app: &AppHandle, f: impl FnOnce(&mut T) -> R) -> Result<R, Error> {
+        let mut guard = self.0.lock().unwrap();
+        let value = guard.get_or_insert_with(|| Self::load(app));
+        let result = f(value);
+        let data = serde_json::to_vec_pretty(&*value).map_err(std::io::Error::from)?;
+        write_atomic(&app.path().app_data_dir()?.join(T::FILE), &data)?;
+        Ok(result)
+    }
+
+    fn load(app: &AppHandle) -> T {
+        app.path()
+            .app_data_dir()
+            .ok()
+            .and_then(|dir| std::fs::read(dir.join(T::FILE)).ok())
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Writes `data` to a temporary file and renames it over `path`, so a crash
+/// leaves either the old or the new contents, never a truncated file.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -15,6 +15,7 @@ use tokio::{
 use crate::{
     engine::{parse_fen_to_position, BaseEngine, EngineLog, EngineOption, GoMode, PlayersTime},
     error::Error,
+    narration::{fill, resolve_move, san_to_spoken, vocab, ChessVocab},
 };
 
 pub type GameId = String;
@@ -51,6 +52,25 @@ pub struct GameConfig {
     pub initial_fen: Option<String>,
     #[serde(default)]
     pub initial_moves: Vec<String>,
+    /// Spoken narration of the game, off when unset
+    #[serde(default)]
+    pub narration: Option<GameNarrationConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GameNarrationConfig {
+    pub lang: String,
+    /// Narrate the moves of human players too, not only the engines'
+    #[serde(default)]
+    pub human_moves: bool,
+    /// Remaining times in milliseconds at which a player is warned
+    #[serde(default = "default_clock_warnings")]
+    pub clock_warnings: Vec<u64>,
+}
+
+fn default_clock_warnings() -> Vec<u64> {
+    vec![60_000, 30_000, 10_000]
 }
 
 #[derive(Clone, Debug, Serialize, Type, PartialEq)]
@@ -87,6 +107,48 @@ pub enum DrawReason {
     Agreement,
 }
 
+impl GameResult {
+    /// Spoken result, e.g. "White wins.  On time."
+    pub fn spoken(&self, lang: &str) -> String {
+        let v = vocab(lang);
+        let (result, reason) = match self {
+            GameResult::WhiteWins { reason } => (v.white_wins, reason.spoken(v)),
+            GameResult::BlackWins { reason } => (v.black_wins, reason.spoken(v)),
+            GameResult::Draw { reason } => (v.draw, reason.spoken(v).to_string()),
+        };
+        format!("{}  {}.", result, reason)
+    }
+}
+
+impl GameEndReason {
+    fn spoken(&self, v: &ChessVocab) -> String {
+        match self {
+            GameEndReason::Checkmate => {
+                let mut chars = v.checkmate.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            GameEndReason::Timeout => v.by_timeout.to_string(),
+            GameEndReason::Resignation => v.by_resignation.to_string(),
+            GameEndReason::Abandonment => v.by_abandonment.to_string(),
+        }
+    }
+}
+
+impl DrawReason {
+    fn spoken(&self, v: &ChessVocab) -> &'static str {
+        match self {
+            DrawReason::Stalemate => v.stalemate,
+            DrawReason::InsufficientMaterial => v.insufficient_material,
+            DrawReason::ThreefoldRepetition => v.repetition,
+            DrawReason::FiftyMoveRule => v.fifty_moves,
+            DrawReason::Agreement => v.by_agreement,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GameMove {
@@ -140,12 +202,45 @@ pub struct GameOverEvent {
     pub moves: Vec<GameMove>,
 }
 
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum NarrationKind {
+    Move,
+    ClockWarning,
+    Result,
+}
+
+/// Text to speak for a game with narration enabled in its `GameConfig`.
+#[derive(Clone, Debug, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct GameNarrationEvent {
+    pub game_id: GameId,
+    pub kind: NarrationKind,
+    pub text: String,
+}
+
+/// Low-clock warning for `color` at `threshold` milliseconds, spoken in whole
+/// minutes when it is one.
+fn clock_warning(v: &ChessVocab, color: Color, threshold: u64) -> String {
+    let side = v.side(color);
+    match threshold / 60_000 {
+        1 if threshold % 60_000 == 0 => fill(v.one_minute_left, &[side]),
+        minutes if minutes > 1 && threshold % 60_000 == 0 => {
+            fill(v.minutes_left, &[side, &minutes.to_string()])
+        }
+        _ => fill(v.seconds_left, &[side, &(threshold / 1000).to_string()]),
+    }
+}
+
 struct ClockState {
     white_time: Option<u64>,
     black_time: Option<u64>,
     white_increment: u64,
     black_increment: u64,
     last_tick: Instant,
+    /// Lowest warning threshold already narrated for each side
+    white_warned: Option<u64>,
+    black_warned: Option<u64>,
 }
 
 struct GameController {
@@ -187,6 +282,8 @@ impl GameController {
                     .map(|tc| tc.increment)
                     .unwrap_or(0),
                 last_tick: Instant::now(),
+                white_warned: None,
+                black_warned: None,
             })
         } else {
             None
@@ -404,6 +501,8 @@ impl GameController {
             }
 
             clock.last_tick = Instant::now();
+            clock.white_warned = None;
+            clock.black_warned = None;
         }
 
         Ok(())
@@ -515,6 +614,83 @@ impl GameController {
         }
     }
 
+    fn narrate(&self, app: &AppHandle, kind: NarrationKind, text: String) {
+        let _ = GameNarrationEvent {
+            game_id: self.game_id.clone(),
+            kind,
+            text,
+        }
+        .emit(app);
+    }
+
+    /// Narrates a move that was just played. Human moves are only narrated
+    /// when asked for, since the player knows what they played.
+    fn narrate_move(&self, app: &AppHandle, game_move: &GameMove) {
+        let Some(narration) = &self.config.narration else {
+            return;
+        };
+        // The side that moved is the one no longer to move
+        let mover = if self.position.turn() == Color::White {
+            &self.config.black
+        } else {
+            &self.config.white
+        };
+        if matches!(mover, PlayerConfig::Human { .. }) && !narration.human_moves {
+            return;
+        }
+        if let Ok(san) = SanPlus::from_ascii(game_move.san.as_bytes()) {
+            self.narrate(
+                app,
+                NarrationKind::Move,
+                san_to_spoken(&san, &narration.lang),
+            );
+        }
+    }
+
+    fn narrate_result(&self, app: &AppHandle, result: &GameResult) {
+        if let Some(narration) = &self.config.narration {
+            self.narrate(app, NarrationKind::Result, result.spoken(&narration.lang));
+        }
+    }
+
+    /// Warns a player once for each configured threshold their clock falls
+    /// under. Thresholds passed at once, as when a game starts with little
+    /// time, give a single warning.
+    fn check_clock_warnings(
+        &mut self,
+        app: &AppHandle,
+        white_time: Option<u64>,
+        black_time: Option<u64>,
+    ) {
+        let (Some(narration), Some(clock)) = (&self.config.narration, &mut self.clock) else {
+            return;
+        };
+        let v = vocab(&narration.lang);
+        for (color, time, warned) in [
+            (Color::White, white_time, &mut clock.white_warned),
+            (Color::Black, black_time, &mut clock.black_warned),
+        ] {
+            let Some(time) = time else {
+                continue;
+            };
+            let threshold = narration
+                .clock_warnings
+                .iter()
+                .copied()
+                .filter(|&t| time <= t && warned.is_none_or(|w| t < w))
+                .min();
+            if let Some(threshold) = threshold {
+                *warned = Some(threshold);
+                let _ = GameNarrationEvent {
+                    game_id: self.game_id.clone(),
+                    kind: NarrationKind::ClockWarning,
+                    text: clock_warning(v, color, threshold),
+                }
+                .emit(app);
+            }
+        }
+    }
+
     fn reset_clock(&mut self) {
         if let Some(ref mut clock) = self.clock {
             clock.last_tick = Instant::now();
@@ -551,18 +727,14 @@ impl GameManager {
         if let PlayerConfig::Engine { path, options, .. } = &config.white {
             let mut engine = BaseEngine::spawn(PathBuf::from(path)).await?;
             engine.init_uci().await?;
-            for opt in options {
-                engine.set_option(&opt.name, &opt.value).await?;
-            }
+            engine.apply_options(options).await?;
             controller.white_engine = Some(Arc::new(Mutex::new(engine)));
         }
 
         if let PlayerConfig::Engine { path, options, .. } = &config.black {
             let mut engine = BaseEngine::spawn(PathBuf::from(path)).await?;
             engine.init_uci().await?;
-            for opt in options {
-                engine.set_option(&opt.name, &opt.value).await?;
-            }
+            engine.apply_options(options).await?;
             controller.black_engine = Some(Arc::new(Mutex::new(engine)));
         }
 
@@ -601,7 +773,8 @@ impl GameManager {
     pub async fn make_move(
         &self,
         game_id: &str,
-        uci: &str,
+        input: &str,
+        lang: &str,
         app: &AppHandle,
     ) -> Result<GameState, Error> {
         let game = self
@@ -615,17 +788,19 @@ impl GameManager {
             return Err(Error::NotHumanTurn);
         }
 
-        let game_move = controller.apply_move(uci)?;
+        let uci = resolve_move(&controller.position, input, lang)?;
+        let game_move = controller.apply_move(&uci)?;
         let (white_time, black_time) = controller.get_current_times();
 
         GameMoveEvent {
             game_id: game_id.to_string(),
             moves: controller.moves.clone(),
-            fen: game_move.fen_after,
+            fen: game_move.fen_after.clone(),
             white_time,
             black_time,
         }
         .emit(app)?;
+        controller.narrate_move(app, &game_move);
 
         if let GameStatus::Finished { result } = &controller.status {
             GameOverEvent {
@@ -634,6 +809,7 @@ impl GameManager {
                 moves: controller.moves.clone(),
             }
             .emit(app)?;
+            controller.narrate_result(app, result);
         } else if let Some(tx) = &controller.move_notify_tx {
             let _ = tx.try_send(());
         }
@@ -725,6 +901,7 @@ impl GameManager {
         };
 
         controller.end_game(result.clone());
+        controller.narrate_result(app, &result);
 
         GameOverEvent {
             game_id: game_id.to_string(),
@@ -876,6 +1053,7 @@ async fn game_loop(
                             GameResult::WhiteWins { reason: GameEndReason::Abandonment }
                         };
                         ctrl.end_game(result.clone());
+                        ctrl.narrate_result(&app, &result);
                         let _ = GameOverEvent { game_id: game_id.clone(), result, moves: ctrl.moves.clone() }.emit(&app);
                         break;
                     }
@@ -892,6 +1070,7 @@ async fn game_loop(
                             GameResult::WhiteWins { reason: GameEndReason::Abandonment }
                         };
                         ctrl.end_game(result.clone());
+                        ctrl.narrate_result(&app, &result);
                         let _ = GameOverEvent { game_id: game_id.clone(), result, moves: ctrl.moves.clone() }.emit(&app);
                         break;
                     }
@@ -917,6 +1096,7 @@ async fn game_loop(
 
                     if let Some(result) = ctrl.check_timeout() {
                         ctrl.end_game(result.clone());
+                        ctrl.narrate_result(&app, &result);
                         let _ = GameOverEvent { game_id: game_id.clone(), result, moves: ctrl.moves.clone() }.emit(&app);
                         break;
                     }
@@ -927,6 +1107,7 @@ async fn game_loop(
                         white_time,
                         black_time,
                     }.emit(&app);
+                    ctrl.check_clock_warnings(&app, white_time, black_time);
 
                     is_finished = ctrl.status != GameStatus::Playing;
                 }
@@ -1011,6 +1192,7 @@ async fn request_engine_move(
             drop(ctrl);
             let mut ctrl = controller.write().await;
             ctrl.end_game(result.clone());
+            ctrl.narrate_result(app, &result);
             let _ = GameOverEvent {
                 game_id: game_id.to_string(),
                 result,
@@ -1061,11 +1243,12 @@ async fn request_engine_move(
     GameMoveEvent {
         game_id: game_id.to_string(),
         moves: ctrl.moves.clone(),
-        fen: game_move.fen_after,
+        fen: game_move.fen_after.clone(),
         white_time,
         black_time,
     }
     .emit(app)?;
+    ctrl.narrate_move(app, &game_move);
 
     if let GameStatus::Finished { result } = &ctrl.status {
         GameOverEvent {
@@ -1074,6 +1257,7 @@ async fn request_engine_move(
             moves: ctrl.moves.clone(),
         }
         .emit(app)?;
+        ctrl.narrate_result(app, result);
     }
 
     Ok(())
@@ -1100,15 +1284,22 @@ pub async fn get_game_state(
     state.game_manager.get_game_state(&game_id).await
 }
 
+/// Plays a move given as UCI, SAN or a phrase like "knight takes f3" in the
+/// narration language `lang` (English when unset).
 #[tauri::command]
 #[specta::specta]
 pub async fn make_game_move(
     game_id: String,
-    uci: String,
+    input: String,
+    lang: Option<String>,
     app: AppHandle,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<GameState, Error> {
-    state.game_manager.make_move(&game_id, &uci, &app).await
+    let lang = lang.as_deref().unwrap_or("en");
+    state
+        .game_manager
+        .make_move(&game_id, &input, lang, &app)
+        .await
 }
 
 #[tauri::command]
@@ -1150,3 +1341,48 @@ pub async fn get_game_engine_logs(
 ) -> Result<Vec<EngineLog>, Error> {
     state.game_manager.get_engine_logs(&game_id, &color).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoken_results() {
+        let result = GameResult::WhiteWins {
+            reason: GameEndReason::Checkmate,
+        };
+        assert_eq!(result.spoken("en"), "White wins.  Checkmate.");
+        let result = GameResult::BlackWins {
+            reason: GameEndReason::Timeout,
+        };
+        assert_eq!(result.spoken("en"), "Black wins.  On time.");
+        let result = GameResult::Draw {
+            reason: DrawReason::ThreefoldRepetition,
+        };
+        assert_eq!(
+            result.spoken("de"),
+            "Remis.  Dreifache Stellungswiederholung."
+        );
+    }
+
+    #[test]
+    fn clock_warnings() {
+        let v = vocab("en");
+        assert_eq!(
+            clock_warning(v, Color::White, 60_000),
+            "White, one minute left"
+        );
+        assert_eq!(
+            clock_warning(v, Color::Black, 120_000),
+            "Black, 2 minutes left"
+        );
+        assert_eq!(
+            clock_warning(v, Color::White, 30_000),
+            "White, 30 seconds left"
+        );
+        assert_eq!(
+            clock_warning(v, Color::White, 90_000),
+            "White, 90 seconds left"
+        );
+    }
+}
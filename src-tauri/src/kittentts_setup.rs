@@ -0,0 +1,744 @@
+//! Reproducible KittenTTS installs: packages pinned by a hashed lock,
+//! optionally installed from an offline bundle, and model files checked
+//! against a SHA-256 manifest. `scripts/kittentts-lock.sh` writes the lock
+//! and the manifest into `scripts/lock/`, which ships with the app, and
+//! builds bundles.
+//!
+//! A bundle is a directory with `requirements.lock`, `models.sha256`, the
+//! wheels under `wheels/` and the model cache under `models/`.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+use crate::{
+    progress::update_progress,
+    tts_servers::{
+        create_kittentts_venv, find_kittentts_paths, install_kittentts_packages,
+        kittentts_models_dir, kittentts_resource,
+    },
+    AppState,
+};
+
+/// Resource directory holding the lock and the model manifest
+pub const LOCK_DIR: &str = "lock";
+pub const LOCK_FILE: &str = "requirements.lock";
+pub const MODEL_MANIFEST: &str = "models.sha256";
+pub const KITTENTTS_SETUP_PROGRESS_ID: &str = "kittentts_setup";
+
+/// A pinned requirement from the lock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    /// Normalized as pip compares names, e.g. "typing-extensions"
+    pub name: String,
+    pub version: String,
+    /// Environment marker, e.g. `python_version < "3.13"`
+    pub marker: Option<String>,
+    pub hashes: Vec<String>,
+}
+
+impl LockedPackage {
+    fn requirement(&self) -> String {
+        let mut line = format!("{}=={}", self.name, self.version);
+        if let Some(marker) = &self.marker {
+            line.push_str(&format!(" ; {}", marker));
+        }
+        for hash in &self.hashes {
+            line.push_str(&format!(" --hash={}", hash));
+        }
+        line
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Parses a lock as written by `pip-compile --generate-hashes`. Lines
+/// continued with a backslash are joined; comments are dropped.
+pub fn parse_lock(text: &str) -> Vec<LockedPackage> {
+    let joined = text.replace("\\\r\n", " ").replace("\\\n", " ");
+    joined
+        .lines()
+        .map(|line| match line.find(" #") {
+            Some(i) => &line[..i],
+            None => line,
+        })
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+        .filter_map(|line| {
+            let (spec, options) = line.split_at(line.find(" --").unwrap_or(line.len()));
+            let (spec, marker) = match spec.split_once(';') {
+                Some((spec, marker)) => (spec, Some(marker.trim().to_string())),
+                None => (spec, None),
+            };
+            let (name, version) = spec.trim().split_once("==")?;
+            let hashes = options
+                .split_whitespace()
+                .filter_map(|part| part.strip_prefix("--hash="))
+                .map(str::to_string)
+                .collect();
+            Some(LockedPackage {
+                name: normalize_name(name),
+                version: version.trim().to_string(),
+                marker: marker.filter(|m| !m.is_empty()),
+                hashes,
+            })
+        })
+        .collect()
+}
+
+/// A shipped lock file, from the lock directory among the app's resources.
+pub fn lock_resource(app: &tauri::AppHandle, name: &str) -> Option<PathBuf> {
+    kittentts_resource(app, &format!("{}/{}", LOCK_DIR, name))
+}
+
+/// Drops packages whose environment marker doesn't hold for `python`. The
+/// markers are evaluated by the `packaging` copy vendored in pip, which is
+/// what pip itself goes by when installing.
+fn applicable_packages(
+    python: &Path,
+    packages: Vec<LockedPackage>,
+) -> Result<Vec<LockedPackage>, String> {
+    let markers: Vec<&str> = packages
+        .iter()
+        .filter_map(|p| p.marker.as_deref())
+        .collect();
+    if markers.is_empty() {
+        return Ok(packages);
+    }
+    let output = Command::new(python)
+        .args([
+            "-c",
+            "import sys\n\
+             from pip._vendor.packaging.markers import Marker\n\
+             for m in sys.argv[1:]: print(Marker(m).evaluate())",
+        ])
+        .args(&markers)
+        .output()
+        .map_err(|e| format!("Failed to run Python: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Cannot evaluate environment markers: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut results = stdout.lines().map(|line| line.trim() == "True");
+    Ok(packages
+        .into_iter()
+        .filter(|p| p.marker.is_none() || results.next().unwrap_or(false))
+        .collect())
+}
+
+/// Parses `pip list --format=freeze` output into versions by name.
+pub fn parse_freeze(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.trim().split_once("=="))
+        .map(|(name, version)| (normalize_name(name), version.trim().to_string()))
+        .collect()
+}
+
+/// Locked packages that are missing or installed at another version.
+pub fn broken_packages(
+    locked: &[LockedPackage],
+    installed: &HashMap<String, String>,
+) -> Vec<LockedPackage> {
+    locked
+        .iter()
+        .filter(|p| installed.get(&p.name) != Some(&p.version))
+        .cloned()
+        .collect()
+}
+
+/// Parses a `sha256sum` listing into (hash, path relative to the models dir).
+pub fn parse_manifest(text: &str) -> Vec<(String, PathBuf)> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (hash, path) = line.split_once(char::is_whitespace)?;
+            // sha256sum marks binary mode with '*'
+            let path = path.trim_start().trim_start_matches('*');
+            Some((hash.to_lowercase(), PathBuf::from(path)))
+        })
+        .collect()
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Manifest entries under `dir` that are missing or have another checksum.
+pub fn verify_models(dir: &Path, manifest: &[(String, PathBuf)]) -> Vec<PathBuf> {
+    manifest
+        .iter()
+        .filter(|(hash, path)| sha256_file(&dir.join(path)).ok().as_ref() != Some(hash))
+        .map(|(_, path)| path.clone())
+        .collect()
+}
+
+/// Runs a pip install, passing each line pip prints to `on_output` as it
+/// comes, e.g. "Collecting numpy" or "Downloading ...".
+pub fn run_pip(cmd: &mut Command, on_output: &mut dyn FnMut(&str)) -> Result<(), String> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run pip: {}", e))?;
+    // Drained on its own thread so a chatty stderr can't block pip
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let errors = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let line = line.trim();
+            if !line.is_empty() {
+                on_output(line);
+            }
+        }
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run pip: {}", e))?;
+    let errors = errors.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("pip install failed: {}", errors.trim()));
+    }
+    Ok(())
+}
+
+/// Installs exactly `packages` with pip's hash checking. Dependencies are
+/// not resolved, since the lock pins all of them. With a `wheelhouse` the
+/// index is never contacted.
+pub fn install_locked(
+    python: &Path,
+    packages: &[LockedPackage],
+    wheelhouse: Option<&Path>,
+    force: bool,
+    on_output: &mut dyn FnMut(&str),
+) -> Result<(), String> {
+    let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let requirements = dir.path().join(LOCK_FILE);
+    let lines: Vec<String> = packages.iter().map(LockedPackage::requirement).collect();
+    std::fs::write(&requirements, lines.join("\n")).map_err(|e| e.to_string())?;
+
+    let mut cmd = Command::new(python);
+    cmd.args([
+        "-m",
+        "pip",
+        "install",
+        "--require-hashes",
+        "--no-deps",
+        "-r",
+    ])
+    .arg(&requirements);
+    if let Some(wheelhouse) = wheelhouse {
+        cmd.args(["--no-index", "--find-links"]).arg(wheelhouse);
+    }
+    if force {
+        cmd.arg("--force-reinstall");
+    }
+    info!(
+        "Installing {} locked packages{}",
+        packages.len(),
+        if wheelhouse.is_some() {
+            " from the wheelhouse"
+        } else {
+            ""
+        }
+    );
+    run_pip(&mut cmd, on_output)
+}
+
+fn installed_packages(python: &Path) -> Result<HashMap<String, String>, String> {
+    let output = Command::new(python)
+        .args(["-m", "pip", "list", "--format=freeze"])
+        .output()
+        .map_err(|e| format!("Failed to run pip: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pip list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_freeze(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Puts the model files into `models_dir`: copied from the bundle when
+/// there is one, else downloaded by loading the model once.
+fn fetch_models(
+    python: &Path,
+    models_dir: &Path,
+    manifest: &[(String, PathBuf)],
+    bundle: Option<&Path>,
+) -> Result<(), String> {
+    if let Some(bundle) = bundle {
+        for (_, path) in manifest {
+            let (from, to) = (bundle.join("models").join(path), models_dir.join(path));
+            if let Some(parent) = to.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::copy(&from, &to)
+                .map_err(|e| format!("Cannot copy {}: {}", from.display(), e))?;
+        }
+        return Ok(());
+    }
+
+    info!(
+        "Downloading the KittenTTS model into {}",
+        models_dir.display()
+    );
+    let output = Command::new(python)
+        .args(["-c", "from kittentts import KittenTTS; KittenTTS()"])
+        .env("HF_HOME", models_dir)
+        .output()
+        .map_err(|e| format!("Failed to run Python: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Model download failed: {}",
+            stderr.lines().last().unwrap_or_default().trim()
+        ));
+    }
+    Ok(())
+}
+
+fn python_version() -> Result<String, String> {
+    let python = if cfg!(target_os = "windows") {
+        "python"
+    } else {
+        "python3"
+    };
+    let output = Command::new(python)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .ok_or("Python 3.10+ is required but not installed")?;
+    // Python 3.3 and older print the version to stderr
+    let text = [output.stdout, output.stderr].concat();
+    let version = String::from_utf8_lossy(&text).trim().to_string();
+    let minor = version
+        .strip_prefix("Python 3.")
+        .and_then(|rest| rest.split('.').next())
+        .and_then(|minor| minor.parse::<u32>().ok());
+    match minor {
+        Some(minor) if minor >= 10 => Ok(version),
+        _ => Err(format!("Python 3.10+ is required, found {}", version)),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SetupStep {
+    Python,
+    Venv,
+    Packages,
+    Models,
+    Script,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum StepOutcome {
+    /// Already in place and verified
+    Ok,
+    Installed,
+    Repaired,
+    /// Nothing to verify against
+    Skipped,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StepReport {
+    pub step: SetupStep,
+    pub outcome: StepOutcome,
+    pub detail: String,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct KittenTtsSetupReport {
+    pub steps: Vec<StepReport>,
+}
+
+/// The step that failed, so the wizard can say exactly what went wrong.
+#[derive(Debug, Clone, thiserror::Error, Serialize, Type)]
+#[error("KittenTTS setup failed at {step:?}: {message}")]
+pub struct KittenTtsSetupError {
+    pub step: SetupStep,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct KittenTtsSetupOptions {
+    /// Verify every component and reinstall only the broken ones
+    pub repair: bool,
+    /// Offline bundle directory; nothing is downloaded when set
+    pub bundle: Option<String>,
+}
+
+fn at(step: SetupStep) -> impl Fn(String) -> KittenTtsSetupError {
+    move |message| KittenTtsSetupError { step, message }
+}
+
+fn run_setup(
+    app: &tauri::AppHandle,
+    options: &KittenTtsSetupOptions,
+    mut progress: impl FnMut(SetupStep),
+) -> Result<KittenTtsSetupReport, KittenTtsSetupError> {
+    let bundle = options.bundle.as_deref().map(Path::new);
+    let changed = if options.repair {
+        StepOutcome::Repaired
+    } else {
+        StepOutcome::Installed
+    };
+    let mut steps = Vec::new();
+    let mut report = |step, outcome, detail: String| {
+        info!("KittenTTS setup: {:?} {:?} {}", step, outcome, detail);
+        steps.push(StepReport {
+            step,
+            outcome,
+            detail,
+        });
+    };
+
+    progress(SetupStep::Python);
+    let version = python_version().map_err(at(SetupStep::Python))?;
+    report(SetupStep::Python, StepOutcome::Ok, version);
+
+    progress(SetupStep::Venv);
+    let paths = find_kittentts_paths(app);
+    let venv_works = paths.python.as_ref().is_some_and(|python| {
+        Command::new(python)
+            .args(["-c", "import sys"])
+            .output()
+            .is_ok_and(|o| o.status.success())
+    });
+    if venv_works {
+        report(
+            SetupStep::Venv,
+            StepOutcome::Ok,
+            paths.venv_dir.unwrap_or_default(),
+        );
+    } else {
+        // An interpreter that doesn't start, e.g. after a Python upgrade
+        if let Some(dir) = paths.venv_dir.filter(|_| options.repair) {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| at(SetupStep::Venv)(format!("Cannot remove {}: {}", dir, e)))?;
+        }
+        let message = create_kittentts_venv(app).map_err(at(SetupStep::Venv))?;
+        report(SetupStep::Venv, changed, message);
+    }
+    let python: PathBuf = find_kittentts_paths(app)
+        .python
+        .ok_or_else(|| at(SetupStep::Venv)("Virtual environment not found".to_string()))?
+        .into();
+
+    progress(SetupStep::Packages);
+    let lock = match bundle {
+        Some(bundle) => Some(bundle.join(LOCK_FILE)),
+        None => lock_resource(app, LOCK_FILE),
+    };
+    match lock {
+        Some(lock) => {
+            let text = std::fs::read_to_string(&lock).map_err(|e| {
+                at(SetupStep::Packages)(format!("Cannot read {}: {}", lock.display(), e))
+            })?;
+            let locked = parse_lock(&text);
+            if locked.is_empty() {
+                return Err(at(SetupStep::Packages)(format!(
+                    "{} pins no packages",
+                    lock.display()
+                )));
+            }
+            let locked = applicable_packages(&python, locked).map_err(at(SetupStep::Packages))?;
+            let installed = installed_packages(&python).map_err(at(SetupStep::Packages))?;
+            let broken = broken_packages(&locked, &installed);
+            if broken.is_empty() {
+                report(
+                    SetupStep::Packages,
+                    StepOutcome::Ok,
+                    format!("{} packages match the lock", locked.len()),
+                );
+            } else {
+                let wheelhouse = bundle.map(|b| b.join("wheels"));
+                let targets = if options.repair { &broken } else { &locked };
+                install_locked(
+                    &python,
+                    targets,
+                    wheelhouse.as_deref(),
+                    options.repair,
+                    &mut |_| {},
+                )
+                .map_err(at(SetupStep::Packages))?;
+                let installed = installed_packages(&python).map_err(at(SetupStep::Packages))?;
+                let still_broken = broken_packages(&locked, &installed);
+                if !still_broken.is_empty() {
+                    let names: Vec<String> = still_broken
+                        .iter()
+                        .map(LockedPackage::requirement)
+                        .collect();
+                    return Err(at(SetupStep::Packages)(format!(
+                        "Still not matching the lock: {}",
+                        names.join(", ")
+                    )));
+                }
+                let names: Vec<&str> = broken.iter().map(|p| p.name.as_str()).collect();
+                report(SetupStep::Packages, changed, names.join(", "));
+            }
+        }
+        None => {
+            let imports = Command::new(&python)
+                .args([
+                    "-c",
+                    "import kittentts; import flask; import soundfile; import numpy",
+                ])
+                .output()
+                .is_ok_and(|o| o.status.success());
+            if imports {
+                report(
+                    SetupStep::Packages,
+                    StepOutcome::Ok,
+                    "Unpinned, no lock shipped".to_string(),
+                );
+            } else {
+                let message = install_kittentts_packages(app, &mut |_| {})
+                    .map_err(at(SetupStep::Packages))?;
+                report(SetupStep::Packages, changed, message);
+            }
+        }
+    }
+
+    progress(SetupStep::Models);
+    let manifest = match bundle {
+        Some(bundle) => Some(bundle.join(MODEL_MANIFEST)),
+        None => lock_resource(app, MODEL_MANIFEST),
+    };
+    match manifest {
+        Some(manifest) => {
+            let text = std::fs::read_to_string(&manifest).map_err(|e| {
+                at(SetupStep::Models)(format!("Cannot read {}: {}", manifest.display(), e))
+            })?;
+            let manifest = parse_manifest(&text);
+            let models_dir = kittentts_models_dir(app)
+                .ok_or_else(|| at(SetupStep::Models)("No models directory".to_string()))?;
+            let broken = verify_models(&models_dir, &manifest);
+            if broken.is_empty() {
+                report(
+                    SetupStep::Models,
+                    StepOutcome::Ok,
+                    format!("{} files verified", manifest.len()),
+                );
+            } else {
+                if options.repair {
+                    for path in &broken {
+                        let _ = std::fs::remove_file(models_dir.join(path));
+                    }
+                }
+                fetch_models(&python, &models_dir, &manifest, bundle)
+                    .map_err(at(SetupStep::Models))?;
+                let still_broken = verify_models(&models_dir, &manifest);
+                if !still_broken.is_empty() {
+                    let names: Vec<String> = still_broken
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect();
+                    return Err(at(SetupStep::Models)(format!(
+                        "Checksum mismatch: {}",
+                        names.join(", ")
+                    )));
+                }
+                let names: Vec<String> = broken.iter().map(|p| p.display().to_string()).collect();
+                report(SetupStep::Models, changed, names.join(", "));
+            }
+        }
+        None => report(
+            SetupStep::Models,
+            StepOutcome::Skipped,
+            "No model manifest; the model downloads on first start".to_string(),
+        ),
+    }
+
+    progress(SetupStep::Script);
+    let script = find_kittentts_paths(app).script.ok_or_else(|| {
+        at(SetupStep::Script)("kittentts-server.py not found, reinstall the application".into())
+    })?;
+    report(SetupStep::Script, StepOutcome::Ok, script);
+
+    Ok(KittenTtsSetupReport { steps })
+}
+
+/// Installs or, with `repair`, verifies and fixes the KittenTTS environment
+/// step by step. Progress is reported under [`KITTENTTS_SETUP_PROGRESS_ID`].
+#[tauri::command]
+#[specta::specta]
+pub async fn kittentts_setup(
+    options: Option<KittenTtsSetupOptions>,
+    app: tauri::AppHandle,
+) -> Result<KittenTtsSetupReport, KittenTtsSetupError> {
+    use tauri::Manager;
+
+    let options = options.unwrap_or_default();
+    let task_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let state = task_app.state::<AppState>();
+        run_setup(&task_app, &options, |step| {
+            let _ = update_progress(
+                &state.progress_state,
+                &task_app,
+                KITTENTTS_SETUP_PROGRESS_ID.to_string(),
+                step as u8 as f32 * 20.0,
+                false,
+            );
+        })
+    })
+    .await
+    .unwrap_or_else(|e| Err(at(SetupStep::Python)(e.to_string())));
+
+    let state = app.state::<AppState>();
+    let _ = update_progress(
+        &state.progress_state,
+        &app,
+        KITTENTTS_SETUP_PROGRESS_ID.to_string(),
+        100.0,
+        true,
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK: &str = "\
+#
+# This file is autogenerated by pip-compile with Python 3.12
+#
+flask==3.1.3 \\
+    --hash=sha256:aaaa \\
+    --hash=sha256:bbbb
+    # via -r requirements.txt
+numpy==2.3.5 \\
+    --hash=sha256:cccc
+    # via
+    #   kittentts
+    #   soundfile
+typing_extensions==4.12.2 ; python_version < \"3.13\" \\
+    --hash=sha256:dddd
+";
+
+    #[test]
+    fn parses_hashed_lock() {
+        let locked = parse_lock(LOCK);
+        assert_eq!(locked.len(), 3);
+        assert_eq!(
+            locked[0].requirement(),
+            "flask==3.1.3 --hash=sha256:aaaa --hash=sha256:bbbb"
+        );
+        assert_eq!(locked[0].marker, None);
+        assert_eq!(locked[2].name, "typing-extensions");
+        assert_eq!(locked[2].version, "4.12.2");
+        assert_eq!(
+            locked[2].marker.as_deref(),
+            Some("python_version < \"3.13\"")
+        );
+        assert_eq!(locked[2].hashes, vec!["sha256:dddd"]);
+        // pip skips the package itself where the marker doesn't hold
+        assert_eq!(
+            locked[2].requirement(),
+            "typing-extensions==4.12.2 ; python_version < \"3.13\" --hash=sha256:dddd"
+        );
+    }
+
+    #[test]
+    fn finds_packages_off_the_lock() {
+        let locked = parse_lock(LOCK);
+        let installed = parse_freeze("Flask==3.1.3\nnumpy==2.2.0\npip==24.0\n");
+        let broken: Vec<String> = broken_packages(&locked, &installed)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(broken, vec!["numpy", "typing-extensions"]);
+    }
+
+    #[test]
+    fn copies_bundled_models_with_refs() {
+        let bundle = tempfile::tempdir().unwrap();
+        let repo = bundle
+            .path()
+            .join("models/hub/models--KittenML--kitten-tts");
+        std::fs::create_dir_all(repo.join("refs")).unwrap();
+        std::fs::create_dir_all(repo.join("snapshots/abc")).unwrap();
+        std::fs::write(repo.join("refs/main"), b"abc").unwrap();
+        std::fs::write(repo.join("snapshots/abc/model.onnx"), b"weights").unwrap();
+
+        let manifest = vec![
+            (
+                String::new(),
+                PathBuf::from("hub/models--KittenML--kitten-tts/refs/main"),
+            ),
+            (
+                String::new(),
+                PathBuf::from("hub/models--KittenML--kitten-tts/snapshots/abc/model.onnx"),
+            ),
+        ];
+        let models = tempfile::tempdir().unwrap();
+        fetch_models(
+            Path::new("python3"),
+            models.path(),
+            &manifest,
+            Some(bundle.path()),
+        )
+        .unwrap();
+        // Offline, the loader finds the snapshot through refs/main
+        assert_eq!(
+            std::fs::read(models.path().join(&manifest[0].1)).unwrap(),
+            b"abc"
+        );
+        assert!(models.path().join(&manifest[1].1).exists());
+    }
+
+    #[test]
+    fn verifies_model_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("hub/snapshots/main");
+        std::fs::create_dir_all(&snapshot).unwrap();
+        std::fs::write(snapshot.join("config.json"), b"{}").unwrap();
+        std::fs::write(snapshot.join("model.onnx"), b"weights").unwrap();
+
+        let manifest = parse_manifest(
+            "# sha256sum output\n\
+             44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a  hub/snapshots/main/config.json\n\
+             0000000000000000000000000000000000000000000000000000000000000000 *hub/snapshots/main/model.onnx\n\
+             44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a  hub/snapshots/main/missing.bin\n",
+        );
+        assert_eq!(manifest.len(), 3);
+        assert_eq!(
+            verify_models(dir.path(), &manifest),
+            vec![
+                PathBuf::from("hub/snapshots/main/model.onnx"),
+                PathBuf::from("hub/snapshots/main/missing.bin")
+            ]
+        );
+    }
+}
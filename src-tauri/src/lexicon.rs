@@ -0,0 +1,544 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use futures_util::future::BoxFuture;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    error::Error,
+    tts_provider::{TtsOptions, TtsProvider, TtsVoice},
+    tts_servers::DepCheck,
+};
+
+/// One pronunciation fix. Plain patterns match whole words and phrases
+/// case-insensitively; regex patterns are used as written and may refer to
+/// capture groups (`$1`) in the replacement.
+#[derive(Serialize, Deserialize, Debug, Clone, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LexiconEntry {
+    pub pattern: String,
+    #[serde(default)]
+    pub regex: bool,
+    /// Respelling spoken by every provider, e.g. "nepo-mnyash-chee"
+    pub replacement: String,
+    /// IPA pronunciation for providers that accept SSML phonemes
+    pub ipa: Option<String>,
+}
+
+impl LexiconEntry {
+    fn compile(&self) -> Result<Regex, Error> {
+        if self.pattern.trim().is_empty() {
+            return Err(Error::InvalidLexiconEntry("empty pattern".to_string()));
+        }
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            let phrase = self.pattern.trim();
+            // \b only works next to word characters, so "O-O" or "!?" still match
+            let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            let words: Vec<String> = phrase.split_whitespace().map(regex::escape).collect();
+            format!(
+                "(?i){}{}{}",
+                if word(phrase.chars().next()) {
+                    r"\b"
+                } else {
+                    ""
+                },
+                words.join(r"\s+"),
+                if word(phrase.chars().last()) {
+                    r"\b"
+                } else {
+                    ""
+                },
+            )
+        };
+        Regex::new(&pattern)
+            .map_err(|e| Error::InvalidLexiconEntry(format!("{}: {}", self.pattern, e)))
+    }
+}
+
+/// A piece of text after the lexicon ran over it. Replaced text is final,
+/// so one entry's output is never rewritten by a later one.
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Replaced(String),
+    Phoneme {
+        original: String,
+        replacement: String,
+        ipa: String,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Lexicon {
+    entries: Vec<(LexiconEntry, Regex)>,
+}
+
+/// Text ready for a provider. `ssml` is set when it uses phoneme markup.
+#[derive(Serialize, Debug, Clone, Type, PartialEq)]
+pub struct Pronounced {
+    pub text: String,
+    pub ssml: bool,
+}
+
+impl Lexicon {
+    pub fn new(entries: &[LexiconEntry]) -> Result<Self, Error> {
+        let entries = entries
+            .iter()
+            .map(|e| Ok((e.clone(), e.compile()?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { entries })
+    }
+
+    fn pieces(&self, text: &str, phonemes: bool) -> Vec<Piece> {
+        let mut pieces = vec![Piece::Text(text.to_string())];
+        for (entry, regex) in &self.entries {
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| match piece {
+                    Piece::Text(text) => split_matches(&text, entry, regex, phonemes),
+                    other => vec![other],
+                })
+                .collect();
+        }
+        pieces
+    }
+
+    /// Applies every entry in order. With `phonemes`, entries that have an
+    /// IPA hint become SSML `<phoneme>` tags and the whole text is returned
+    /// as an SSML document.
+    pub fn apply(&self, text: &str, phonemes: bool) -> Pronounced {
+        let pieces = self.pieces(text, phonemes);
+        let ssml = pieces.iter().any(|p| matches!(p, Piece::Phoneme { .. }));
+        let mut out = String::new();
+        if ssml {
+            out.push_str("<speak>");
+        }
+        for piece in &pieces {
+            match piece {
+                Piece::Text(text) | Piece::Replaced(text) if ssml => {
+                    out.push_str(&escape_xml(text))
+                }
+                Piece::Text(text) | Piece::Replaced(text) => out.push_str(text),
+                Piece::Phoneme { original, ipa, .. } if ssml => out.push_str(&format!(
+                    r#"<phoneme alphabet="ipa" ph="{}">{}</phoneme>"#,
+                    escape_xml(ipa),
+                    escape_xml(original)
+                )),
+                Piece::Phoneme { replacement, .. } => out.push_str(replacement),
+            }
+        }
+        if ssml {
+            out.push_str("</speak>");
+        }
+        Pronounced { text: out, ssml }
+    }
+
+    /// Patterns of the entries that matched `text`.
+    pub fn matches(&self, text: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(_, regex)| regex.is_match(text))
+            .map(|(entry, _)| entry.pattern.clone())
+            .collect()
+    }
+}
+
+fn split_matches(text: &str, entry: &LexiconEntry, regex: &Regex, phonemes: bool) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut last = 0;
+    for caps in regex.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        if m.as_str().is_empty() {
+            continue;
+        }
+        if m.start() > last {
+            pieces.push(Piece::Text(text[last..m.start()].to_string()));
+        }
+        let replacement = expand(&caps, &entry.replacement);
+        pieces.push(match (&entry.ipa, phonemes) {
+            (Some(ipa), true) => Piece::Phoneme {
+                original: m.as_str().to_string(),
+                replacement,
+                ipa: ipa.clone(),
+            },
+            _ => Piece::Replaced(replacement),
+        });
+        last = m.end();
+    }
+    if last < text.len() {
+        pieces.push(Piece::Text(text[last..].to_string()));
+    }
+    pieces
+}
+
+fn expand(caps: &Captures, replacement: &str) -> String {
+    let mut out = String::new();
+    caps.expand(replacement, &mut out);
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Entries as stored on disk, in `lexicon/<lang>.toml` or
+/// `lexicon/<lang>.json` under the app data dir.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct LexiconFile {
+    #[serde(default)]
+    entries: Vec<LexiconEntry>,
+}
+
+fn base_language(lang: &str) -> String {
+    lang.split(['-', '_'])
+        .next()
+        .unwrap_or(lang)
+        .to_ascii_lowercase()
+}
+
+/// The existing lexicon file for `lang`, TOML taking precedence, or the
+/// JSON path a new one is written to.
+fn lexicon_path(dir: &Path, lang: &str) -> Result<PathBuf, Error> {
+    let lang = base_language(lang);
+    if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(Error::InvalidLexiconEntry(format!(
+            "invalid language: {}",
+            lang
+        )));
+    }
+    let toml = dir.join(format!("{}.toml", lang));
+    if toml.exists() {
+        Ok(toml)
+    } else {
+        Ok(dir.join(format!("{}.json", lang)))
+    }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<LexiconEntry>, Error> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let file: LexiconFile = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str(&text).map_err(|e| Error::InvalidLexiconEntry(e.to_string()))?
+    } else {
+        serde_json::from_str(&text).map_err(|e| Error::InvalidLexiconEntry(e.to_string()))?
+    };
+    Ok(file.entries)
+}
+
+fn write_entries(path: &Path, entries: Vec<LexiconEntry>) -> Result<(), Error> {
+    let file = LexiconFile { entries };
+    let text = if path.extension().is_some_and(|e| e == "toml") {
+        toml::to_string_pretty(&file).map_err(|e| Error::InvalidLexiconEntry(e.to_string()))?
+    } else {
+        serde_json::to_string_pretty(&file)
+            .map_err(|e| Error::InvalidLexiconEntry(e.to_string()))?
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+/// Compiled lexicons by language, reloaded after edits.
+#[derive(Default)]
+pub struct LexiconState(Mutex<HashMap<String, Arc<Lexicon>>>);
+
+impl LexiconState {
+    fn dir(app: &AppHandle) -> Result<PathBuf, Error> {
+        Ok(app.path().app_data_dir()?.join("lexicon"))
+    }
+
+    pub fn get(&self, app: &AppHandle, lang: &str) -> Result<Arc<Lexicon>, Error> {
+        let lang = base_language(lang);
+        if let Some(lexicon) = self.0.lock().unwrap().get(&lang) {
+            return Ok(lexicon.clone());
+        }
+        let entries = read_entries(&lexicon_path(&Self::dir(app)?, &lang)?)?;
+        let lexicon = Arc::new(Lexicon::new(&entries)?);
+        self.0.lock().unwrap().insert(lang, lexicon.clone());
+        Ok(lexicon)
+    }
+
+    fn invalidate(&self, lang: &str) {
+        self.0.lock().unwrap().remove(&base_language(lang));
+    }
+}
+
+/// Runs text through the lexicon of the request's language before handing
+/// it to the wrapped provider. Every registered provider is wrapped, so no
+/// caller can bypass it.
+pub struct WithLexicon(pub Arc<dyn TtsProvider>);
+
+impl WithLexicon {
+    fn pronounce(&self, app: &AppHandle, text: &str, options: &TtsOptions) -> (String, TtsOptions) {
+        let mut options = options.clone();
+        let lexicon = app
+            .state::<LexiconState>()
+            .get(app, options.language())
+            .unwrap_or_else(|e| {
+                log::warn!("Ignoring lexicon: {}", e);
+                Arc::new(Lexicon::default())
+            });
+        let pronounced = lexicon.apply(text, self.0.supports_ssml());
+        options.ssml = pronounced.ssml;
+        (pronounced.text, options)
+    }
+}
+
+impl TtsProvider for WithLexicon {
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    fn supports_ssml(&self) -> bool {
+        self.0.supports_ssml()
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        self.0.list_voices(app, options)
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let (text, options) = self.pronounce(app, text, options);
+            self.0.synthesize(app, &text, voice, &options).await
+        })
+    }
+
+    fn speak<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let (text, options) = self.pronounce(app, text, options);
+            self.0.speak(app, &text, voice, &options).await
+        })
+    }
+
+    fn stop<'a>(&'a self, app: &'a AppHandle) -> BoxFuture<'a, Result<(), Error>> {
+        self.0.stop(app)
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        self.0.health(app, options)
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn lexicon_list(lang: String, app: AppHandle) -> Result<Vec<LexiconEntry>, Error> {
+    read_entries(&lexicon_path(&LexiconState::dir(&app)?, &lang)?)
+}
+
+/// Adds `entry`, or replaces the entry at `index`. Returns the updated list.
+#[tauri::command]
+#[specta::specta]
+pub fn lexicon_put(
+    lang: String,
+    index: Option<u32>,
+    entry: LexiconEntry,
+    app: AppHandle,
+    state: tauri::State<'_, LexiconState>,
+) -> Result<Vec<LexiconEntry>, Error> {
+    entry.compile()?;
+    let path = lexicon_path(&LexiconState::dir(&app)?, &lang)?;
+    let mut entries = read_entries(&path)?;
+    match index {
+        Some(index) => {
+            let slot = entries.get_mut(index as usize).ok_or_else(|| {
+                Error::InvalidLexiconEntry(format!("no entry at index {}", index))
+            })?;
+            *slot = entry;
+        }
+        None => entries.push(entry),
+    }
+    write_entries(&path, entries.clone())?;
+    state.invalidate(&lang);
+    Ok(entries)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn lexicon_delete(
+    lang: String,
+    index: u32,
+    app: AppHandle,
+    state: tauri::State<'_, LexiconState>,
+) -> Result<Vec<LexiconEntry>, Error> {
+    let path = lexicon_path(&LexiconState::dir(&app)?, &lang)?;
+    let mut entries = read_entries(&path)?;
+    if (index as usize) < entries.len() {
+        entries.remove(index as usize);
+        write_entries(&path, entries.clone())?;
+        state.invalidate(&lang);
+    }
+    Ok(entries)
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LexiconTest {
+    /// What providers without phoneme support are sent
+    pub plain: String,
+    /// What SSML-capable providers are sent, if it differs
+    pub ssml: Option<String>,
+    pub matched: Vec<String>,
+}
+
+/// Shows what `text` turns into with the saved lexicon, plus an unsaved
+/// `draft` entry when given.
+#[tauri::command]
+#[specta::specta]
+pub fn lexicon_test(
+    lang: String,
+    text: String,
+    draft: Option<LexiconEntry>,
+    app: AppHandle,
+) -> Result<LexiconTest, Error> {
+    let mut entries = read_entries(&lexicon_path(&LexiconState::dir(&app)?, &lang)?)?;
+    entries.extend(draft);
+    let lexicon = Lexicon::new(&entries)?;
+    let ssml = lexicon.apply(&text, true);
+    Ok(LexiconTest {
+        plain: lexicon.apply(&text, false).text,
+        ssml: ssml.ssml.then_some(ssml.text),
+        matched: lexicon.matches(&text),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pattern: &str, replacement: &str, ipa: Option<&str>) -> LexiconEntry {
+        LexiconEntry {
+            pattern: pattern.to_string(),
+            regex: false,
+            replacement: replacement.to_string(),
+            ipa: ipa.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn whole_words_only() {
+        let lexicon = Lexicon::new(&[
+            entry("Nepomniachtchi", "Nepo-mnyash-chee", None),
+            entry("Giuoco Piano", "jaw-co pyah-no", None),
+            entry("Tal", "Tahl", None),
+        ])
+        .unwrap();
+        assert_eq!(
+            lexicon
+                .apply(
+                    "nepomniachtchi plays the giuoco  piano, Giuoco Piano. Talented Tal!",
+                    false
+                )
+                .text,
+            "Nepo-mnyash-chee plays the jaw-co pyah-no, jaw-co pyah-no. Talented Tahl!"
+        );
+    }
+
+    #[test]
+    fn regex_entries_and_no_cascades() {
+        let lexicon = Lexicon::new(&[
+            LexiconEntry {
+                pattern: r"\b(\d+)\.\.\.".to_string(),
+                regex: true,
+                replacement: "$1, black".to_string(),
+                ipa: None,
+            },
+            entry("black", "BLACK", None),
+        ])
+        .unwrap();
+        assert_eq!(
+            lexicon.apply("12... Nf6 and black is fine", false).text,
+            "12, black Nf6 and BLACK is fine"
+        );
+    }
+
+    #[test]
+    fn phonemes_as_ssml() {
+        let lexicon = Lexicon::new(&[entry("Réti", "Ray-tee", Some("ˈreːti"))]).unwrap();
+        assert_eq!(
+            lexicon.apply("Réti & Co", true),
+            Pronounced {
+                text:
+                    r#"<speak><phoneme alphabet="ipa" ph="ˈreːti">Réti</phoneme> &amp; Co</speak>"#
+                        .to_string(),
+                ssml: true,
+            }
+        );
+        assert_eq!(
+            lexicon.apply("Réti & Co", false),
+            Pronounced {
+                text: "Ray-tee & Co".to_string(),
+                ssml: false,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let err = Lexicon::new(&[LexiconEntry {
+            pattern: "(".to_string(),
+            regex: true,
+            replacement: String::new(),
+            ipa: None,
+        }]);
+        assert!(matches!(err, Err(Error::InvalidLexiconEntry(_))));
+    }
+
+    #[test]
+    fn files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lexicon_path(dir.path(), "en-GB").unwrap();
+        assert_eq!(path, dir.path().join("en.json"));
+        assert!(read_entries(&path).unwrap().is_empty());
+        write_entries(&path, vec![entry("en prise", "on preez", None)]).unwrap();
+        assert_eq!(read_entries(&path).unwrap().len(), 1);
+
+        std::fs::write(
+            dir.path().join("de.toml"),
+            "[[entries]]\npattern = \"Nimzowitsch\"\nreplacement = \"Nim-zo-vitch\"\n",
+        )
+        .unwrap();
+        let path = lexicon_path(dir.path(), "de").unwrap();
+        assert_eq!(
+            read_entries(&path).unwrap(),
+            vec![entry("Nimzowitsch", "Nim-zo-vitch", None)]
+        );
+        assert!(lexicon_path(dir.path(), "../x").is_err());
+    }
+}
@@ -5,30 +5,46 @@
 #![allow(clippy::result_large_err)]
 #![allow(clippy::too_many_arguments)]
 
+mod audio;
+mod audiobook;
+mod captions;
 mod chess;
+mod cloud_tts;
 mod db;
+mod docker;
 mod engine;
+mod engine_presets;
 mod error;
 mod game;
 
 mod fs;
+mod kittentts_setup;
 mod lexer;
+mod lexicon;
+mod narration;
 mod oauth;
 mod opening;
 mod pgn;
+mod precache;
 mod progress;
 mod puzzle;
+mod secrets;
 mod sound;
 mod system_tts;
+mod tts_cache;
+mod tts_doctor;
+mod tts_provider;
 mod tts_servers;
+mod word_clips;
 
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use chess::{BestMovesPayload, EngineProcess};
+use chess::{BestMovesPayload, EngineProcess, EngineSchema};
 use dashmap::DashMap;
 use db::{DatabaseProgress, GameQuery, NormalizedGame, PositionStats};
 use derivative::Derivative;
+use engine::EnginePool;
 use game::GameManager;
 use progress::{clear_progress, get_progress, ProgressEvent, ProgressStore};
 
@@ -40,10 +56,13 @@ use sysinfo::SystemExt;
 use tauri::{Manager, Window};
 use tauri_plugin_log::{Target, TargetKind};
 
+use crate::audiobook::{cancel_tts_job, export_audiobook};
 use crate::chess::{
-    analyze_game, cancel_analysis, get_engine_config, get_engine_logs, kill_engine, kill_engines,
-    stop_engine,
+    analyze_game, cancel_analysis, engine_pool_configure, engine_pool_status, get_engine_config,
+    get_engine_logs, kill_engine, kill_engines, narrate_best_line, ponder_hit, stop_engine,
+    validate_engine_options,
 };
+use crate::cloud_tts::{tts_usage, tts_usage_set_budget, TtsBudgetEvent, UsageLedgerState};
 use crate::db::{
     clear_games, convert_pgn, create_indexes, delete_database, delete_db_game, delete_empty_games,
     delete_indexes, export_to_pgn, get_db_game, get_player, get_players_game_info, get_tournaments,
@@ -51,27 +70,51 @@ use crate::db::{
 };
 use crate::game::{
     abort_game, get_game_engine_logs, get_game_state, make_game_move, resign_game, start_game,
-    take_back_game_move, ClockUpdateEvent, GameMoveEvent, GameOverEvent,
+    take_back_game_move, ClockUpdateEvent, GameMoveEvent, GameNarrationEvent, GameOverEvent,
 };
 
+use crate::audio::{audio_post_process_get, audio_post_process_set, PostProcessState};
+use crate::engine_presets::{
+    engine_preset_delete, engine_preset_save, engine_presets_list, EnginePresetsState,
+};
 use crate::fs::set_file_as_executable;
+use crate::kittentts_setup::kittentts_setup;
 use crate::lexer::lex_pgn;
-use crate::oauth::authenticate;
+use crate::lexicon::{lexicon_delete, lexicon_list, lexicon_put, lexicon_test, LexiconState};
+use crate::narration::{build_narration, describe_position, parse_spoken_move};
+use crate::oauth::{authenticate, authorized_fetch};
 use crate::pgn::{count_pgn_games, delete_game, read_games, write_game};
+use crate::precache::precache_game;
 use crate::puzzle::{
     delete_puzzle_database, get_puzzle, get_puzzle_db_info, get_puzzle_themes,
     get_themes_for_puzzle,
 };
+use crate::secrets::{
+    secrets_delete, secrets_get, secrets_has, secrets_list, secrets_put, secrets_reset,
+    secrets_set_passphrase, secrets_status, secrets_unlock, SecretsState,
+};
 use crate::sound::get_sound_server_port;
 use crate::system_tts::{
-    system_tts_list_voices, system_tts_set_voice, system_tts_speak, system_tts_stop, SystemTtsState,
+    system_tts_list_voices, system_tts_set_voice, system_tts_speak, system_tts_stop,
+    SystemTtsState, SystemTtsUtteranceEvent,
+};
+use crate::tts_cache::{
+    tts_cache_clear, tts_cache_prune, tts_cache_set_limit, tts_cache_stats, TtsCacheState,
+};
+use crate::tts_doctor::{tts_diagnose, TtsFixEvent};
+use crate::tts_provider::{
+    tts_health, tts_list_providers, tts_list_voices, tts_speak, tts_stop, tts_synthesize,
+    TtsProviders,
 };
 use crate::tts_servers::{
-    check_docker_installed, check_docker_running, check_kittentts_packages, check_kittentts_script,
-    check_kittentts_venv, check_opentts_image, check_python_installed, fetch_tts_audio,
-    kittentts_start, kittentts_stop, opentts_start, opentts_stop, setup_kittentts_venv,
-    setup_opentts_load, setup_opentts_pull, TtsServerState,
+    cancel_tts_fetch, check_command_tts, check_docker_installed, check_docker_running,
+    check_kittentts_packages, check_kittentts_script, check_kittentts_venv, check_opentts_image,
+    check_python_installed, command_tts_get_config, command_tts_set_config, fetch_tts_audio,
+    kittentts_start, kittentts_status, kittentts_stop, opentts_start, opentts_status, opentts_stop,
+    setup_kittentts_venv, setup_opentts_load, setup_opentts_pull, KittenTtsStatusEvent,
+    TtsServerState,
 };
+use crate::word_clips::{compose_clips, move_clip_tokens};
 use crate::{
     chess::get_best_moves,
     db::{
@@ -101,7 +144,11 @@ pub struct AppState {
     pgn_offsets: DashMap<String, Vec<u64>>,
 
     engine_processes: DashMap<(String, String), Arc<tokio::sync::Mutex<EngineProcess>>>,
+    engine_pool: Mutex<EnginePool<Arc<tokio::sync::Mutex<EngineProcess>>>>,
+    engine_schemas: DashMap<PathBuf, EngineSchema>,
     analysis_cancel_flags: DashMap<String, Arc<AtomicBool>>,
+    tts_job_cancel_flags: DashMap<String, Arc<AtomicBool>>,
+    tts_fetches: DashMap<String, (u64, tokio::task::AbortHandle)>,
     auth: AuthState,
     game_manager: GameManager,
     progress_state: ProgressStore,
@@ -123,12 +170,16 @@ fn main() {
         .commands(tauri_specta::collect_commands!(
             close_splashscreen,
             get_best_moves,
+            narrate_best_line,
             analyze_game,
             cancel_analysis,
             stop_engine,
+            ponder_hit,
             kill_engine,
             kill_engines,
             get_engine_logs,
+            engine_pool_status,
+            engine_pool_configure,
             memory_size,
             get_puzzle,
             search_opening_name,
@@ -137,6 +188,10 @@ fn main() {
             get_opening_from_name,
             get_players_game_info,
             get_engine_config,
+            validate_engine_options,
+            engine_presets_list,
+            engine_preset_save,
+            engine_preset_delete,
             file_exists,
             get_file_metadata,
             merge_players,
@@ -145,6 +200,9 @@ fn main() {
             count_pgn_games,
             read_games,
             lex_pgn,
+            build_narration,
+            describe_position,
+            parse_spoken_move,
             is_bmi2_compatible,
             delete_game,
             delete_duplicated_games,
@@ -158,6 +216,16 @@ fn main() {
             delete_database,
             export_to_pgn,
             authenticate,
+            authorized_fetch,
+            secrets_status,
+            secrets_unlock,
+            secrets_set_passphrase,
+            secrets_put,
+            secrets_get,
+            secrets_has,
+            secrets_delete,
+            secrets_list,
+            secrets_reset,
             write_game,
             download_file,
             get_tournaments,
@@ -187,10 +255,41 @@ fn main() {
             system_tts_set_voice,
             opentts_start,
             opentts_stop,
+            opentts_status,
             kittentts_start,
             kittentts_stop,
+            kittentts_status,
+            move_clip_tokens,
+            lexicon_list,
+            lexicon_put,
+            lexicon_delete,
+            lexicon_test,
+            compose_clips,
             fetch_tts_audio,
+            cancel_tts_fetch,
+            tts_list_providers,
+            tts_list_voices,
+            tts_synthesize,
+            tts_speak,
+            tts_stop,
+            tts_health,
+            tts_cache_stats,
+            tts_cache_set_limit,
+            tts_cache_prune,
+            tts_cache_clear,
+            audio_post_process_get,
+            audio_post_process_set,
+            tts_usage,
+            tts_usage_set_budget,
+            export_audiobook,
+            cancel_tts_job,
+            precache_game,
+            tts_diagnose,
+            kittentts_setup,
             check_docker_installed,
+            check_command_tts,
+            command_tts_get_config,
+            command_tts_set_config,
             check_docker_running,
             check_opentts_image,
             check_python_installed,
@@ -208,7 +307,12 @@ fn main() {
             ProgressEvent,
             GameMoveEvent,
             ClockUpdateEvent,
-            GameOverEvent
+            GameOverEvent,
+            GameNarrationEvent,
+            SystemTtsUtteranceEvent,
+            KittenTtsStatusEvent,
+            TtsFixEvent,
+            TtsBudgetEvent
         ));
 
     #[cfg(debug_assertions)]
@@ -253,13 +357,16 @@ fn main() {
 
             specta_builder.mount_events(app);
 
+            tauri::async_runtime::spawn(chess::reap_idle_engines(app.handle().clone()));
+
             #[cfg(target_os = "linux")]
             {
                 let sound_dir = app
                     .path()
                     .resolve("sound", tauri::path::BaseDirectory::Resource)
                     .expect("failed to resolve sound resource directory");
-                let port = sound::start_sound_server(sound_dir);
+                let composed_dir = word_clips::composed_dir(app.handle())?;
+                let port = sound::start_sound_server(sound_dir, composed_dir);
                 app.manage(sound::SoundServerPort(port));
             }
             #[cfg(not(target_os = "linux"))]
@@ -276,21 +383,33 @@ fn main() {
 
             Ok(())
         })
-        .manage(SystemTtsState(std::sync::Mutex::new(None)))
-        .manage(TtsServerState {
-            kittentts_pid: std::sync::Mutex::new(None),
-        })
+        .manage(SystemTtsState::default())
+        .manage(TtsServerState::default())
+        .manage(TtsCacheState::default())
+        .manage(TtsProviders::default())
+        .manage(LexiconState::default())
+        .manage(PostProcessState::default())
+        .manage(UsageLedgerState::default())
+        .manage(SecretsState::default())
+        .manage(EnginePresetsState::default())
         .manage(AppState::default())
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app, event| {
             if let tauri::RunEvent::ExitRequested { .. } = event {
                 let state = app.state::<AppState>();
-                for entry in state.engine_processes.iter() {
-                    if let Ok(mut process) = entry.value().try_lock() {
+                let idle = state.engine_pool.lock().unwrap().drain_idle();
+                for process in state
+                    .engine_processes
+                    .iter()
+                    .map(|e| e.value().clone())
+                    .chain(idle)
+                {
+                    if let Ok(mut process) = process.try_lock() {
                         process.kill_sync();
                     }
                 }
+                app.state::<TtsServerState>().shutdown();
             }
         });
 }
@@ -0,0 +1,179 @@
+use serde::Deserialize;
+use shakmaty::{san::SanPlus, Color};
+use specta::Type;
+use vampirc_uci::uci::{Score, ScoreValue};
+
+use super::{fill, san_to_spoken, vocab, ChessVocab};
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalNarrationOptions {
+    pub lang: String,
+    /// Also speak the win, draw and loss percentages when the engine reports
+    /// them
+    #[serde(default)]
+    pub wdl: bool,
+    /// Moves of the principal variation to speak
+    #[serde(default = "default_line_length")]
+    pub line_length: u32,
+}
+
+fn default_line_length() -> u32 {
+    2
+}
+
+/// "White is clearly better" for a centipawn score from White's point of
+/// view. Below 0.3 pawns the position is equal, and from 2.5 pawns winning.
+fn band(v: &ChessVocab, cp: i64) -> String {
+    let side = v.side(if cp > 0 { Color::White } else { Color::Black });
+    match cp.abs() {
+        0..=30 => v.equal.to_string(),
+        31..=100 => fill(v.slightly_better, &[side]),
+        101..=250 => fill(v.clearly_better, &[side]),
+        _ => fill(v.winning, &[side]),
+    }
+}
+
+/// "plus 1.4", with the decimal separator of the language.
+fn pawns(v: &ChessVocab, cp: i64) -> String {
+    let sign = if cp > 0 { v.plus } else { v.minus };
+    let value = format!("{:.1}", cp.abs() as f64 / 100.0).replace('.', v.decimal);
+    format!("{} {}", sign, value)
+}
+
+/// Spoken summary of an engine line, e.g. "White is clearly better, about
+/// plus 1.4; best is Knight takes e5, then Queen h5." The score is from
+/// White's point of view, as in `BestMoves`.
+pub fn narrate_eval(score: &Score, san_moves: &[String], options: &EvalNarrationOptions) -> String {
+    let v = vocab(&options.lang);
+
+    let mut text = match score.value {
+        ScoreValue::Mate(moves) => {
+            let side = v.side(if moves > 0 {
+                Color::White
+            } else {
+                Color::Black
+            });
+            fill(v.mates_in, &[side, &moves.abs().to_string()])
+        }
+        ScoreValue::Cp(cp) => {
+            let cp = cp as i64;
+            let band = band(v, cp);
+            if cp == 0 {
+                band
+            } else {
+                format!("{}, {}", band, fill(v.about, &[&pawns(v, cp)]))
+            }
+        }
+    };
+
+    if options.wdl {
+        if let Some((w, d, l)) = score.wdl {
+            // Reported in permille
+            let percent = |x: f64| format!("{}", (x / 10.0).round());
+            let (w, d, l) = (percent(w as f64), percent(d as f64), percent(l as f64));
+            text.push_str(&format!("; {}", fill(v.wdl, &[&w, &d, &l])));
+        }
+    }
+
+    let line: Vec<String> = san_moves
+        .iter()
+        .take(options.line_length as usize)
+        .filter_map(|san| SanPlus::from_ascii(san.as_bytes()).ok())
+        .map(|san| san_to_spoken(&san, &options.lang))
+        .collect();
+    if let Some((best, rest)) = line.split_first() {
+        text.push_str(&format!("; {}", fill(v.best_is, &[best])));
+        for san in rest {
+            text.push_str(&format!(", {} {}", v.then, san));
+        }
+    }
+    text.push('.');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(lang: &str, wdl: bool) -> EvalNarrationOptions {
+        EvalNarrationOptions {
+            lang: lang.to_string(),
+            wdl,
+            line_length: default_line_length(),
+        }
+    }
+
+    fn line(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn centipawn_bands() {
+        let score = Score {
+            value: ScoreValue::Cp(140),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &line(&["Nxe5", "Qh5", "g6"]), &options("en", false)),
+            "White is clearly better, about plus 1.4; best is Knight takes e5, then Queen h5."
+        );
+
+        let score = Score {
+            value: ScoreValue::Cp(-60),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &line(&["e4"]), &options("en", false)),
+            "Black is slightly better, about minus 0.6; best is e4."
+        );
+
+        let score = Score {
+            value: ScoreValue::Cp(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &[], &options("en", false)),
+            "The position is equal."
+        );
+    }
+
+    #[test]
+    fn mate_and_wdl() {
+        let score = Score {
+            value: ScoreValue::Mate(-3),
+            wdl: Some((0, 0, 1000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &line(&["Qh4+"]), &options("en", true)),
+            "Black mates in 3; 0 percent for White, 0 percent draw, 100 percent for Black; best is Queen h4, check."
+        );
+
+        let score = Score {
+            value: ScoreValue::Cp(400),
+            wdl: Some((612, 350, 38)),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &[], &options("en", true)),
+            "White is winning, about plus 4.0; 61 percent for White, 35 percent draw, 4 percent for Black."
+        );
+        assert_eq!(
+            narrate_eval(&score, &[], &options("en", false)),
+            "White is winning, about plus 4.0."
+        );
+    }
+
+    #[test]
+    fn other_languages() {
+        let score = Score {
+            value: ScoreValue::Cp(-140),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate_eval(&score, &line(&["Nf6"]), &options("de", false)),
+            "Schwarz steht klar besser, etwa minus 1,4; am besten ist Springer f6."
+        );
+    }
+}
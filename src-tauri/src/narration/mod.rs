@@ -0,0 +1,497 @@
+mod eval;
+mod move_input;
+mod position;
+mod vocab;
+
+use std::collections::HashSet;
+
+use lazy_static::lazy_static;
+use pgn_reader::Nag;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use shakmaty::{
+    san::{San, SanPlus, Suffix},
+    CastlingSide, Color, Outcome,
+};
+use specta::Type;
+
+use crate::error::Error;
+
+pub use eval::{narrate_eval, EvalNarrationOptions};
+pub use move_input::*;
+pub use position::*;
+pub use vocab::{vocab, ChessVocab};
+
+lazy_static! {
+    static ref EMBEDDED_TAG: Regex =
+        Regex::new(r"\[%(?:eval|csl|cal|clk|timestamp)\s+[^\]]*\]").unwrap();
+    static ref DIVIDER: Regex = Regex::new(r"—{2,}|–{2,}|-{3,}").unwrap();
+    static ref QUOTES: Regex = Regex::new(r"['‘’]").unwrap();
+    static ref LEADING_ANNOTATION: Regex = Regex::new(r"^(?:\?\?|\?!|!\?|!!|\?|!)\s*").unwrap();
+    static ref LEADING_QUALITY: Regex =
+        Regex::new(r"(?i)^(?:BLUNDER|BRILLIANT|EXCELLENT|GOOD|MISTAKE|INACCURACY|DUBIOUS)[.!,:]?\s*")
+            .unwrap();
+    static ref SQUARE_HYPHEN: Regex = Regex::new(r"\b([a-h][1-8])-square").unwrap();
+    static ref BLACK_RANGE: Regex = Regex::new(r"\.{3}([a-h][1-8])-([a-h][1-8])").unwrap();
+    static ref RANGE: Regex = Regex::new(r"\b([a-h][1-8])-([a-h][1-8])\b").unwrap();
+    static ref NUMBERED_SAN: Regex =
+        Regex::new(r"\b(\d+)\.{1,3}([KQRBN]?[a-h]?x?[a-h][1-8](?:=[QRBN])?[+#]?)").unwrap();
+    static ref STANDALONE_SAN: Regex = Regex::new(r"\b([KQRBN][a-h]?x?[a-h][1-8][+#]?)").unwrap();
+    static ref MOVE_NUMBER_COMMA: Regex = Regex::new(r"(\d+),\s+").unwrap();
+    static ref MOVE_NUMBER_DOTS: Regex = Regex::new(r"(\d+)\.{1,3}").unwrap();
+    static ref EN_PRISE: Regex = Regex::new(r"(?i)\ben prise\b").unwrap();
+    static ref ROOK_VS_ROOK: Regex = Regex::new(r"(?i)\bR vs R\b").unwrap();
+    static ref ROOK_VS: Regex = Regex::new(r"(?i)\bR vs\b").unwrap();
+    static ref ROOK_PLUS: Regex = Regex::new(r"\bR\+").unwrap();
+    static ref BISHOP_PLUS: Regex = Regex::new(r"\bB\+").unwrap();
+    static ref KNIGHT_PLUS: Regex = Regex::new(r"\bN\+").unwrap();
+    static ref VERSUS: Regex = Regex::new(r"(?i)\bvs\b").unwrap();
+    static ref LONG_CASTLE: Regex = Regex::new(r"\bO-O-O\b").unwrap();
+    static ref SHORT_CASTLE: Regex = Regex::new(r"\bO-O\b").unwrap();
+    static ref PIECE_ON_SQUARE: Regex = Regex::new(
+        r"\b([KQRBN])([a-h][1-8])\s+(is|was|has|can|stays|controls|defends|attacks|guards|covers|on)\b"
+    )
+    .unwrap();
+    static ref PAWN_COUNT: Regex = Regex::new(r"(\d)P\b").unwrap();
+    static ref TRAILING_SEMICOLONS: Regex = Regex::new(r";+\s*$").unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+}
+
+/// Replaces the `{}` placeholders of a vocabulary template in order.
+pub fn fill(template: &str, values: &[&str]) -> String {
+    let mut text = template.to_string();
+    for value in values {
+        text = text.replacen("{}", value, 1);
+    }
+    text
+}
+
+/// Spoken form of a move, e.g. `Nbxd7+` becomes "Knight b takes d7, check".
+pub fn san_to_spoken(san: &SanPlus, lang: &str) -> String {
+    let vocab = vocab(lang);
+
+    let mut spoken = match &san.san {
+        San::Castle(CastlingSide::KingSide) => vocab.castles_kingside.to_string(),
+        San::Castle(CastlingSide::QueenSide) => vocab.castles_queenside.to_string(),
+        San::Normal {
+            role,
+            file,
+            rank,
+            capture,
+            to,
+            promotion,
+        } => {
+            let mut parts: Vec<String> = Vec::new();
+            let piece = vocab.piece(*role);
+            if !piece.is_empty() {
+                parts.push(piece.to_string());
+            }
+            let disambiguation: String = file
+                .map(|f| f.char())
+                .into_iter()
+                .chain(rank.map(|r| r.char()))
+                .collect();
+            if !disambiguation.is_empty() {
+                parts.push(disambiguation);
+            }
+            if *capture {
+                parts.push(vocab.takes.to_string());
+            }
+            parts.push(to.to_string());
+            if let Some(promotion) = promotion {
+                parts.push(vocab.promotes_to.to_string());
+                parts.push(vocab.piece(*promotion).to_string());
+            }
+            parts.join(" ")
+        }
+        San::Put { role, to } => {
+            let piece = vocab.piece(*role);
+            if piece.is_empty() {
+                to.to_string()
+            } else {
+                format!("{} {}", piece, to)
+            }
+        }
+        San::Null => return String::new(),
+    };
+
+    match san.suffix {
+        Some(Suffix::Checkmate) => {
+            spoken.push_str(", ");
+            spoken.push_str(vocab.checkmate);
+        }
+        Some(Suffix::Check) => {
+            spoken.push_str(", ");
+            spoken.push_str(vocab.check);
+        }
+        None => {}
+    }
+
+    spoken
+}
+
+/// Spoken quality assessment for the move-quality NAGs ($1-$6).
+/// Other NAGs are not spoken.
+pub fn nags_to_spoken(nags: &[Nag], lang: &str) -> String {
+    let vocab = vocab(lang);
+    nags.iter()
+        .filter_map(|nag| match nag.0 {
+            1 => Some(vocab.good),
+            2 => Some(vocab.mistake),
+            3 => Some(vocab.brilliant),
+            4 => Some(vocab.blunder),
+            5 => Some(vocab.interesting),
+            6 => Some(vocab.dubious),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Spoken game result, e.g. "White wins.". Unfinished games say nothing.
+pub fn outcome_to_spoken(outcome: Option<Outcome>, lang: &str) -> String {
+    let vocab = vocab(lang);
+    match outcome {
+        Some(Outcome::Decisive {
+            winner: Color::White,
+        }) => vocab.white_wins.to_string(),
+        Some(Outcome::Decisive {
+            winner: Color::Black,
+        }) => vocab.black_wins.to_string(),
+        Some(Outcome::Draw) => vocab.draw.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Like `Regex::replace_all`, but skips matches that run straight into a word
+/// character, so `Nf3` is expanded in "Nf3 controls" but not inside "Nf3x".
+fn replace_san_tokens<F>(re: &Regex, text: &str, mut replace: F) -> String
+where
+    F: FnMut(&Captures) -> String,
+{
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in re.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        let followed_by_word = text[m.end()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if followed_by_word {
+            continue;
+        }
+        out.push_str(&text[last..m.start()]);
+        out.push_str(&replace(&caps));
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn spoken_or_original(san: &str, lang: &str) -> String {
+    match SanPlus::from_ascii(san.as_bytes()) {
+        Ok(san) => san_to_spoken(&san, lang),
+        Err(_) => san.to_string(),
+    }
+}
+
+/// Expands SAN that appears inside comment prose.
+/// "7.Nf3 controls e5" becomes "7, Knight f3 controls e5".
+fn expand_inline_san(text: &str, lang: &str) -> String {
+    // Dots after move numbers are replaced by a comma so TTS doesn't say "dot"
+    let text = replace_san_tokens(&NUMBERED_SAN, text, |caps| {
+        format!("{}, {}", &caps[1], spoken_or_original(&caps[2], lang))
+    });
+    replace_san_tokens(&STANDALONE_SAN, &text, |caps| {
+        spoken_or_original(&caps[1], lang)
+    })
+}
+
+/// Chess shorthand in prose that TTS engines mispronounce.
+fn apply_chess_vocab(text: &str, lang: &str) -> String {
+    let vocab = vocab(lang);
+
+    let mut text = text.to_string();
+    if lang == "en" || lang.starts_with("en-") {
+        // French loan phrase, only a problem for English voices
+        text = EN_PRISE.replace_all(&text, "on preez").into_owned();
+    }
+
+    text = ROOK_VS_ROOK
+        .replace_all(&text, format!("{} versus {}", vocab.rook, vocab.rook))
+        .into_owned();
+    text = ROOK_VS
+        .replace_all(&text, format!("{} versus", vocab.rook))
+        .into_owned();
+    text = ROOK_PLUS
+        .replace_all(&text, format!("{} plus ", vocab.rook))
+        .into_owned();
+    text = BISHOP_PLUS
+        .replace_all(&text, format!("{} plus ", vocab.bishop))
+        .into_owned();
+    text = KNIGHT_PLUS
+        .replace_all(&text, format!("{} plus ", vocab.knight))
+        .into_owned();
+    text = VERSUS.replace_all(&text, "versus").into_owned();
+
+    text = LONG_CASTLE
+        .replace_all(&text, vocab.castles_queenside)
+        .into_owned();
+    text = SHORT_CASTLE
+        .replace_all(&text, vocab.castles_kingside)
+        .into_owned();
+
+    // "Ra8 is hanging" -> "Rook on a8 is hanging"
+    text = PIECE_ON_SQUARE
+        .replace_all(&text, |caps: &Captures| {
+            let piece = match &caps[1] {
+                "K" => vocab.king,
+                "Q" => vocab.queen,
+                "R" => vocab.rook,
+                "B" => vocab.bishop,
+                _ => vocab.knight,
+            };
+            format!("{} on {} {}", piece, &caps[2], &caps[3])
+        })
+        .into_owned();
+
+    // Material counts like "R+6P"
+    PAWN_COUNT.replace_all(&text, "$1 pawns").into_owned()
+}
+
+/// Cleans a raw PGN comment into text suitable for speech: strips embedded
+/// commands and dividers, joins wrapped lines and expands chess notation.
+pub fn clean_comment(comment: &str, lang: &str) -> String {
+    if comment.is_empty() {
+        return String::new();
+    }
+
+    let text = comment.replace("\r\n", " ").replace('\n', " ");
+    let text = EMBEDDED_TAG.replace_all(&text, "");
+    let text = DIVIDER.replace_all(&text, "");
+    let text = QUOTES.replace_all(&text, "");
+
+    // Leading annotation symbols and quality words duplicate the spoken NAGs
+    let text = LEADING_ANNOTATION.replace(&text, "");
+    let text = LEADING_QUALITY.replace(&text, "");
+
+    let text = SQUARE_HYPHEN.replace_all(&text, "$1 square");
+    let text = BLACK_RANGE.replace_all(&text, "$1 to $2");
+    let text = RANGE.replace_all(&text, "$1 to $2");
+
+    let text = expand_inline_san(&text, lang);
+
+    // "6, Bishop f5, 6, Knight d7" -> "6, Bishop f5, Knight d7"
+    let mut seen = HashSet::new();
+    let text = MOVE_NUMBER_COMMA.replace_all(&text, |caps: &Captures| {
+        if seen.insert(caps[1].to_string()) {
+            caps[0].to_string()
+        } else {
+            String::new()
+        }
+    });
+
+    // Move numbers without a recognizable move, e.g. "move 28..."
+    let text = MOVE_NUMBER_DOTS.replace_all(&text, "$1,");
+
+    let text = apply_chess_vocab(&text, lang);
+    let text = TRAILING_SEMICOLONS.replace(&text, "");
+    WHITESPACE.replace_all(&text, " ").trim().to_string()
+}
+
+/// Full narration for one node of a game: move number and spoken move,
+/// quality assessment from NAGs, then the cleaned comment.
+///
+/// `ply` is the ply count after the move (1 for White's first move).
+/// When the comment is a fragment that continues into the following moves,
+/// `continuation` is spoken after it to complete the sentence.
+pub fn narrate_move(
+    san: Option<&SanPlus>,
+    nags: &[Nag],
+    comment: &str,
+    ply: u32,
+    continuation: &[SanPlus],
+    lang: &str,
+) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    if let Some(san) = san {
+        let move_number = ply.div_ceil(2);
+        parts.push(format!("{}, {}.", move_number, san_to_spoken(san, lang)));
+    }
+
+    let quality = nags_to_spoken(nags, lang);
+    if !quality.is_empty() {
+        parts.push(quality);
+    }
+
+    let mut comment = clean_comment(comment, lang);
+    if !comment.is_empty() && !continuation.is_empty() && !comment.ends_with(['.', '!', '?']) {
+        let moves: Vec<String> = continuation
+            .iter()
+            .map(|san| san_to_spoken(san, lang))
+            .collect();
+        comment.push_str(&format!(" {}.", moves.join(", ")));
+    }
+    if !comment.is_empty() {
+        parts.push(comment);
+    }
+
+    // Double space gives TTS a natural breath between parts
+    parts.join("  ")
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NarrationRequest {
+    pub san: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<String>,
+    #[serde(default)]
+    pub comment: String,
+    pub ply: u32,
+    #[serde(default)]
+    pub continuation: Vec<String>,
+    pub lang: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn build_narration(request: NarrationRequest) -> Result<String, Error> {
+    let san = request
+        .san
+        .as_deref()
+        .map(|s| SanPlus::from_ascii(s.as_bytes()))
+        .transpose()?;
+    // Annotations are "!", "?!", "$5" etc.; anything else is ignored
+    let nags: Vec<Nag> = request
+        .annotations
+        .iter()
+        .filter_map(|a| Nag::from_ascii(a.as_bytes()).ok())
+        .collect();
+    let continuation = request
+        .continuation
+        .iter()
+        .map(|s| SanPlus::from_ascii(s.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(narrate_move(
+        san.as_ref(),
+        &nags,
+        &request.comment,
+        request.ply,
+        &continuation,
+        &request.lang,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spoken(san: &str, lang: &str) -> String {
+        san_to_spoken(&san.parse().unwrap(), lang)
+    }
+
+    #[test]
+    fn spoken_pawn_moves() {
+        assert_eq!(spoken("e4", "en"), "e4");
+        assert_eq!(spoken("exd5", "en"), "e takes d5");
+    }
+
+    #[test]
+    fn spoken_castling() {
+        assert_eq!(spoken("O-O", "en"), "castles kingside");
+        assert_eq!(spoken("O-O-O", "en"), "castles queenside");
+        assert_eq!(spoken("O-O+", "en"), "castles kingside, check");
+        assert_eq!(spoken("O-O-O", "de"), "lange Rochade");
+    }
+
+    #[test]
+    fn spoken_promotion() {
+        assert_eq!(spoken("e8=Q", "en"), "e8 promotes to Queen");
+        assert_eq!(
+            spoken("exd8=N#", "en"),
+            "e takes d8 promotes to Knight, checkmate"
+        );
+    }
+
+    #[test]
+    fn spoken_disambiguation() {
+        assert_eq!(spoken("Rae1", "en"), "Rook a e1");
+        assert_eq!(spoken("N5xd4+", "en"), "Knight 5 takes d4, check");
+        assert_eq!(spoken("Qh4xe1", "en"), "Queen h4 takes e1");
+    }
+
+    #[test]
+    fn spoken_other_languages() {
+        assert_eq!(spoken("Nf3", "fr"), "Cavalier f3");
+        assert_eq!(spoken("Bxe6+", "es-MX"), "Alfil captura e6, jaque");
+        assert_eq!(spoken("Nf3", "xx"), "Knight f3");
+    }
+
+    #[test]
+    fn comment_inline_move_numbers() {
+        assert_eq!(
+            clean_comment("7.Nf3 controls e5", "en"),
+            "7, Knight f3 controls e5"
+        );
+        assert_eq!(
+            clean_comment("Better was 28...Rxa8 when 29.Qxd8+ wins.", "en"),
+            "Better was 28, Rook takes a8 when 29, Queen takes d8, check wins."
+        );
+        assert_eq!(
+            clean_comment("6...Bf5, 6...Nd7 or 6...Nf6", "en"),
+            "6, Bishop f5, Knight d7 or Knight f6"
+        );
+    }
+
+    #[test]
+    fn comment_cleanup() {
+        assert_eq!(
+            clean_comment("[%eval 0.35] [%clk 0:10:00] Bg3 stays\nactive", "en"),
+            "Bishop g3 stays active"
+        );
+        assert_eq!(
+            clean_comment("BLUNDER. The h1-square falls after h5-h4;", "en"),
+            "The h1 square falls after h5 to h4"
+        );
+        assert_eq!(
+            clean_comment("Ra8 is en prise after O-O-O", "en"),
+            "Rook a8 is on preez after castles queenside"
+        );
+    }
+
+    #[test]
+    fn full_narration() {
+        let san: SanPlus = "Nf3+".parse().unwrap();
+        assert_eq!(
+            narrate_move(
+                Some(&san),
+                &[Nag(1)],
+                "Developing with tempo.",
+                23,
+                &[],
+                "en"
+            ),
+            "12, Knight f3, check.  Good move.  Developing with tempo."
+        );
+    }
+
+    #[test]
+    fn narration_fragment_continuation() {
+        let san: SanPlus = "e4".parse().unwrap();
+        let continuation: Vec<SanPlus> = vec!["e5".parse().unwrap(), "Nf3".parse().unwrap()];
+        assert_eq!(
+            narrate_move(
+                Some(&san),
+                &[],
+                "The main line runs",
+                1,
+                &continuation,
+                "en"
+            ),
+            "1, e4.  The main line runs e5, Knight f3."
+        );
+    }
+}
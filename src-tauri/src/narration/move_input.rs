@@ -0,0 +1,491 @@
+use serde::Serialize;
+use shakmaty::{
+    san::SanPlus, uci::UciMove, CastlingSide, Chess, File, Move, Position, Rank, Role, Square,
+};
+use specta::Type;
+
+use super::{san_to_spoken, vocab, PositionSource};
+use crate::{engine::parse_fen_to_position, error::Error, AppState};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Piece(Role),
+    Square(Square),
+    File(File),
+    Rank(Rank),
+    Capture,
+    Check,
+    Promote,
+    /// `None` when the side was not said, as in "castle"
+    Castle(Option<CastlingSide>),
+}
+
+/// Words understood in every language: move symbols typed instead of spoken.
+const SYMBOLS: &[(&str, Token)] = &[
+    ("o-o-o", Token::Castle(Some(CastlingSide::QueenSide))),
+    ("0-0-0", Token::Castle(Some(CastlingSide::QueenSide))),
+    ("o-o", Token::Castle(Some(CastlingSide::KingSide))),
+    ("0-0", Token::Castle(Some(CastlingSide::KingSide))),
+    ("x", Token::Capture),
+    ("+", Token::Check),
+    ("#", Token::Check),
+    ("=", Token::Promote),
+];
+
+/// English phrasings beyond the narration vocabulary.
+const ENGLISH: &[(&str, Token)] = &[
+    ("short castle", Token::Castle(Some(CastlingSide::KingSide))),
+    ("short castles", Token::Castle(Some(CastlingSide::KingSide))),
+    ("castle short", Token::Castle(Some(CastlingSide::KingSide))),
+    ("castles short", Token::Castle(Some(CastlingSide::KingSide))),
+    (
+        "castle kingside",
+        Token::Castle(Some(CastlingSide::KingSide)),
+    ),
+    ("long castle", Token::Castle(Some(CastlingSide::QueenSide))),
+    ("long castles", Token::Castle(Some(CastlingSide::QueenSide))),
+    ("castle long", Token::Castle(Some(CastlingSide::QueenSide))),
+    ("castles long", Token::Castle(Some(CastlingSide::QueenSide))),
+    (
+        "castle queenside",
+        Token::Castle(Some(CastlingSide::QueenSide)),
+    ),
+    ("castle", Token::Castle(None)),
+    ("castles", Token::Castle(None)),
+    ("castling", Token::Castle(None)),
+    ("captures", Token::Capture),
+    ("take", Token::Capture),
+    ("mate", Token::Check),
+    ("promote to", Token::Promote),
+    ("promotes", Token::Promote),
+    ("promoting to", Token::Promote),
+    ("equals", Token::Promote),
+];
+
+/// Languages written without spaces between words, matched character by
+/// character instead of word by word.
+fn is_unspaced(lang: &str) -> bool {
+    let base = lang.split(['-', '_']).next().unwrap_or(lang);
+    matches!(base.to_ascii_lowercase().as_str(), "ja" | "zh")
+}
+
+/// Splits text into the units phrases are matched on: words, or characters
+/// for unspaced languages. Runs of ASCII letters and digits are split off
+/// and always stay whole, so "ナイトf3" gives "ナ", "イ", "ト", "f3".
+fn units(text: &str, unspaced: bool) -> Vec<String> {
+    let text = text.to_lowercase();
+    let mut units = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || ",.!?;:()".contains(c)) {
+        let mut run = String::new();
+        for c in word.chars() {
+            let ascii = c.is_ascii_alphanumeric() || c == '-';
+            let symbol = "+#=".contains(c);
+            let run_ascii = run.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-');
+            if !run.is_empty() && (symbol || ascii != run_ascii || (unspaced && !ascii)) {
+                units.push(std::mem::take(&mut run));
+            }
+            if symbol {
+                units.push(c.to_string());
+            } else {
+                run.push(c);
+            }
+        }
+        if !run.is_empty() {
+            units.push(run);
+        }
+    }
+    units
+}
+
+struct Phrases {
+    unspaced: bool,
+    /// Longest first, so "castles kingside" wins over "castles"
+    entries: Vec<(Vec<String>, Token)>,
+}
+
+impl Phrases {
+    fn new(lang: &str) -> Self {
+        let v = vocab(lang);
+        let unspaced = is_unspaced(lang);
+        let mut phrases: Vec<(&str, Token)> = vec![
+            (
+                v.castles_kingside,
+                Token::Castle(Some(CastlingSide::KingSide)),
+            ),
+            (
+                v.castles_queenside,
+                Token::Castle(Some(CastlingSide::QueenSide)),
+            ),
+            (v.takes, Token::Capture),
+            (v.check, Token::Check),
+            (v.checkmate, Token::Check),
+            (v.promotes_to, Token::Promote),
+        ];
+        for role in Role::ALL {
+            phrases.push((v.piece_name(role), Token::Piece(role)));
+        }
+        for (rank, word) in Rank::ALL.iter().zip(v.numbers) {
+            phrases.push((word, Token::Rank(*rank)));
+        }
+        phrases.extend_from_slice(SYMBOLS);
+        if std::ptr::eq(v, vocab("en")) {
+            phrases.extend_from_slice(ENGLISH);
+        }
+
+        let mut entries: Vec<(Vec<String>, Token)> = phrases
+            .into_iter()
+            .map(|(phrase, token)| (units(phrase, unspaced), token))
+            .filter(|(units, _)| !units.is_empty())
+            .collect();
+        entries.sort_by_key(|(units, _)| std::cmp::Reverse(units.len()));
+        Self { unspaced, entries }
+    }
+
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        let units = units(text, self.unspaced);
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < units.len() {
+            if let Some((phrase, token)) = self
+                .entries
+                .iter()
+                .find(|(phrase, _)| units[i..].starts_with(phrase))
+            {
+                tokens.push(*token);
+                i += phrase.len();
+                continue;
+            }
+            tokens.extend(coordinate_tokens(&units[i]));
+            i += 1;
+        }
+
+        // A file followed by a rank is a square, as in "f three"
+        let mut merged: Vec<Token> = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match (merged.last(), token) {
+                (Some(Token::File(file)), Token::Rank(rank)) => {
+                    let square = Square::from_coords(*file, rank);
+                    *merged.last_mut().unwrap() = Token::Square(square);
+                }
+                _ => merged.push(token),
+            }
+        }
+        merged
+    }
+}
+
+/// Squares, files and ranks in a unit like "f3", "f", "3" or "e2-e4".
+/// Anything else is filler such as "to" and yields nothing.
+fn coordinate_tokens(unit: &str) -> Vec<Token> {
+    if unit.contains('-') {
+        return unit.split('-').flat_map(coordinate_tokens).collect();
+    }
+    if let Ok(square) = unit.parse::<Square>() {
+        return vec![Token::Square(square)];
+    }
+    let mut chars = unit.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => File::from_char(c)
+            .map(Token::File)
+            .or_else(|| Rank::from_char(c).map(Token::Rank))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Default)]
+struct Constraints {
+    castle: Option<Option<CastlingSide>>,
+    role: Option<Role>,
+    promotion: Option<Role>,
+    from: Option<Square>,
+    from_file: Option<File>,
+    from_rank: Option<Rank>,
+    to: Option<Square>,
+    capture: bool,
+    check: bool,
+}
+
+impl Constraints {
+    fn from_tokens(tokens: &[Token]) -> Self {
+        let mut c = Constraints::default();
+        let mut promoting = false;
+        for token in tokens {
+            match *token {
+                Token::Castle(side) => c.castle = Some(side),
+                // A piece named after the destination is a promotion, as
+                // in "e8 queen"
+                Token::Piece(role) if promoting || c.to.is_some() => c.promotion = Some(role),
+                Token::Piece(role) => c.role = c.role.or(Some(role)),
+                Token::Promote => promoting = true,
+                Token::Square(square) => {
+                    // With two squares, the first is where the piece stands
+                    c.from = c.to.or(c.from);
+                    c.to = Some(square);
+                }
+                Token::File(file) if c.to.is_none() => c.from_file = Some(file),
+                Token::Rank(rank) if c.to.is_none() => c.from_rank = Some(rank),
+                Token::File(_) | Token::Rank(_) => {}
+                Token::Capture => c.capture = true,
+                Token::Check => c.check = true,
+            }
+        }
+        c
+    }
+
+    fn is_empty(&self) -> bool {
+        self.castle.is_none() && self.role.is_none() && self.to.is_none()
+    }
+
+    fn matches(&self, pos: &Chess, m: &Move, disambiguation: bool) -> bool {
+        if let Some(side) = self.castle {
+            return m
+                .castling_side()
+                .is_some_and(|s| side.is_none_or(|side| side == s));
+        }
+        let to = match m.castling_side() {
+            Some(side) => side.king_to(pos.turn()),
+            None => m.to(),
+        };
+        self.role.is_none_or(|role| m.role() == role)
+            && self.to.is_none_or(|sq| to == sq)
+            && self.from.is_none_or(|sq| m.from() == Some(sq))
+            && (!disambiguation
+                || (self
+                    .from_file
+                    .is_none_or(|f| m.from().map(Square::file) == Some(f))
+                    && self
+                        .from_rank
+                        .is_none_or(|r| m.from().map(Square::rank) == Some(r))))
+            && (!self.capture || m.is_capture())
+            && self
+                .promotion
+                .is_none_or(|role| m.promotion() == Some(role))
+    }
+}
+
+/// Legal moves of `pos` matching a typed or spoken move such as "Nf3",
+/// "knight to f three", "pawn takes d5" or "long castles", in the narration
+/// language `lang`. More than one move means the phrase was ambiguous.
+pub fn match_move(pos: &Chess, text: &str, lang: &str) -> Vec<Move> {
+    let text = text.trim();
+    if let Ok(Ok(m)) = SanPlus::from_ascii(text.as_bytes()).map(|san| san.san.to_move(pos)) {
+        return vec![m];
+    }
+
+    let constraints = Constraints::from_tokens(&Phrases::new(lang).tokens(text));
+    if constraints.is_empty() {
+        return Vec::new();
+    }
+    let legal = pos.legal_moves();
+    let mut candidates: Vec<Move> = legal
+        .iter()
+        .filter(|m| constraints.matches(pos, m, true))
+        .cloned()
+        .collect();
+    if candidates.is_empty() && (constraints.from_file.is_some() || constraints.from_rank.is_some())
+    {
+        // A stray letter, like the article in "takes a pawn on d5", is not
+        // worth failing over
+        candidates = legal
+            .iter()
+            .filter(|m| constraints.matches(pos, m, false))
+            .cloned()
+            .collect();
+    }
+
+    // Like SAN, a move without a piece is a pawn move when one fits
+    if constraints.role.is_none()
+        && constraints.from.is_none()
+        && candidates.iter().any(|m| m.role() == Role::Pawn)
+    {
+        candidates.retain(|m| m.role() == Role::Pawn);
+    }
+    // "Check" only narrows, since it is easily misheard
+    if constraints.check && candidates.len() > 1 {
+        let checks: Vec<Move> = candidates
+            .iter()
+            .filter(|m| {
+                let mut after = pos.clone();
+                after.play_unchecked(m);
+                after.is_check()
+            })
+            .cloned()
+            .collect();
+        if !checks.is_empty() {
+            candidates = checks;
+        }
+    }
+    candidates
+}
+
+/// The UCI for `input`, which is passed through when it already is UCI and
+/// otherwise matched as a typed or spoken move.
+pub fn resolve_move(pos: &Chess, input: &str, lang: &str) -> Result<String, Error> {
+    if UciMove::from_ascii(input.trim().as_bytes()).is_ok() {
+        return Ok(input.trim().to_string());
+    }
+    let candidates = match_move(pos, input, lang);
+    match candidates.as_slice() {
+        [m] => Ok(m.to_uci(pos.castles().mode()).to_string()),
+        [] => Err(Error::NoMatchingMove(input.to_string())),
+        _ => Err(Error::AmbiguousMove(
+            candidates
+                .iter()
+                .map(|m| SanPlus::from_move(pos.clone(), m).to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCandidate {
+    pub uci: String,
+    pub san: String,
+    /// The move as narration would speak it, for asking which one was meant
+    pub spoken: String,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SpokenMoveMatch {
+    Move(MoveCandidate),
+    Ambiguous { candidates: Vec<MoveCandidate> },
+    NoMatch,
+}
+
+/// Matches a typed or dictated move against the legal moves of a position.
+/// When several moves fit, they are all returned so the player can be asked
+/// which one they meant.
+#[tauri::command]
+#[specta::specta]
+pub async fn parse_spoken_move(
+    source: PositionSource,
+    text: String,
+    lang: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<SpokenMoveMatch, Error> {
+    let fen = match source {
+        PositionSource::Fen(fen) => fen,
+        PositionSource::Game(game_id) => {
+            state
+                .game_manager
+                .get_game_state(&game_id)
+                .await?
+                .current_fen
+        }
+    };
+    let pos = parse_fen_to_position(&fen)?;
+    let mut candidates: Vec<MoveCandidate> = match_move(&pos, &text, &lang)
+        .iter()
+        .map(|m| {
+            let san = SanPlus::from_move(pos.clone(), m);
+            MoveCandidate {
+                uci: m.to_uci(pos.castles().mode()).to_string(),
+                spoken: san_to_spoken(&san, &lang),
+                san: san.to_string(),
+            }
+        })
+        .collect();
+    Ok(match candidates.len() {
+        0 => SpokenMoveMatch::NoMatch,
+        1 => SpokenMoveMatch::Move(candidates.remove(0)),
+        _ => SpokenMoveMatch::Ambiguous { candidates },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sans(fen: &str, text: &str, lang: &str) -> Vec<String> {
+        let pos = parse_fen_to_position(fen).unwrap();
+        let mut sans: Vec<String> = match_move(&pos, text, lang)
+            .iter()
+            .map(|m| SanPlus::from_move(pos.clone(), m).to_string())
+            .collect();
+        sans.sort();
+        sans
+    }
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const SCANDINAVIAN: &str = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+    const CASTLES: &str = "r3k2r/pppq1ppp/2n1bn2/3pp3/3PP3/2N1BN2/PPPQ1PPP/R3K2R w KQkq - 0 8";
+
+    #[test]
+    fn english_phrases() {
+        assert_eq!(sans(START, "knight to f three", "en"), vec!["Nf3"]);
+        assert_eq!(sans(START, "Knight f3", "en"), vec!["Nf3"]);
+        assert_eq!(sans(START, "e4", "en"), vec!["e4"]);
+        assert_eq!(sans(START, "e two e four", "en"), vec!["e4"]);
+        assert_eq!(sans(START, "e2-e4", "en"), vec!["e4"]);
+        assert_eq!(sans(SCANDINAVIAN, "pawn takes d5", "en"), vec!["exd5"]);
+        assert_eq!(sans(SCANDINAVIAN, "e takes d5", "en"), vec!["exd5"]);
+        assert_eq!(sans(SCANDINAVIAN, "takes a pawn on d5", "en"), vec!["exd5"]);
+        assert_eq!(sans(START, "play the bishop", "en"), Vec::<String>::new());
+        assert_eq!(sans(START, "hello", "en"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn castling() {
+        assert_eq!(sans(CASTLES, "long castles", "en"), vec!["O-O-O"]);
+        assert_eq!(sans(CASTLES, "castle short", "en"), vec!["O-O"]);
+        assert_eq!(sans(CASTLES, "castles kingside", "en"), vec!["O-O"]);
+        assert_eq!(sans(CASTLES, "O-O-O", "en"), vec!["O-O-O"]);
+        assert_eq!(sans(CASTLES, "king to g1", "en"), vec!["O-O"]);
+        assert_eq!(sans(CASTLES, "castle", "en").len(), 2);
+    }
+
+    #[test]
+    fn ambiguity_returns_candidates() {
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        assert_eq!(sans(fen, "knight d2", "en"), vec!["Nbd2", "Nfd2"]);
+        assert_eq!(sans(fen, "knight b d2", "en"), vec!["Nbd2"]);
+        assert_eq!(sans(fen, "knight f3 d2", "en"), vec!["Nfd2"]);
+    }
+
+    #[test]
+    fn check_and_promotion() {
+        let fen = "4k3/8/8/8/1Q6/8/8/4K3 w - - 0 1";
+        assert_eq!(sans(fen, "queen e7 check", "en"), vec!["Qe7+"]);
+        assert_eq!(sans(fen, "queen b1 check", "en"), vec!["Qb1"]);
+        assert_eq!(sans(fen, "queen to b eight", "en"), vec!["Qb8+"]);
+
+        let fen = "8/4P1k1/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(sans(fen, "e8", "en").len(), 4);
+        assert_eq!(sans(fen, "e8 queen", "en"), vec!["e8=Q"]);
+        assert_eq!(sans(fen, "pawn e8 promotes to knight", "en"), vec!["e8=N"]);
+    }
+
+    #[test]
+    fn other_languages() {
+        assert_eq!(sans(START, "Springer f drei", "de"), vec!["Nf3"]);
+        assert_eq!(sans(SCANDINAVIAN, "e schlägt d5", "de"), vec!["exd5"]);
+        assert_eq!(sans(START, "cavalier f trois", "fr"), vec!["Nf3"]);
+        assert_eq!(sans(START, "ナイトf三", "ja"), vec!["Nf3"]);
+        assert_eq!(sans(SCANDINAVIAN, "пешка берёт d5", "ru"), vec!["exd5"]);
+        assert_eq!(
+            sans(CASTLES, "рокировка на ферзевый фланг", "ru"),
+            vec!["O-O-O"]
+        );
+    }
+
+    #[test]
+    fn resolves_to_uci() {
+        let pos = parse_fen_to_position(CASTLES).unwrap();
+        assert_eq!(resolve_move(&pos, "e1g1", "en").unwrap(), "e1g1");
+        assert_eq!(resolve_move(&pos, "castle short", "en").unwrap(), "e1g1");
+        assert_eq!(resolve_move(&pos, "knight b5", "en").unwrap(), "c3b5");
+        assert!(matches!(
+            resolve_move(&pos, "knight takes", "en"),
+            Err(Error::AmbiguousMove(_))
+        ));
+        assert!(matches!(
+            resolve_move(&pos, "bishop h8", "en"),
+            Err(Error::NoMatchingMove(_))
+        ));
+    }
+}
@@ -0,0 +1,385 @@
+use serde::Deserialize;
+use shakmaty::{Bitboard, Board, Chess, Color, File, Piece, Position, Rank, Role, Square};
+use specta::Type;
+
+use super::{vocab, ChessVocab};
+use crate::{engine::parse_fen_to_position, error::Error, AppState};
+
+/// Roles in the order pieces are listed, most valuable first.
+const ROLES: [Role; 6] = [
+    Role::King,
+    Role::Queen,
+    Role::Rook,
+    Role::Bishop,
+    Role::Knight,
+    Role::Pawn,
+];
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Verbosity {
+    /// Side to move and pieces, without pawns
+    Brief,
+    /// Side to move and every piece
+    Normal,
+    /// Like `Normal`, followed by the hanging pieces
+    Full,
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PositionQuery {
+    /// Pieces grouped by side
+    Pieces { verbosity: Verbosity },
+    /// Occupied squares of one file, or of every file from a to h
+    File { file: Option<String> },
+    /// Occupied squares of one rank, or of every rank from 8 to 1
+    Rank { rank: Option<String> },
+    /// The piece on a square with its attackers and defenders
+    Square { square: String },
+    /// Pieces attacked by the opponent and defended by nothing
+    Hanging,
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum PositionSource {
+    Fen(String),
+    /// Current position of a game run by the game manager
+    Game(String),
+}
+
+fn piece_on(v: &ChessVocab, piece: Piece, square: Square) -> String {
+    format!(
+        "{} {} {}",
+        v.side(piece.color),
+        v.piece_name(piece.role),
+        square
+    )
+}
+
+/// Lists a side's pieces by role, e.g. "White: King g1; Rook a1, f1".
+fn side_pieces(v: &ChessVocab, board: &Board, color: Color, pawns: bool) -> String {
+    let groups: Vec<String> = ROLES
+        .iter()
+        .filter(|&&role| pawns || role != Role::Pawn)
+        .filter_map(|&role| {
+            let squares = board.by_piece(Piece { color, role });
+            if squares.is_empty() {
+                return None;
+            }
+            let squares: Vec<String> = squares.into_iter().map(|sq| sq.to_string()).collect();
+            Some(format!("{} {}", v.piece_name(role), squares.join(", ")))
+        })
+        .collect();
+    format!("{}: {}.", v.side(color), groups.join("; "))
+}
+
+/// Occupied squares of a file or rank, e.g. "e file: White King e1".
+fn scan(v: &ChessVocab, board: &Board, name: String, squares: Bitboard) -> String {
+    let pieces: Vec<String> = squares
+        .into_iter()
+        .filter_map(|sq| board.piece_at(sq).map(|piece| piece_on(v, piece, sq)))
+        .collect();
+    if pieces.is_empty() {
+        format!("{}: {}.", name, v.empty)
+    } else {
+        format!("{}: {}.", name, pieces.join(", "))
+    }
+}
+
+fn pieces_list(v: &ChessVocab, board: &Board, squares: Bitboard) -> String {
+    if squares.is_empty() {
+        return v.nothing.to_string();
+    }
+    squares
+        .into_iter()
+        .filter_map(|sq| board.piece_at(sq).map(|piece| piece_on(v, piece, sq)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Pieces other than kings that the opponent attacks and nothing defends.
+pub fn hanging_pieces(board: &Board) -> Bitboard {
+    let occupied = board.occupied();
+    (occupied & !board.kings())
+        .into_iter()
+        .filter(|&sq| {
+            let color = board.color_at(sq).expect("occupied square");
+            board.attacks_to(sq, !color, occupied).any()
+                && board.attacks_to(sq, color, occupied).is_empty()
+        })
+        .collect()
+}
+
+fn parse_file(file: &str) -> Result<File, Error> {
+    let mut chars = file.chars();
+    match (
+        chars
+            .next()
+            .and_then(|c| File::from_char(c.to_ascii_lowercase())),
+        chars.next(),
+    ) {
+        (Some(file), None) => Ok(file),
+        _ => Err(Error::InvalidSquare(file.to_string())),
+    }
+}
+
+fn parse_rank(rank: &str) -> Result<Rank, Error> {
+    let mut chars = rank.chars();
+    match (chars.next().and_then(Rank::from_char), chars.next()) {
+        (Some(rank), None) => Ok(rank),
+        _ => Err(Error::InvalidSquare(rank.to_string())),
+    }
+}
+
+/// Spoken description of `pos` answering `query`, in the narration language
+/// `lang`.
+pub fn describe(pos: &Chess, query: &PositionQuery, lang: &str) -> Result<String, Error> {
+    let v = vocab(lang);
+    let board = pos.board();
+
+    let text = match query {
+        PositionQuery::Pieces { verbosity } => {
+            let pawns = *verbosity != Verbosity::Brief;
+            let mut parts = vec![v.to_move.replace("{}", v.side(pos.turn()))];
+            if pos.is_checkmate() {
+                parts[0].push_str(&format!(", {}", v.checkmate));
+            } else if pos.is_check() {
+                parts[0].push_str(&format!(", {}", v.check));
+            }
+            parts[0].push('.');
+            parts.push(side_pieces(v, board, Color::White, pawns));
+            parts.push(side_pieces(v, board, Color::Black, pawns));
+            if *verbosity == Verbosity::Full {
+                let hanging = hanging_pieces(board);
+                if hanging.any() {
+                    parts.push(format!(
+                        "{}: {}.",
+                        v.hanging,
+                        pieces_list(v, board, hanging)
+                    ));
+                }
+            }
+            parts.join("  ")
+        }
+        PositionQuery::File { file } => {
+            let files = match file {
+                Some(file) => vec![parse_file(file)?],
+                None => File::ALL.to_vec(),
+            };
+            files
+                .into_iter()
+                .map(|file| {
+                    let name = v.file.replace("{}", &file.char().to_string());
+                    scan(v, board, name, Bitboard::from_file(file))
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        }
+        PositionQuery::Rank { rank } => {
+            let ranks = match rank {
+                Some(rank) => vec![parse_rank(rank)?],
+                // From White's side of the board, as a diagram is read
+                None => Rank::ALL.iter().rev().copied().collect(),
+            };
+            ranks
+                .into_iter()
+                .map(|rank| {
+                    let name = v.rank.replace("{}", &rank.char().to_string());
+                    scan(v, board, name, Bitboard::from_rank(rank))
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        }
+        PositionQuery::Square { square } => {
+            let sq: Square = square
+                .parse()
+                .map_err(|_| Error::InvalidSquare(square.clone()))?;
+            // An empty square is attacked and defended from the point of
+            // view of the side to move
+            let (name, color) = match board.piece_at(sq) {
+                Some(piece) => (piece_on(v, piece, sq), piece.color),
+                None => (format!("{} {}", sq, v.empty), pos.turn()),
+            };
+            let occupied = board.occupied();
+            format!(
+                "{}; {} {}; {} {}.",
+                name,
+                v.attacked_by,
+                pieces_list(v, board, board.attacks_to(sq, !color, occupied)),
+                v.defended_by,
+                pieces_list(v, board, board.attacks_to(sq, color, occupied)),
+            )
+        }
+        PositionQuery::Hanging => format!(
+            "{}: {}.",
+            v.hanging,
+            pieces_list(v, board, hanging_pieces(board))
+        ),
+    };
+    Ok(text)
+}
+
+/// Describes a position for blindfold play or a screen reader. The text is
+/// plain, so it can be spoken by any TTS provider.
+#[tauri::command]
+#[specta::specta]
+pub async fn describe_position(
+    source: PositionSource,
+    query: PositionQuery,
+    lang: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, Error> {
+    let fen = match source {
+        PositionSource::Fen(fen) => fen,
+        PositionSource::Game(game_id) => {
+            state
+                .game_manager
+                .get_game_state(&game_id)
+                .await?
+                .current_fen
+        }
+    };
+    let pos = parse_fen_to_position(&fen)?;
+    describe(&pos, &query, &lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe_fen(fen: &str, query: PositionQuery) -> String {
+        describe(&parse_fen_to_position(fen).unwrap(), &query, "en").unwrap()
+    }
+
+    const ITALIAN: &str = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3";
+
+    #[test]
+    fn pieces_by_side() {
+        let text = describe_fen(
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1",
+            PositionQuery::Pieces {
+                verbosity: Verbosity::Brief,
+            },
+        );
+        assert_eq!(
+            text,
+            "White to move.  White: King g1; Rook a1.  Black: King g8."
+        );
+
+        let text = describe_fen(
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1",
+            PositionQuery::Pieces {
+                verbosity: Verbosity::Normal,
+            },
+        );
+        assert_eq!(
+            text,
+            "White to move.  White: King g1; Rook a1; Pawn f2, g2, h2.  Black: King g8; Pawn f7, g7, h7."
+        );
+    }
+
+    #[test]
+    fn full_lists_hanging_pieces() {
+        let text = describe_fen(
+            "4k3/8/8/3n4/8/8/8/3QK3 b - - 0 1",
+            PositionQuery::Pieces {
+                verbosity: Verbosity::Full,
+            },
+        );
+        assert_eq!(
+            text,
+            "Black to move.  White: King e1; Queen d1.  Black: King e8; Knight d5.  Hanging: Black Knight d5."
+        );
+    }
+
+    #[test]
+    fn check_is_mentioned() {
+        let text = describe_fen(
+            "4k3/8/8/8/8/8/8/4R1K1 b - - 0 1",
+            PositionQuery::Pieces {
+                verbosity: Verbosity::Brief,
+            },
+        );
+        assert!(text.starts_with("Black to move, check."));
+    }
+
+    #[test]
+    fn file_and_rank_scans() {
+        assert_eq!(
+            describe_fen(
+                ITALIAN,
+                PositionQuery::File {
+                    file: Some("e".to_string())
+                }
+            ),
+            "e file: White King e1, White Pawn e4, Black Pawn e5, Black King e8."
+        );
+        assert_eq!(
+            describe_fen(
+                ITALIAN,
+                PositionQuery::Rank {
+                    rank: Some("3".to_string())
+                }
+            ),
+            "rank 3: White Knight f3."
+        );
+        let all = describe_fen(ITALIAN, PositionQuery::Rank { rank: None });
+        assert!(all.starts_with("rank 8: "));
+        assert!(all.contains("rank 4: White Bishop c4, White Pawn e4.  rank 3"));
+        assert!(describe(
+            &parse_fen_to_position(ITALIAN).unwrap(),
+            &PositionQuery::File {
+                file: Some("j".to_string())
+            },
+            "en"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn square_attackers_and_defenders() {
+        assert_eq!(
+            describe_fen(
+                ITALIAN,
+                PositionQuery::Square {
+                    square: "e5".to_string()
+                }
+            ),
+            "Black Pawn e5; attacked by White Knight f3; defended by Black Knight c6."
+        );
+        assert_eq!(
+            describe_fen(
+                ITALIAN,
+                PositionQuery::Square {
+                    square: "f7".to_string()
+                }
+            ),
+            "Black Pawn f7; attacked by White Bishop c4; defended by Black King e8."
+        );
+        assert_eq!(
+            describe_fen(
+                ITALIAN,
+                PositionQuery::Square {
+                    square: "f6".to_string()
+                }
+            ),
+            "f6 empty; attacked by nothing; defended by Black Pawn g7, Black Queen d8, Black Knight g8."
+        );
+    }
+
+    #[test]
+    fn hanging_in_other_languages() {
+        let pos = parse_fen_to_position("4k3/8/8/3n4/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(
+            describe(&pos, &PositionQuery::Hanging, "de").unwrap(),
+            "Ungedeckt: Schwarz Springer d5."
+        );
+        let pos = parse_fen_to_position("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            describe(&pos, &PositionQuery::Hanging, "en").unwrap(),
+            "Hanging: nothing."
+        );
+    }
+}
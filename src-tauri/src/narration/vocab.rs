@@ -0,0 +1,1577 @@
+use shakmaty::{Color, Role};
+
+/// Spoken chess vocabulary for a single language.
+pub struct ChessVocab {
+    pub king: &'static str,
+    pub queen: &'static str,
+    pub rook: &'static str,
+    pub bishop: &'static str,
+    pub knight: &'static str,
+    pub castles_kingside: &'static str,
+    pub castles_queenside: &'static str,
+    pub takes: &'static str,
+    pub check: &'static str,
+    pub checkmate: &'static str,
+    pub promotes_to: &'static str,
+    pub brilliant: &'static str,
+    pub good: &'static str,
+    pub interesting: &'static str,
+    pub dubious: &'static str,
+    pub mistake: &'static str,
+    pub blunder: &'static str,
+    pub white_wins: &'static str,
+    pub black_wins: &'static str,
+    pub draw: &'static str,
+    pub pawn: &'static str,
+    pub white: &'static str,
+    pub black: &'static str,
+    /// Side to move, `{}` is the side
+    pub to_move: &'static str,
+    /// `{}` is the file letter
+    pub file: &'static str,
+    /// `{}` is the rank number
+    pub rank: &'static str,
+    pub empty: &'static str,
+    pub attacked_by: &'static str,
+    pub defended_by: &'static str,
+    pub hanging: &'static str,
+    pub nothing: &'static str,
+    /// Spoken ranks one to eight
+    pub numbers: [&'static str; 8],
+    pub equal: &'static str,
+    /// `{}` is the side in the following three
+    pub slightly_better: &'static str,
+    pub clearly_better: &'static str,
+    pub winning: &'static str,
+    /// Side, then the number of moves
+    pub mates_in: &'static str,
+    /// `{}` is the evaluation in pawns
+    pub about: &'static str,
+    pub plus: &'static str,
+    pub minus: &'static str,
+    /// `{}` is the best move
+    pub best_is: &'static str,
+    pub then: &'static str,
+    /// White win, draw and Black win percentages
+    pub wdl: &'static str,
+    pub decimal: &'static str,
+    /// Side, then the remaining time, in the following two
+    pub seconds_left: &'static str,
+    pub minutes_left: &'static str,
+    /// `{}` is the side
+    pub one_minute_left: &'static str,
+    pub by_timeout: &'static str,
+    pub by_resignation: &'static str,
+    pub by_abandonment: &'static str,
+    pub stalemate: &'static str,
+    pub insufficient_material: &'static str,
+    pub repetition: &'static str,
+    pub fifty_moves: &'static str,
+    pub by_agreement: &'static str,
+    /// Audiobook chapter titles
+    pub opening: &'static str,
+    pub middlegame: &'static str,
+    pub endgame: &'static str,
+    pub variation: &'static str,
+}
+
+impl ChessVocab {
+    /// Spoken piece name. Pawns are never named in spoken SAN.
+    pub fn piece(&self, role: Role) -> &'static str {
+        match role {
+            Role::King => self.king,
+            Role::Queen => self.queen,
+            Role::Rook => self.rook,
+            Role::Bishop => self.bishop,
+            Role::Knight => self.knight,
+            Role::Pawn => "",
+        }
+    }
+
+    /// Piece name for describing a board, where pawns are named too.
+    pub fn piece_name(&self, role: Role) -> &'static str {
+        match role {
+            Role::Pawn => self.pawn,
+            role => self.piece(role),
+        }
+    }
+
+    pub fn side(&self, color: Color) -> &'static str {
+        color.fold_wb(self.white, self.black)
+    }
+}
+
+/// Returns the vocabulary for `lang`, falling back to English.
+///
+/// Region suffixes are ignored, so `pt-BR` and `pt` share a vocabulary.
+pub fn vocab(lang: &str) -> &'static ChessVocab {
+    let base = lang.split(['-', '_']).next().unwrap_or(lang);
+    VOCABS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(base))
+        .map(|(_, v)| v)
+        .unwrap_or(&VOCABS[0].1)
+}
+
+static VOCABS: &[(&str, ChessVocab)] = &[
+    (
+        "en",
+        ChessVocab {
+            king: "King",
+            queen: "Queen",
+            rook: "Rook",
+            bishop: "Bishop",
+            knight: "Knight",
+            castles_kingside: "castles kingside",
+            castles_queenside: "castles queenside",
+            takes: "takes",
+            check: "check",
+            checkmate: "checkmate",
+            promotes_to: "promotes to",
+            brilliant: "Brilliant move.",
+            good: "Good move.",
+            interesting: "Interesting move.",
+            dubious: "Dubious move.",
+            mistake: "Mistake.",
+            blunder: "Blunder.",
+            white_wins: "White wins.",
+            black_wins: "Black wins.",
+            draw: "Draw.",
+            pawn: "Pawn",
+            white: "White",
+            black: "Black",
+            to_move: "{} to move",
+            file: "{} file",
+            rank: "rank {}",
+            empty: "empty",
+            attacked_by: "attacked by",
+            defended_by: "defended by",
+            hanging: "Hanging",
+            nothing: "nothing",
+            numbers: [
+                "one", "two", "three", "four", "five", "six", "seven", "eight",
+            ],
+            equal: "The position is equal",
+            slightly_better: "{} is slightly better",
+            clearly_better: "{} is clearly better",
+            winning: "{} is winning",
+            mates_in: "{} mates in {}",
+            about: "about {}",
+            plus: "plus",
+            minus: "minus",
+            best_is: "best is {}",
+            then: "then",
+            wdl: "{} percent for White, {} percent draw, {} percent for Black",
+            decimal: ".",
+            seconds_left: "{}, {} seconds left",
+            minutes_left: "{}, {} minutes left",
+            one_minute_left: "{}, one minute left",
+            by_timeout: "On time",
+            by_resignation: "By resignation",
+            by_abandonment: "By abandonment",
+            stalemate: "Stalemate",
+            insufficient_material: "Insufficient material",
+            repetition: "Threefold repetition",
+            fifty_moves: "Fifty-move rule",
+            by_agreement: "By agreement",
+            opening: "Opening",
+            middlegame: "Middlegame",
+            endgame: "Endgame",
+            variation: "Variation",
+        },
+    ),
+    (
+        "fr",
+        ChessVocab {
+            king: "Roi",
+            queen: "Dame",
+            rook: "Tour",
+            bishop: "Fou",
+            knight: "Cavalier",
+            castles_kingside: "petit roque",
+            castles_queenside: "grand roque",
+            takes: "prend",
+            check: "échec",
+            checkmate: "échec et mat",
+            promotes_to: "promu en",
+            brilliant: "Coup brillant.",
+            good: "Bon coup.",
+            interesting: "Coup intéressant.",
+            dubious: "Coup douteux.",
+            mistake: "Erreur.",
+            blunder: "Gaffe.",
+            white_wins: "Les blancs gagnent.",
+            black_wins: "Les noirs gagnent.",
+            draw: "Partie nulle.",
+            pawn: "Pion",
+            white: "Blancs",
+            black: "Noirs",
+            to_move: "Trait aux {}",
+            file: "colonne {}",
+            rank: "rangée {}",
+            empty: "vide",
+            attacked_by: "attaqué par",
+            defended_by: "défendu par",
+            hanging: "En prise",
+            nothing: "rien",
+            numbers: [
+                "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit",
+            ],
+            equal: "La position est égale",
+            slightly_better: "Les {} sont légèrement mieux",
+            clearly_better: "Les {} sont nettement mieux",
+            winning: "Les {} gagnent",
+            mates_in: "Les {} matent en {}",
+            about: "environ {}",
+            plus: "plus",
+            minus: "moins",
+            best_is: "le meilleur coup est {}",
+            then: "puis",
+            wdl: "{} pour cent pour les Blancs, {} pour cent de nulle, {} pour cent pour les Noirs",
+            decimal: ",",
+            seconds_left: "{}, plus que {} secondes",
+            minutes_left: "{}, plus que {} minutes",
+            one_minute_left: "{}, plus qu'une minute",
+            by_timeout: "Au temps",
+            by_resignation: "Par abandon",
+            by_abandonment: "Par forfait",
+            stalemate: "Pat",
+            insufficient_material: "Matériel insuffisant",
+            repetition: "Triple répétition",
+            fifty_moves: "Règle des cinquante coups",
+            by_agreement: "Par accord mutuel",
+            opening: "Ouverture",
+            middlegame: "Milieu de partie",
+            endgame: "Finale",
+            variation: "Variante",
+        },
+    ),
+    (
+        "es",
+        ChessVocab {
+            king: "Rey",
+            queen: "Dama",
+            rook: "Torre",
+            bishop: "Alfil",
+            knight: "Caballo",
+            castles_kingside: "enroque corto",
+            castles_queenside: "enroque largo",
+            takes: "captura",
+            check: "jaque",
+            checkmate: "jaque mate",
+            promotes_to: "corona a",
+            brilliant: "Jugada brillante.",
+            good: "Buena jugada.",
+            interesting: "Jugada interesante.",
+            dubious: "Jugada dudosa.",
+            mistake: "Error.",
+            blunder: "Pifia.",
+            white_wins: "Ganan las blancas.",
+            black_wins: "Ganan las negras.",
+            draw: "Tablas.",
+            pawn: "Peón",
+            white: "Blancas",
+            black: "Negras",
+            to_move: "Juegan {}",
+            file: "columna {}",
+            rank: "fila {}",
+            empty: "vacía",
+            attacked_by: "atacado por",
+            defended_by: "defendido por",
+            hanging: "Colgando",
+            nothing: "nada",
+            numbers: [
+                "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho",
+            ],
+            equal: "La posición está igualada",
+            slightly_better: "Las {} están ligeramente mejor",
+            clearly_better: "Las {} están claramente mejor",
+            winning: "Las {} están ganando",
+            mates_in: "Las {} dan mate en {}",
+            about: "aproximadamente {}",
+            plus: "más",
+            minus: "menos",
+            best_is: "la mejor es {}",
+            then: "luego",
+            wdl: "{} por ciento para las Blancas, {} por ciento tablas, {} por ciento para las Negras",
+            decimal: ",",
+            seconds_left: "{}, quedan {} segundos",
+            minutes_left: "{}, quedan {} minutos",
+            one_minute_left: "{}, queda un minuto",
+            by_timeout: "Por tiempo",
+            by_resignation: "Por abandono",
+            by_abandonment: "Por incomparecencia",
+            stalemate: "Ahogado",
+            insufficient_material: "Material insuficiente",
+            repetition: "Triple repetición",
+            fifty_moves: "Regla de los cincuenta movimientos",
+            by_agreement: "Por acuerdo",
+            opening: "Apertura",
+            middlegame: "Medio juego",
+            endgame: "Final",
+            variation: "Variante",
+        },
+    ),
+    (
+        "de",
+        ChessVocab {
+            king: "König",
+            queen: "Dame",
+            rook: "Turm",
+            bishop: "Läufer",
+            knight: "Springer",
+            castles_kingside: "kurze Rochade",
+            castles_queenside: "lange Rochade",
+            takes: "schlägt",
+            check: "Schach",
+            checkmate: "Schachmatt",
+            promotes_to: "wandelt um in",
+            brilliant: "Brillanter Zug.",
+            good: "Guter Zug.",
+            interesting: "Interessanter Zug.",
+            dubious: "Zweifelhafter Zug.",
+            mistake: "Fehler.",
+            blunder: "Grober Fehler.",
+            white_wins: "Weiß gewinnt.",
+            black_wins: "Schwarz gewinnt.",
+            draw: "Remis.",
+            pawn: "Bauer",
+            white: "Weiß",
+            black: "Schwarz",
+            to_move: "{} am Zug",
+            file: "{}-Linie",
+            rank: "{}. Reihe",
+            empty: "leer",
+            attacked_by: "angegriffen von",
+            defended_by: "gedeckt von",
+            hanging: "Ungedeckt",
+            nothing: "nichts",
+            numbers: [
+                "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht",
+            ],
+            equal: "Die Stellung ist ausgeglichen",
+            slightly_better: "{} steht leicht besser",
+            clearly_better: "{} steht klar besser",
+            winning: "{} steht auf Gewinn",
+            mates_in: "{} setzt matt in {}",
+            about: "etwa {}",
+            plus: "plus",
+            minus: "minus",
+            best_is: "am besten ist {}",
+            then: "dann",
+            wdl: "{} Prozent für Weiß, {} Prozent Remis, {} Prozent für Schwarz",
+            decimal: ",",
+            seconds_left: "{}, noch {} Sekunden",
+            minutes_left: "{}, noch {} Minuten",
+            one_minute_left: "{}, noch eine Minute",
+            by_timeout: "Durch Zeitüberschreitung",
+            by_resignation: "Durch Aufgabe",
+            by_abandonment: "Durch Abbruch",
+            stalemate: "Patt",
+            insufficient_material: "Ungenügendes Material",
+            repetition: "Dreifache Stellungswiederholung",
+            fifty_moves: "Fünfzig-Züge-Regel",
+            by_agreement: "Durch Einigung",
+            opening: "Eröffnung",
+            middlegame: "Mittelspiel",
+            endgame: "Endspiel",
+            variation: "Variante",
+        },
+    ),
+    (
+        "ja",
+        ChessVocab {
+            king: "キング",
+            queen: "クイーン",
+            rook: "ルーク",
+            bishop: "ビショップ",
+            knight: "ナイト",
+            castles_kingside: "キングサイドキャスリング",
+            castles_queenside: "クイーンサイドキャスリング",
+            takes: "テイクス",
+            check: "チェック",
+            checkmate: "チェックメイト",
+            promotes_to: "プロモーション",
+            brilliant: "素晴らしい手。",
+            good: "好手。",
+            interesting: "興味深い手。",
+            dubious: "疑問手。",
+            mistake: "悪手。",
+            blunder: "大悪手。",
+            white_wins: "白の勝ち。",
+            black_wins: "黒の勝ち。",
+            draw: "引き分け。",
+            pawn: "ポーン",
+            white: "白",
+            black: "黒",
+            to_move: "{}の手番",
+            file: "{}ファイル",
+            rank: "{}ランク",
+            empty: "空",
+            attacked_by: "攻撃している駒",
+            defended_by: "守っている駒",
+            hanging: "タダ取りされる駒",
+            nothing: "なし",
+            numbers: ["一", "二", "三", "四", "五", "六", "七", "八"],
+            equal: "形勢は互角",
+            slightly_better: "{}がやや有利",
+            clearly_better: "{}が優勢",
+            winning: "{}が勝勢",
+            mates_in: "{}が{}手で詰み",
+            about: "約{}",
+            plus: "プラス",
+            minus: "マイナス",
+            best_is: "最善手は{}",
+            then: "続いて",
+            wdl: "白の勝率{}パーセント、引き分け{}パーセント、黒の勝率{}パーセント",
+            decimal: ".",
+            seconds_left: "{}、残り{}秒",
+            minutes_left: "{}、残り{}分",
+            one_minute_left: "{}、残り1分",
+            by_timeout: "時間切れ",
+            by_resignation: "投了",
+            by_abandonment: "放棄",
+            stalemate: "ステイルメイト",
+            insufficient_material: "戦力不足",
+            repetition: "三回同一局面",
+            fifty_moves: "50手ルール",
+            by_agreement: "合意",
+            opening: "序盤",
+            middlegame: "中盤",
+            endgame: "終盤",
+            variation: "変化",
+        },
+    ),
+    (
+        "ru",
+        ChessVocab {
+            king: "Король",
+            queen: "Ферзь",
+            rook: "Ладья",
+            bishop: "Слон",
+            knight: "Конь",
+            castles_kingside: "рокировка на королевский фланг",
+            castles_queenside: "рокировка на ферзевый фланг",
+            takes: "берёт",
+            check: "шах",
+            checkmate: "мат",
+            promotes_to: "превращается в",
+            brilliant: "Блестящий ход.",
+            good: "Хороший ход.",
+            interesting: "Интересный ход.",
+            dubious: "Сомнительный ход.",
+            mistake: "Ошибка.",
+            blunder: "Грубая ошибка.",
+            white_wins: "Белые выиграли.",
+            black_wins: "Чёрные выиграли.",
+            draw: "Ничья.",
+            pawn: "Пешка",
+            white: "Белые",
+            black: "Чёрные",
+            to_move: "Ход: {}",
+            file: "вертикаль {}",
+            rank: "горизонталь {}",
+            empty: "пусто",
+            attacked_by: "атакован",
+            defended_by: "защищён",
+            hanging: "Под боем",
+            nothing: "ничем",
+            numbers: [
+                "один",
+                "два",
+                "три",
+                "четыре",
+                "пять",
+                "шесть",
+                "семь",
+                "восемь",
+            ],
+            equal: "Позиция равная",
+            slightly_better: "{} стоят немного лучше",
+            clearly_better: "{} стоят явно лучше",
+            winning: "{} выигрывают",
+            mates_in: "{} дают мат в {}",
+            about: "около {}",
+            plus: "плюс",
+            minus: "минус",
+            best_is: "лучший ход {}",
+            then: "затем",
+            wdl: "{} процентов за белых, {} процентов ничья, {} процентов за чёрных",
+            decimal: ",",
+            seconds_left: "{}, осталось {} секунд",
+            minutes_left: "{}, осталось {} минут",
+            one_minute_left: "{}, осталась одна минута",
+            by_timeout: "По времени",
+            by_resignation: "Сдача",
+            by_abandonment: "Уход из игры",
+            stalemate: "Пат",
+            insufficient_material: "Недостаточно материала",
+            repetition: "Троекратное повторение",
+            fifty_moves: "Правило пятидесяти ходов",
+            by_agreement: "По соглашению",
+            opening: "Дебют",
+            middlegame: "Миттельшпиль",
+            endgame: "Эндшпиль",
+            variation: "Вариант",
+        },
+    ),
+    (
+        "zh",
+        ChessVocab {
+            king: "王",
+            queen: "后",
+            rook: "车",
+            bishop: "象",
+            knight: "马",
+            castles_kingside: "王翼易位",
+            castles_queenside: "后翼易位",
+            takes: "吃",
+            check: "将军",
+            checkmate: "将杀",
+            promotes_to: "升变为",
+            brilliant: "妙招。",
+            good: "好棋。",
+            interesting: "有趣的棋。",
+            dubious: "可疑的棋。",
+            mistake: "错着。",
+            blunder: "漏着。",
+            white_wins: "白方胜。",
+            black_wins: "黑方胜。",
+            draw: "和棋。",
+            pawn: "兵",
+            white: "白方",
+            black: "黑方",
+            to_move: "{}走棋",
+            file: "{}线",
+            rank: "第{}横排",
+            empty: "空",
+            attacked_by: "被攻击",
+            defended_by: "被保护",
+            hanging: "无保护",
+            nothing: "无",
+            numbers: ["一", "二", "三", "四", "五", "六", "七", "八"],
+            equal: "局面均势",
+            slightly_better: "{}稍优",
+            clearly_better: "{}明显占优",
+            winning: "{}胜势",
+            mates_in: "{}{}步杀",
+            about: "约{}",
+            plus: "正",
+            minus: "负",
+            best_is: "最佳着法是{}",
+            then: "然后",
+            wdl: "白方胜率百分之{}，和棋百分之{}，黑方胜率百分之{}",
+            decimal: ".",
+            seconds_left: "{}，剩余{}秒",
+            minutes_left: "{}，剩余{}分钟",
+            one_minute_left: "{}，剩余一分钟",
+            by_timeout: "超时",
+            by_resignation: "认输",
+            by_abandonment: "弃赛",
+            stalemate: "逼和",
+            insufficient_material: "子力不足",
+            repetition: "三次重复局面",
+            fifty_moves: "五十步规则",
+            by_agreement: "协议和棋",
+            opening: "开局",
+            middlegame: "中局",
+            endgame: "残局",
+            variation: "变化",
+        },
+    ),
+    (
+        "ko",
+        ChessVocab {
+            king: "킹",
+            queen: "퀸",
+            rook: "룩",
+            bishop: "비숍",
+            knight: "나이트",
+            castles_kingside: "킹사이드 캐슬링",
+            castles_queenside: "퀸사이드 캐슬링",
+            takes: "잡음",
+            check: "체크",
+            checkmate: "체크메이트",
+            promotes_to: "프로모션",
+            brilliant: "brilliant한 수.",
+            good: "좋은 수.",
+            interesting: "흥미로운 수.",
+            dubious: "의심스러운 수.",
+            mistake: "실수.",
+            blunder: "대실수.",
+            white_wins: "백 승리.",
+            black_wins: "흑 승리.",
+            draw: "무승부.",
+            pawn: "폰",
+            white: "백",
+            black: "흑",
+            to_move: "{} 차례",
+            file: "{} 파일",
+            rank: "{} 랭크",
+            empty: "비어 있음",
+            attacked_by: "공격하는 기물",
+            defended_by: "지키는 기물",
+            hanging: "무방비",
+            nothing: "없음",
+            numbers: ["일", "이", "삼", "사", "오", "육", "칠", "팔"],
+            equal: "형세는 균형",
+            slightly_better: "{}이 약간 유리",
+            clearly_better: "{}이 확실히 유리",
+            winning: "{}이 승세",
+            mates_in: "{}이 {}수 만에 메이트",
+            about: "약 {}",
+            plus: "플러스",
+            minus: "마이너스",
+            best_is: "최선수는 {}",
+            then: "그다음",
+            wdl: "백 승률 {}퍼센트, 무승부 {}퍼센트, 흑 승률 {}퍼센트",
+            decimal: ".",
+            seconds_left: "{}, {}초 남음",
+            minutes_left: "{}, {}분 남음",
+            one_minute_left: "{}, 1분 남음",
+            by_timeout: "시간 초과",
+            by_resignation: "기권",
+            by_abandonment: "경기 포기",
+            stalemate: "스테일메이트",
+            insufficient_material: "기물 부족",
+            repetition: "3회 동형 반복",
+            fifty_moves: "50수 규칙",
+            by_agreement: "합의",
+            opening: "오프닝",
+            middlegame: "미들게임",
+            endgame: "엔드게임",
+            variation: "변화",
+        },
+    ),
+    (
+        "hi",
+        ChessVocab {
+            king: "राजा",
+            queen: "वज़ीर",
+            rook: "हाथी",
+            bishop: "ऊँट",
+            knight: "घोड़ा",
+            castles_kingside: "किंगसाइड कैसलिंग",
+            castles_queenside: "क्वीनसाइड कैसलिंग",
+            takes: "मारता है",
+            check: "शह",
+            checkmate: "शह और मात",
+            promotes_to: "में बदलता है",
+            brilliant: "शानदार चाल।",
+            good: "अच्छी चाल।",
+            interesting: "दिलचस्प चाल।",
+            dubious: "संदिग्ध चाल।",
+            mistake: "गलती।",
+            blunder: "बड़ी गलती।",
+            white_wins: "सफ़ेद जीता।",
+            black_wins: "काला जीता।",
+            draw: "ड्रॉ।",
+            pawn: "प्यादा",
+            white: "सफ़ेद",
+            black: "काला",
+            to_move: "{} की चाल",
+            file: "{} फ़ाइल",
+            rank: "{} रैंक",
+            empty: "खाली",
+            attacked_by: "हमला",
+            defended_by: "रक्षा",
+            hanging: "असुरक्षित",
+            nothing: "कोई नहीं",
+            numbers: ["एक", "दो", "तीन", "चार", "पाँच", "छह", "सात", "आठ"],
+            equal: "स्थिति बराबर है",
+            slightly_better: "{} थोड़ा बेहतर है",
+            clearly_better: "{} स्पष्ट रूप से बेहतर है",
+            winning: "{} जीत रहा है",
+            mates_in: "{} {} चाल में मात देता है",
+            about: "लगभग {}",
+            plus: "प्लस",
+            minus: "माइनस",
+            best_is: "सबसे अच्छी चाल {} है",
+            then: "फिर",
+            wdl: "सफ़ेद के लिए {} प्रतिशत, ड्रॉ {} प्रतिशत, काले के लिए {} प्रतिशत",
+            decimal: ".",
+            seconds_left: "{}, {} सेकंड बचे",
+            minutes_left: "{}, {} मिनट बचे",
+            one_minute_left: "{}, एक मिनट बचा",
+            by_timeout: "समय समाप्त",
+            by_resignation: "हार मानकर",
+            by_abandonment: "खेल छोड़कर",
+            stalemate: "स्टेलमेट",
+            insufficient_material: "अपर्याप्त मोहरे",
+            repetition: "तीन बार दोहराव",
+            fifty_moves: "पचास चाल नियम",
+            by_agreement: "सहमति से",
+            opening: "ओपनिंग",
+            middlegame: "मिडिलगेम",
+            endgame: "एंडगेम",
+            variation: "वेरिएशन",
+        },
+    ),
+    (
+        "it",
+        ChessVocab {
+            king: "Re",
+            queen: "Donna",
+            rook: "Torre",
+            bishop: "Alfiere",
+            knight: "Cavallo",
+            castles_kingside: "arrocco corto",
+            castles_queenside: "arrocco lungo",
+            takes: "cattura",
+            check: "scacco",
+            checkmate: "scacco matto",
+            promotes_to: "promuove a",
+            brilliant: "Mossa brillante.",
+            good: "Buona mossa.",
+            interesting: "Mossa interessante.",
+            dubious: "Mossa dubbia.",
+            mistake: "Errore.",
+            blunder: "Errore grave.",
+            white_wins: "Vince il Bianco.",
+            black_wins: "Vince il Nero.",
+            draw: "Patta.",
+            pawn: "Pedone",
+            white: "Bianco",
+            black: "Nero",
+            to_move: "Muove il {}",
+            file: "colonna {}",
+            rank: "traversa {}",
+            empty: "vuota",
+            attacked_by: "attaccato da",
+            defended_by: "difeso da",
+            hanging: "In presa",
+            nothing: "niente",
+            numbers: [
+                "uno", "due", "tre", "quattro", "cinque", "sei", "sette", "otto",
+            ],
+            equal: "La posizione è pari",
+            slightly_better: "Il {} sta leggermente meglio",
+            clearly_better: "Il {} sta nettamente meglio",
+            winning: "Il {} sta vincendo",
+            mates_in: "Il {} dà matto in {}",
+            about: "circa {}",
+            plus: "più",
+            minus: "meno",
+            best_is: "la mossa migliore è {}",
+            then: "poi",
+            wdl: "{} per cento per il Bianco, {} per cento patta, {} per cento per il Nero",
+            decimal: ",",
+            seconds_left: "{}, restano {} secondi",
+            minutes_left: "{}, restano {} minuti",
+            one_minute_left: "{}, resta un minuto",
+            by_timeout: "Per tempo",
+            by_resignation: "Per abbandono",
+            by_abandonment: "Per forfait",
+            stalemate: "Stallo",
+            insufficient_material: "Materiale insufficiente",
+            repetition: "Triplice ripetizione",
+            fifty_moves: "Regola delle cinquanta mosse",
+            by_agreement: "Per accordo",
+            opening: "Apertura",
+            middlegame: "Mediogioco",
+            endgame: "Finale",
+            variation: "Variante",
+        },
+    ),
+    (
+        "pt",
+        ChessVocab {
+            king: "Rei",
+            queen: "Dama",
+            rook: "Torre",
+            bishop: "Bispo",
+            knight: "Cavalo",
+            castles_kingside: "roque menor",
+            castles_queenside: "roque maior",
+            takes: "captura",
+            check: "xeque",
+            checkmate: "xeque-mate",
+            promotes_to: "promove a",
+            brilliant: "Jogada brilhante.",
+            good: "Boa jogada.",
+            interesting: "Jogada interessante.",
+            dubious: "Jogada duvidosa.",
+            mistake: "Erro.",
+            blunder: "Erro grave.",
+            white_wins: "As brancas vencem.",
+            black_wins: "As pretas vencem.",
+            draw: "Empate.",
+            pawn: "Peão",
+            white: "Brancas",
+            black: "Pretas",
+            to_move: "Jogam as {}",
+            file: "coluna {}",
+            rank: "fileira {}",
+            empty: "vazia",
+            attacked_by: "atacado por",
+            defended_by: "defendido por",
+            hanging: "Pendurado",
+            nothing: "nada",
+            numbers: [
+                "um", "dois", "três", "quatro", "cinco", "seis", "sete", "oito",
+            ],
+            equal: "A posição está igual",
+            slightly_better: "As {} estão ligeiramente melhor",
+            clearly_better: "As {} estão claramente melhor",
+            winning: "As {} estão ganhando",
+            mates_in: "As {} dão mate em {}",
+            about: "cerca de {}",
+            plus: "mais",
+            minus: "menos",
+            best_is: "o melhor lance é {}",
+            then: "depois",
+            wdl: "{} por cento para as Brancas, {} por cento empate, {} por cento para as Pretas",
+            decimal: ",",
+            seconds_left: "{}, restam {} segundos",
+            minutes_left: "{}, restam {} minutos",
+            one_minute_left: "{}, resta um minuto",
+            by_timeout: "Por tempo",
+            by_resignation: "Por desistência",
+            by_abandonment: "Por abandono",
+            stalemate: "Afogamento",
+            insufficient_material: "Material insuficiente",
+            repetition: "Tripla repetição",
+            fifty_moves: "Regra dos cinquenta lances",
+            by_agreement: "Por acordo",
+            opening: "Abertura",
+            middlegame: "Meio-jogo",
+            endgame: "Final",
+            variation: "Variante",
+        },
+    ),
+    (
+        "nl",
+        ChessVocab {
+            king: "Koning",
+            queen: "Dame",
+            rook: "Toren",
+            bishop: "Loper",
+            knight: "Paard",
+            castles_kingside: "korte rokade",
+            castles_queenside: "lange rokade",
+            takes: "slaat",
+            check: "schaak",
+            checkmate: "schaakmat",
+            promotes_to: "promoveert tot",
+            brilliant: "Briljante zet.",
+            good: "Goede zet.",
+            interesting: "Interessante zet.",
+            dubious: "Twijfelachtige zet.",
+            mistake: "Fout.",
+            blunder: "Blunder.",
+            white_wins: "Wit wint.",
+            black_wins: "Zwart wint.",
+            draw: "Remise.",
+            pawn: "Pion",
+            white: "Wit",
+            black: "Zwart",
+            to_move: "{} aan zet",
+            file: "{}-lijn",
+            rank: "{}e rij",
+            empty: "leeg",
+            attacked_by: "aangevallen door",
+            defended_by: "gedekt door",
+            hanging: "Hangend",
+            nothing: "niets",
+            numbers: [
+                "een", "twee", "drie", "vier", "vijf", "zes", "zeven", "acht",
+            ],
+            equal: "De stelling is gelijk",
+            slightly_better: "{} staat iets beter",
+            clearly_better: "{} staat duidelijk beter",
+            winning: "{} staat gewonnen",
+            mates_in: "{} geeft mat in {}",
+            about: "ongeveer {}",
+            plus: "plus",
+            minus: "min",
+            best_is: "de beste zet is {}",
+            then: "dan",
+            wdl: "{} procent voor Wit, {} procent remise, {} procent voor Zwart",
+            decimal: ",",
+            seconds_left: "{}, nog {} seconden",
+            minutes_left: "{}, nog {} minuten",
+            one_minute_left: "{}, nog één minuut",
+            by_timeout: "Door tijdsoverschrijding",
+            by_resignation: "Door opgave",
+            by_abandonment: "Door verlaten",
+            stalemate: "Pat",
+            insufficient_material: "Onvoldoende materiaal",
+            repetition: "Drievoudige herhaling",
+            fifty_moves: "Vijftigzettenregel",
+            by_agreement: "Door overeenkomst",
+            opening: "Opening",
+            middlegame: "Middenspel",
+            endgame: "Eindspel",
+            variation: "Variant",
+        },
+    ),
+    (
+        "pl",
+        ChessVocab {
+            king: "Król",
+            queen: "Hetman",
+            rook: "Wieża",
+            bishop: "Goniec",
+            knight: "Skoczek",
+            castles_kingside: "roszada krótka",
+            castles_queenside: "roszada długa",
+            takes: "bije",
+            check: "szach",
+            checkmate: "szach-mat",
+            promotes_to: "promuje na",
+            brilliant: "Świetne posunięcie.",
+            good: "Dobre posunięcie.",
+            interesting: "Ciekawe posunięcie.",
+            dubious: "Wątpliwe posunięcie.",
+            mistake: "Błąd.",
+            blunder: "Poważny błąd.",
+            white_wins: "Białe wygrywają.",
+            black_wins: "Czarne wygrywają.",
+            draw: "Remis.",
+            pawn: "Pion",
+            white: "Białe",
+            black: "Czarne",
+            to_move: "Ruch: {}",
+            file: "kolumna {}",
+            rank: "rząd {}",
+            empty: "puste",
+            attacked_by: "atakowane przez",
+            defended_by: "bronione przez",
+            hanging: "Bez obrony",
+            nothing: "nic",
+            numbers: [
+                "jeden", "dwa", "trzy", "cztery", "pięć", "sześć", "siedem", "osiem",
+            ],
+            equal: "Pozycja jest równa",
+            slightly_better: "{} stoją nieco lepiej",
+            clearly_better: "{} stoją wyraźnie lepiej",
+            winning: "{} wygrywają",
+            mates_in: "{} dają mata w {}",
+            about: "około {}",
+            plus: "plus",
+            minus: "minus",
+            best_is: "najlepszy jest {}",
+            then: "potem",
+            wdl: "{} procent dla białych, {} procent remis, {} procent dla czarnych",
+            decimal: ",",
+            seconds_left: "{}, zostało {} sekund",
+            minutes_left: "{}, zostało {} minut",
+            one_minute_left: "{}, została minuta",
+            by_timeout: "Na czas",
+            by_resignation: "Przez poddanie",
+            by_abandonment: "Przez porzucenie",
+            stalemate: "Pat",
+            insufficient_material: "Niewystarczający materiał",
+            repetition: "Trzykrotne powtórzenie",
+            fifty_moves: "Zasada pięćdziesięciu ruchów",
+            by_agreement: "Za porozumieniem",
+            opening: "Debiut",
+            middlegame: "Gra środkowa",
+            endgame: "Końcówka",
+            variation: "Wariant",
+        },
+    ),
+    (
+        "tr",
+        ChessVocab {
+            king: "Şah",
+            queen: "Vezir",
+            rook: "Kale",
+            bishop: "Fil",
+            knight: "At",
+            castles_kingside: "kısa rok",
+            castles_queenside: "uzun rok",
+            takes: "alır",
+            check: "şah",
+            checkmate: "şah mat",
+            promotes_to: "terfi eder",
+            brilliant: "Harika hamle.",
+            good: "İyi hamle.",
+            interesting: "İlginç hamle.",
+            dubious: "Şüpheli hamle.",
+            mistake: "Hata.",
+            blunder: "Büyük hata.",
+            white_wins: "Beyaz kazandı.",
+            black_wins: "Siyah kazandı.",
+            draw: "Berabere.",
+            pawn: "Piyon",
+            white: "Beyaz",
+            black: "Siyah",
+            to_move: "Hamle sırası: {}",
+            file: "{} sütunu",
+            rank: "{}. yatay",
+            empty: "boş",
+            attacked_by: "saldıran",
+            defended_by: "koruyan",
+            hanging: "Korumasız",
+            nothing: "hiçbiri",
+            numbers: ["bir", "iki", "üç", "dört", "beş", "altı", "yedi", "sekiz"],
+            equal: "Pozisyon eşit",
+            slightly_better: "{} biraz daha iyi",
+            clearly_better: "{} açıkça daha iyi",
+            winning: "{} kazanıyor",
+            mates_in: "{} {} hamlede mat ediyor",
+            about: "yaklaşık {}",
+            plus: "artı",
+            minus: "eksi",
+            best_is: "en iyi hamle {}",
+            then: "sonra",
+            wdl: "Beyaz için yüzde {}, beraberlik yüzde {}, Siyah için yüzde {}",
+            decimal: ",",
+            seconds_left: "{}, {} saniye kaldı",
+            minutes_left: "{}, {} dakika kaldı",
+            one_minute_left: "{}, bir dakika kaldı",
+            by_timeout: "Süre bitti",
+            by_resignation: "Terk",
+            by_abandonment: "Oyunu bırakma",
+            stalemate: "Pat",
+            insufficient_material: "Yetersiz materyal",
+            repetition: "Üçlü tekrar",
+            fifty_moves: "Elli hamle kuralı",
+            by_agreement: "Anlaşma",
+            opening: "Açılış",
+            middlegame: "Oyun ortası",
+            endgame: "Oyun sonu",
+            variation: "Varyant",
+        },
+    ),
+    (
+        "ar",
+        ChessVocab {
+            king: "ملك",
+            queen: "وزير",
+            rook: "رخ",
+            bishop: "فيل",
+            knight: "حصان",
+            castles_kingside: "تبييت قصير",
+            castles_queenside: "تبييت طويل",
+            takes: "يأخذ",
+            check: "كش",
+            checkmate: "كش ملك",
+            promotes_to: "يترقى إلى",
+            brilliant: "نقلة رائعة.",
+            good: "نقلة جيدة.",
+            interesting: "نقلة مثيرة.",
+            dubious: "نقلة مشكوك فيها.",
+            mistake: "خطأ.",
+            blunder: "خطأ فادح.",
+            white_wins: "الأبيض يفوز.",
+            black_wins: "الأسود يفوز.",
+            draw: "تعادل.",
+            pawn: "بيدق",
+            white: "الأبيض",
+            black: "الأسود",
+            to_move: "الدور على {}",
+            file: "العمود {}",
+            rank: "الصف {}",
+            empty: "فارغ",
+            attacked_by: "مهاجم من",
+            defended_by: "محمي من",
+            hanging: "غير محمي",
+            nothing: "لا شيء",
+            numbers: [
+                "واحد",
+                "اثنان",
+                "ثلاثة",
+                "أربعة",
+                "خمسة",
+                "ستة",
+                "سبعة",
+                "ثمانية",
+            ],
+            equal: "الموقف متكافئ",
+            slightly_better: "{} أفضل قليلاً",
+            clearly_better: "{} أفضل بوضوح",
+            winning: "{} يفوز",
+            mates_in: "{} يكش مات في {}",
+            about: "حوالي {}",
+            plus: "زائد",
+            minus: "ناقص",
+            best_is: "أفضل نقلة {}",
+            then: "ثم",
+            wdl: "{} بالمئة للأبيض، {} بالمئة تعادل، {} بالمئة للأسود",
+            decimal: ".",
+            seconds_left: "{}، بقي {} ثانية",
+            minutes_left: "{}، بقي {} دقائق",
+            one_minute_left: "{}، بقيت دقيقة واحدة",
+            by_timeout: "بانتهاء الوقت",
+            by_resignation: "بالاستسلام",
+            by_abandonment: "بالانسحاب",
+            stalemate: "تعادل بالجمود",
+            insufficient_material: "مادة غير كافية",
+            repetition: "التكرار الثلاثي",
+            fifty_moves: "قاعدة الخمسين نقلة",
+            by_agreement: "بالاتفاق",
+            opening: "الافتتاح",
+            middlegame: "وسط اللعبة",
+            endgame: "نهاية اللعبة",
+            variation: "تفريعة",
+        },
+    ),
+    (
+        "sv",
+        ChessVocab {
+            king: "Kung",
+            queen: "Dam",
+            rook: "Torn",
+            bishop: "Löpare",
+            knight: "Springare",
+            castles_kingside: "kort rockad",
+            castles_queenside: "lång rockad",
+            takes: "slår",
+            check: "schack",
+            checkmate: "schack matt",
+            promotes_to: "uppflyttning till",
+            brilliant: "Briljant drag.",
+            good: "Bra drag.",
+            interesting: "Intressant drag.",
+            dubious: "Tveksamt drag.",
+            mistake: "Misstag.",
+            blunder: "Grovt misstag.",
+            white_wins: "Vit vinner.",
+            black_wins: "Svart vinner.",
+            draw: "Remi.",
+            pawn: "Bonde",
+            white: "Vit",
+            black: "Svart",
+            to_move: "{} vid draget",
+            file: "{}-linjen",
+            rank: "rad {}",
+            empty: "tom",
+            attacked_by: "anfallen av",
+            defended_by: "skyddad av",
+            hanging: "Hängande",
+            nothing: "ingenting",
+            numbers: ["ett", "två", "tre", "fyra", "fem", "sex", "sju", "åtta"],
+            equal: "Ställningen är jämn",
+            slightly_better: "{} står något bättre",
+            clearly_better: "{} står klart bättre",
+            winning: "{} står till vinst",
+            mates_in: "{} gör matt i {}",
+            about: "ungefär {}",
+            plus: "plus",
+            minus: "minus",
+            best_is: "bäst är {}",
+            then: "sedan",
+            wdl: "{} procent för Vit, {} procent remi, {} procent för Svart",
+            decimal: ",",
+            seconds_left: "{}, {} sekunder kvar",
+            minutes_left: "{}, {} minuter kvar",
+            one_minute_left: "{}, en minut kvar",
+            by_timeout: "På tid",
+            by_resignation: "Genom uppgivet parti",
+            by_abandonment: "Genom övergivet parti",
+            stalemate: "Patt",
+            insufficient_material: "Otillräckligt material",
+            repetition: "Trefaldig upprepning",
+            fifty_moves: "Femtiodragsregeln",
+            by_agreement: "Genom överenskommelse",
+            opening: "Öppning",
+            middlegame: "Mittspel",
+            endgame: "Slutspel",
+            variation: "Variant",
+        },
+    ),
+    (
+        "no",
+        ChessVocab {
+            king: "Konge",
+            queen: "Dronning",
+            rook: "Tårn",
+            bishop: "Løper",
+            knight: "Springer",
+            castles_kingside: "kort rokade",
+            castles_queenside: "lang rokade",
+            takes: "slår",
+            check: "sjakk",
+            checkmate: "sjakk matt",
+            promotes_to: "forvandling til",
+            brilliant: "Strålende trekk.",
+            good: "Godt trekk.",
+            interesting: "Interessant trekk.",
+            dubious: "Tvilsomt trekk.",
+            mistake: "Feil.",
+            blunder: "Grov feil.",
+            white_wins: "Hvit vinner.",
+            black_wins: "Svart vinner.",
+            draw: "Remis.",
+            pawn: "Bonde",
+            white: "Hvit",
+            black: "Svart",
+            to_move: "{} i trekket",
+            file: "{}-linjen",
+            rank: "rad {}",
+            empty: "tom",
+            attacked_by: "angrepet av",
+            defended_by: "dekket av",
+            hanging: "Henger",
+            nothing: "ingenting",
+            numbers: ["en", "to", "tre", "fire", "fem", "seks", "sju", "åtte"],
+            equal: "Stillingen er lik",
+            slightly_better: "{} står litt bedre",
+            clearly_better: "{} står klart bedre",
+            winning: "{} vinner",
+            mates_in: "{} setter matt i {}",
+            about: "omtrent {}",
+            plus: "pluss",
+            minus: "minus",
+            best_is: "best er {}",
+            then: "deretter",
+            wdl: "{} prosent for Hvit, {} prosent remis, {} prosent for Svart",
+            decimal: ",",
+            seconds_left: "{}, {} sekunder igjen",
+            minutes_left: "{}, {} minutter igjen",
+            one_minute_left: "{}, ett minutt igjen",
+            by_timeout: "På tid",
+            by_resignation: "Ved oppgivelse",
+            by_abandonment: "Ved forlatt parti",
+            stalemate: "Patt",
+            insufficient_material: "Utilstrekkelig materiell",
+            repetition: "Trekkgjentakelse",
+            fifty_moves: "Femtitrekksregelen",
+            by_agreement: "Ved enighet",
+            opening: "Åpning",
+            middlegame: "Midtspill",
+            endgame: "Sluttspill",
+            variation: "Variant",
+        },
+    ),
+    (
+        "da",
+        ChessVocab {
+            king: "Konge",
+            queen: "Dronning",
+            rook: "Tårn",
+            bishop: "Løber",
+            knight: "Springer",
+            castles_kingside: "kort rokade",
+            castles_queenside: "lang rokade",
+            takes: "slår",
+            check: "skak",
+            checkmate: "skakmat",
+            promotes_to: "forvandling til",
+            brilliant: "Brillant træk.",
+            good: "Godt træk.",
+            interesting: "Interessant træk.",
+            dubious: "Tvivlsomt træk.",
+            mistake: "Fejl.",
+            blunder: "Grov fejl.",
+            white_wins: "Hvid vinder.",
+            black_wins: "Sort vinder.",
+            draw: "Remis.",
+            pawn: "Bonde",
+            white: "Hvid",
+            black: "Sort",
+            to_move: "{} i trækket",
+            file: "{}-linjen",
+            rank: "række {}",
+            empty: "tom",
+            attacked_by: "angrebet af",
+            defended_by: "dækket af",
+            hanging: "Hænger",
+            nothing: "intet",
+            numbers: ["en", "to", "tre", "fire", "fem", "seks", "syv", "otte"],
+            equal: "Stillingen er lige",
+            slightly_better: "{} står lidt bedre",
+            clearly_better: "{} står klart bedre",
+            winning: "{} vinder",
+            mates_in: "{} sætter mat i {}",
+            about: "omkring {}",
+            plus: "plus",
+            minus: "minus",
+            best_is: "bedst er {}",
+            then: "derefter",
+            wdl: "{} procent til Hvid, {} procent remis, {} procent til Sort",
+            decimal: ",",
+            seconds_left: "{}, {} sekunder tilbage",
+            minutes_left: "{}, {} minutter tilbage",
+            one_minute_left: "{}, et minut tilbage",
+            by_timeout: "På tid",
+            by_resignation: "Ved opgivelse",
+            by_abandonment: "Ved forladt parti",
+            stalemate: "Pat",
+            insufficient_material: "Utilstrækkeligt materiale",
+            repetition: "Trefoldig gentagelse",
+            fifty_moves: "Halvtredstrækreglen",
+            by_agreement: "Ved aftale",
+            opening: "Åbning",
+            middlegame: "Midtspil",
+            endgame: "Slutspil",
+            variation: "Variant",
+        },
+    ),
+    (
+        "cs",
+        ChessVocab {
+            king: "Král",
+            queen: "Dáma",
+            rook: "Věž",
+            bishop: "Střelec",
+            knight: "Jezdec",
+            castles_kingside: "krátká rošáda",
+            castles_queenside: "dlouhá rošáda",
+            takes: "bere",
+            check: "šach",
+            checkmate: "šach mat",
+            promotes_to: "proměňuje na",
+            brilliant: "Brilantní tah.",
+            good: "Dobrý tah.",
+            interesting: "Zajímavý tah.",
+            dubious: "Pochybný tah.",
+            mistake: "Chyba.",
+            blunder: "Hrubá chyba.",
+            white_wins: "Bílý vyhrává.",
+            black_wins: "Černý vyhrává.",
+            draw: "Remíza.",
+            pawn: "Pěšec",
+            white: "Bílý",
+            black: "Černý",
+            to_move: "Na tahu: {}",
+            file: "sloupec {}",
+            rank: "řada {}",
+            empty: "prázdné",
+            attacked_by: "napaden",
+            defended_by: "krytý",
+            hanging: "Visí",
+            nothing: "nic",
+            numbers: ["jedna", "dva", "tři", "čtyři", "pět", "šest", "sedm", "osm"],
+            equal: "Pozice je vyrovnaná",
+            slightly_better: "{} stojí o něco lépe",
+            clearly_better: "{} stojí jasně lépe",
+            winning: "{} vyhrává",
+            mates_in: "{} dává mat v {}",
+            about: "přibližně {}",
+            plus: "plus",
+            minus: "mínus",
+            best_is: "nejlepší je {}",
+            then: "potom",
+            wdl: "{} procent pro bílého, {} procent remíza, {} procent pro černého",
+            decimal: ",",
+            seconds_left: "{}, zbývá {} sekund",
+            minutes_left: "{}, zbývá {} minut",
+            one_minute_left: "{}, zbývá jedna minuta",
+            by_timeout: "Na čas",
+            by_resignation: "Vzdáním",
+            by_abandonment: "Opuštěním partie",
+            stalemate: "Pat",
+            insufficient_material: "Nedostatečný materiál",
+            repetition: "Trojí opakování",
+            fifty_moves: "Pravidlo padesáti tahů",
+            by_agreement: "Dohodou",
+            opening: "Zahájení",
+            middlegame: "Střední hra",
+            endgame: "Koncovka",
+            variation: "Varianta",
+        },
+    ),
+    (
+        "hu",
+        ChessVocab {
+            king: "Király",
+            queen: "Vezér",
+            rook: "Bástya",
+            bishop: "Futó",
+            knight: "Huszár",
+            castles_kingside: "rövid sáncolás",
+            castles_queenside: "hosszú sáncolás",
+            takes: "üt",
+            check: "sakk",
+            checkmate: "sakk-matt",
+            promotes_to: "átváltozik",
+            brilliant: "Brilliáns lépés.",
+            good: "Jó lépés.",
+            interesting: "Érdekes lépés.",
+            dubious: "Kétes lépés.",
+            mistake: "Hiba.",
+            blunder: "Durva hiba.",
+            white_wins: "Világos nyert.",
+            black_wins: "Sötét nyert.",
+            draw: "Döntetlen.",
+            pawn: "Gyalog",
+            white: "Világos",
+            black: "Sötét",
+            to_move: "{} lép",
+            file: "{}-vonal",
+            rank: "{}. sor",
+            empty: "üres",
+            attacked_by: "támadja",
+            defended_by: "védi",
+            hanging: "Védtelen",
+            nothing: "semmi",
+            numbers: ["egy", "kettő", "három", "négy", "öt", "hat", "hét", "nyolc"],
+            equal: "Az állás kiegyenlített",
+            slightly_better: "{} kicsit jobban áll",
+            clearly_better: "{} egyértelműen jobban áll",
+            winning: "{} nyerésre áll",
+            mates_in: "{} {} lépésben mattot ad",
+            about: "körülbelül {}",
+            plus: "plusz",
+            minus: "mínusz",
+            best_is: "a legjobb {}",
+            then: "majd",
+            wdl: "Világosnak {} százalék, döntetlen {} százalék, Sötétnek {} százalék",
+            decimal: ",",
+            seconds_left: "{}, {} másodperc maradt",
+            minutes_left: "{}, {} perc maradt",
+            one_minute_left: "{}, egy perc maradt",
+            by_timeout: "Időtúllépéssel",
+            by_resignation: "Feladással",
+            by_abandonment: "Elhagyással",
+            stalemate: "Patt",
+            insufficient_material: "Elégtelen anyag",
+            repetition: "Háromszori ismétlés",
+            fifty_moves: "Ötven lépéses szabály",
+            by_agreement: "Megegyezéssel",
+            opening: "Megnyitás",
+            middlegame: "Középjáték",
+            endgame: "Végjáték",
+            variation: "Változat",
+        },
+    ),
+    (
+        "uk",
+        ChessVocab {
+            king: "Король",
+            queen: "Ферзь",
+            rook: "Тура",
+            bishop: "Слон",
+            knight: "Кінь",
+            castles_kingside: "коротка рокіровка",
+            castles_queenside: "довга рокіровка",
+            takes: "бере",
+            check: "шах",
+            checkmate: "мат",
+            promotes_to: "перетворюється на",
+            brilliant: "Блискучий хід.",
+            good: "Гарний хід.",
+            interesting: "Цікавий хід.",
+            dubious: "Сумнівний хід.",
+            mistake: "Помилка.",
+            blunder: "Груба помилка.",
+            white_wins: "Білі перемогли.",
+            black_wins: "Чорні перемогли.",
+            draw: "Нічия.",
+            pawn: "Пішак",
+            white: "Білі",
+            black: "Чорні",
+            to_move: "Хід: {}",
+            file: "вертикаль {}",
+            rank: "горизонталь {}",
+            empty: "порожньо",
+            attacked_by: "атакований",
+            defended_by: "захищений",
+            hanging: "Під боєм",
+            nothing: "нічим",
+            numbers: [
+                "один",
+                "два",
+                "три",
+                "чотири",
+                "п'ять",
+                "шість",
+                "сім",
+                "вісім",
+            ],
+            equal: "Позиція рівна",
+            slightly_better: "{} стоять трохи краще",
+            clearly_better: "{} стоять явно краще",
+            winning: "{} виграють",
+            mates_in: "{} ставлять мат у {}",
+            about: "близько {}",
+            plus: "плюс",
+            minus: "мінус",
+            best_is: "найкращий хід {}",
+            then: "потім",
+            wdl: "{} відсотків за білих, {} відсотків нічия, {} відсотків за чорних",
+            decimal: ",",
+            seconds_left: "{}, залишилось {} секунд",
+            minutes_left: "{}, залишилось {} хвилин",
+            one_minute_left: "{}, залишилась одна хвилина",
+            by_timeout: "За часом",
+            by_resignation: "Здача",
+            by_abandonment: "Залишення гри",
+            stalemate: "Пат",
+            insufficient_material: "Недостатньо матеріалу",
+            repetition: "Триразове повторення",
+            fifty_moves: "Правило п'ятдесяти ходів",
+            by_agreement: "За згодою",
+            opening: "Дебют",
+            middlegame: "Мітельшпіль",
+            endgame: "Ендшпіль",
+            variation: "Варіант",
+        },
+    ),
+    (
+        "fi",
+        ChessVocab {
+            king: "Kuningas",
+            queen: "Daami",
+            rook: "Torni",
+            bishop: "Lähetti",
+            knight: "Ratsu",
+            castles_kingside: "lyhyt linna",
+            castles_queenside: "pitkä linna",
+            takes: "lyö",
+            check: "shakki",
+            checkmate: "shakkimatti",
+            promotes_to: "korottuu",
+            brilliant: "Loistava siirto.",
+            good: "Hyvä siirto.",
+            interesting: "Mielenkiintoinen siirto.",
+            dubious: "Kyseenalainen siirto.",
+            mistake: "Virhe.",
+            blunder: "Karkea virhe.",
+            white_wins: "Valkea voittaa.",
+            black_wins: "Musta voittaa.",
+            draw: "Tasapeli.",
+            pawn: "Sotilas",
+            white: "Valkea",
+            black: "Musta",
+            to_move: "{} siirtää",
+            file: "{}-linja",
+            rank: "rivi {}",
+            empty: "tyhjä",
+            attacked_by: "uhkaajat",
+            defended_by: "puolustajat",
+            hanging: "Suojaton",
+            nothing: "ei mitään",
+            numbers: [
+                "yksi",
+                "kaksi",
+                "kolme",
+                "neljä",
+                "viisi",
+                "kuusi",
+                "seitsemän",
+                "kahdeksan",
+            ],
+            equal: "Asema on tasainen",
+            slightly_better: "{} on hieman paremmin",
+            clearly_better: "{} on selvästi paremmin",
+            winning: "{} on voittamassa",
+            mates_in: "{} tekee matin {} siirrossa",
+            about: "noin {}",
+            plus: "plus",
+            minus: "miinus",
+            best_is: "paras on {}",
+            then: "sitten",
+            wdl: "{} prosenttia Valkealle, {} prosenttia tasapeli, {} prosenttia Mustalle",
+            decimal: ",",
+            seconds_left: "{}, {} sekuntia jäljellä",
+            minutes_left: "{}, {} minuuttia jäljellä",
+            one_minute_left: "{}, yksi minuutti jäljellä",
+            by_timeout: "Aikaylitys",
+            by_resignation: "Luovutus",
+            by_abandonment: "Keskeytys",
+            stalemate: "Patti",
+            insufficient_material: "Riittämätön materiaali",
+            repetition: "Kolminkertainen toisto",
+            fifty_moves: "Viidenkymmenen siirron sääntö",
+            by_agreement: "Sopimus",
+            opening: "Avaus",
+            middlegame: "Keskipeli",
+            endgame: "Loppupeli",
+            variation: "Muunnelma",
+        },
+    ),
+];
@@ -1,5 +1,5 @@
 use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Router};
-use log::info;
+use log::{error, info};
 use oauth2::{
     basic::BasicClient, reqwest::async_http_client, AuthUrl, AuthorizationCode, ClientId,
     CsrfToken, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
@@ -12,7 +12,11 @@ use std::{
 use tauri::{Emitter, Manager};
 use tauri_plugin_opener::OpenerExt;
 
-use crate::{error::Error, AppState};
+use crate::{
+    error::Error,
+    secrets::{bearer_token, lichess_token_name, SecretsState},
+    AppState,
+};
 
 fn create_client(redirect_url: RedirectUrl) -> BasicClient {
     let client_id = ClientId::new("org.enparlant.app".to_string());
@@ -73,18 +77,28 @@ pub async fn authenticate(
         .set_pkce_challenge(state.auth.pkce.0.clone())
         .url();
     app.opener().open_url(auth_url.as_str(), None::<&str>)?;
-    let _server_handle = tauri::async_runtime::spawn(async move { run_server(app).await });
+    let _server_handle =
+        tauri::async_runtime::spawn(async move { run_server(app, username).await });
     Ok(())
 }
 
+/// The account being authorized, passed to the callback.
+#[derive(Clone)]
+struct PendingLogin {
+    username: String,
+}
+
 #[derive(Deserialize)]
 struct CallbackQuery {
     code: AuthorizationCode,
     state: CsrfToken,
 }
 
+/// Stores the access token in the secrets store and tells the frontend
+/// which account was authorized. The token itself never reaches the webview.
 async fn authorize(
     app: Extension<tauri::AppHandle>,
+    login: Extension<PendingLogin>,
     query: Query<CallbackQuery>,
 ) -> impl IntoResponse {
     let auth = &app.state::<AppState>().auth;
@@ -103,15 +117,24 @@ async fn authorize(
         .unwrap();
 
     let access_token = token.access_token().secret();
-    app.emit("access_token", access_token).unwrap();
+    let name = lichess_token_name(&login.username);
+    if let Err(e) = app
+        .state::<SecretsState>()
+        .with(&app, |store| store.put(&name, access_token))
+    {
+        error!("Failed to store the lichess token: {}", e);
+        return "failed to store the token".to_string();
+    }
+    app.emit("lichess_authenticated", &login.username).unwrap();
 
     "authorized".to_string()
 }
 
-async fn run_server(handle: tauri::AppHandle) -> Result<(), axum::Error> {
+async fn run_server(handle: tauri::AppHandle, username: String) -> Result<(), axum::Error> {
     let app = Router::new()
         .route("/callback", get(authorize))
-        .layer(Extension(handle.clone()));
+        .layer(Extension(handle.clone()))
+        .layer(Extension(PendingLogin { username }));
 
     let _ = axum::Server::bind(&handle.state::<AppState>().auth.socket_addr.clone())
         .serve(app.into_make_service())
@@ -119,3 +142,22 @@ async fn run_server(handle: tauri::AppHandle) -> Result<(), axum::Error> {
 
     Ok(())
 }
+
+/// GETs `url` with the bearer token stored under `auth_secret`, for API
+/// calls that need an account. Returns the response body.
+#[tauri::command]
+#[specta::specta]
+pub async fn authorized_fetch(
+    url: String,
+    auth_secret: String,
+    app: tauri::AppHandle,
+) -> Result<String, Error> {
+    let token = bearer_token(&app, &auth_secret, &url)?;
+    let response = reqwest::Client::new()
+        .get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.text().await?)
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use futures_util::{stream, StreamExt};
+use log::{info, warn};
+use pgn_reader::{BufferedReader, Nag, RawComment, RawHeader, SanPlus, Skip, Visitor};
+use serde::{Deserialize, Serialize};
+use shakmaty::{fen::Fen, Color};
+use specta::Type;
+use tauri::Manager;
+
+use crate::{
+    error::Error,
+    narration::narrate_move,
+    progress::update_progress,
+    tts_provider::{TtsOptions, TtsProviders},
+    AppState,
+};
+
+struct Node {
+    san: Option<SanPlus>,
+    ply: u32,
+    nags: Vec<Nag>,
+    comments: Vec<String>,
+    parent: usize,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn new(san: Option<SanPlus>, ply: u32, parent: usize) -> Self {
+        Self {
+            san,
+            ply,
+            nags: Vec::new(),
+            comments: Vec::new(),
+            parent,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Rebuilds the move tree of a PGN game, variations included, so every node
+/// can be narrated with its continuations the way the board view does.
+struct TreeBuilder {
+    nodes: Vec<Node>,
+    current: usize,
+    variation_stack: Vec<usize>,
+}
+
+impl TreeBuilder {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(None, 0, 0)],
+            current: 0,
+            variation_stack: Vec::new(),
+        }
+    }
+}
+
+impl Visitor for TreeBuilder {
+    type Result = Vec<Node>;
+
+    fn header(&mut self, key: &[u8], value: RawHeader<'_>) {
+        if key == b"FEN" {
+            if let Ok(fen) = String::from_utf8_lossy(value.as_bytes()).parse::<Fen>() {
+                let setup = fen.as_setup();
+                self.nodes[0].ply =
+                    (setup.fullmoves.get() - 1) * 2 + u32::from(setup.turn == Color::Black);
+            }
+        }
+    }
+
+    fn san(&mut self, san: SanPlus) {
+        let ply = self.nodes[self.current].ply + 1;
+        let index = self.nodes.len();
+        self.nodes.push(Node::new(Some(san), ply, self.current));
+        self.nodes[self.current].children.push(index);
+        self.current = index;
+    }
+
+    fn nag(&mut self, nag: Nag) {
+        self.nodes[self.current].nags.push(nag);
+    }
+
+    fn comment(&mut self, comment: RawComment<'_>) {
+        let comment = String::from_utf8_lossy(comment.as_bytes()).to_string();
+        self.nodes[self.current].comments.push(comment);
+    }
+
+    fn begin_variation(&mut self) -> Skip {
+        // A variation is an alternative to the move just played
+        self.variation_stack.push(self.current);
+        self.current = self.nodes[self.current].parent;
+        Skip(false)
+    }
+
+    fn end_variation(&mut self) {
+        if let Some(current) = self.variation_stack.pop() {
+            self.current = current;
+        }
+    }
+
+    fn end_game(&mut self) -> Self::Result {
+        std::mem::replace(&mut self.nodes, vec![Node::new(None, 0, 0)])
+    }
+}
+
+/// Narration for every annotated node of the game, in PGN order and without
+/// duplicates. Like the board view, a node is narrated when it has a comment
+/// or annotations, and its comment is followed by up to three continuations.
+pub fn collect_narrations(pgn: &str, lang: &str) -> Result<Vec<String>, Error> {
+    let mut reader = BufferedReader::new(pgn.as_bytes());
+    let nodes = reader
+        .read_game(&mut TreeBuilder::new())?
+        .unwrap_or_default();
+
+    let mut seen = HashSet::new();
+    let mut texts = Vec::new();
+    for node in &nodes {
+        if node.comments.is_empty() && node.nags.is_empty() {
+            continue;
+        }
+        let continuation: Vec<SanPlus> = node
+            .children
+            .iter()
+            .take(3)
+            .filter_map(|&child| nodes[child].san.clone())
+            .collect();
+        let text = narrate_move(
+            node.san.as_ref(),
+            &node.nags,
+            &node.comments.join(" "),
+            node.ply,
+            &continuation,
+            lang,
+        );
+        if !text.is_empty() && seen.insert(text.clone()) {
+            texts.push(text);
+        }
+    }
+    Ok(texts)
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecacheOptions {
+    pub provider: String,
+    pub voice: String,
+    pub lang: String,
+    pub server_url: Option<String>,
+    /// Requests in flight at once
+    #[serde(default = "default_concurrency")]
+    pub concurrency: u32,
+}
+
+fn default_concurrency() -> u32 {
+    2
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecacheSummary {
+    /// Distinct narrations in the game
+    pub total: u32,
+    /// Narrations now in the cache, including ones that already were
+    pub cached: u32,
+}
+
+/// Synthesizes the narration of every annotated node of a game into the
+/// audio cache; clips that are already cached are not requested again. Progress is reported under `id`, and the job can be
+/// cancelled with `cancel_tts_job`. Stops at the first provider error.
+#[tauri::command]
+#[specta::specta]
+pub async fn precache_game(
+    id: String,
+    pgn: String,
+    options: PrecacheOptions,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<PrecacheSummary, Error> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    state
+        .tts_job_cancel_flags
+        .insert(id.clone(), cancel_flag.clone());
+
+    let result = run_precache(&id, &pgn, &options, &cancel_flag, &app, &state).await;
+
+    state.tts_job_cancel_flags.remove(&id);
+    update_progress(&state.progress_state, &app, id, 100.0, true)?;
+    result
+}
+
+async fn run_precache(
+    id: &str,
+    pgn: &str,
+    options: &PrecacheOptions,
+    cancel_flag: &AtomicBool,
+    app: &tauri::AppHandle,
+    state: &tauri::State<'_, AppState>,
+) -> Result<PrecacheSummary, Error> {
+    let provider = app.state::<TtsProviders>().get(&options.provider)?;
+    let texts = collect_narrations(pgn, &options.lang)?;
+    let total = texts.len() as u32;
+
+    let tts_options = TtsOptions {
+        language: Some(options.lang.clone()),
+        server_url: options.server_url.clone(),
+        ..Default::default()
+    };
+    let failed = AtomicBool::new(false);
+    let done = AtomicU32::new(0);
+    let results: Vec<Result<bool, Error>> = stream::iter(texts.iter())
+        .map(|text| {
+            let (provider, tts_options, failed, done) = (&provider, &tts_options, &failed, &done);
+            async move {
+                if cancel_flag.load(Ordering::SeqCst) || failed.load(Ordering::SeqCst) {
+                    return Ok(false);
+                }
+                let result = provider
+                    .synthesize(app, text, &options.voice, tts_options)
+                    .await;
+                if let Err(e) = &result {
+                    // Likely the server is down or rate limiting, so the
+                    // remaining requests would fail too
+                    warn!("Precache stopped: {}", e);
+                    failed.store(true, Ordering::SeqCst);
+                }
+                let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                update_progress(
+                    &state.progress_state,
+                    app,
+                    id.to_string(),
+                    done as f32 / total as f32 * 100.0,
+                    false,
+                )?;
+                result.map(|_| true)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1) as usize)
+        .collect()
+        .await;
+
+    if cancel_flag.load(Ordering::SeqCst) {
+        return Err(Error::PrecacheCancelled);
+    }
+    let mut cached = 0;
+    for result in results {
+        if result? {
+            cached += 1;
+        }
+    }
+    info!("Precached {} of {} narrations", cached, total);
+
+    Ok(PrecacheSummary { total, cached })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrations_include_variations() {
+        let pgn = r#"{A famous game} 1. e4 e5 2. Nf3 d6 {Philidor Defense} 3. d4 Bg4? (3... exd4 {is normal}) (3... Nd7 {is also fine}) 4. dxe5 1-0"#;
+        assert_eq!(
+            collect_narrations(pgn, "en").unwrap(),
+            vec![
+                "A famous game e4.",
+                "2, d6.  Philidor Defense d4.",
+                "3, Bishop g4.  Mistake.",
+                "3, e takes d4.  is normal",
+                "3, Knight d7.  is also fine",
+            ]
+        );
+    }
+
+    #[test]
+    fn narrations_from_fen() {
+        let pgn = r#"[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40"]
+
+40... Kd7 {the king walks} 41. e4 *"#;
+        assert_eq!(
+            collect_narrations(pgn, "en").unwrap(),
+            vec!["40, King d7.  the king walks e4."]
+        );
+    }
+
+    #[test]
+    fn duplicate_narrations_are_skipped() {
+        let pgn = "1. e4 ! (1. e4 !) *";
+        assert_eq!(
+            collect_narrations(pgn, "en").unwrap(),
+            vec!["1, e4.  Good move."]
+        );
+    }
+}
@@ -0,0 +1,532 @@
+//! Encrypted store for API keys and OAuth tokens.
+//!
+//! Secrets are kept in `secrets.json` in the app data dir, encrypted with
+//! XChaCha20-Poly1305 under a key derived with Argon2id from an id bound to
+//! this machine or, once the user sets one, from a passphrase. The machine
+//! key keeps secrets out of plain settings files and backups; only a
+//! passphrase protects them from other programs running as the same user.
+//!
+//! Backend code reads secrets with [`secret`] and [`bearer_token`]. API keys
+//! (`tts.`) and OAuth tokens (`oauth.`) are never handed to the webview,
+//! which can only check that they are set.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::error::Error;
+
+const SECRETS_FILE: &str = "secrets.json";
+const FORMAT_VERSION: u32 = 1;
+/// Names the webview may store but never read back
+const BACKEND_ONLY_PREFIXES: [&str; 2] = ["oauth.", "tts."];
+
+fn backend_only(name: &str) -> bool {
+    BACKEND_ONLY_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// The secret holding a lichess account's OAuth token.
+pub fn lichess_token_name(username: &str) -> String {
+    format!("oauth.lichess.{}", username.to_lowercase())
+}
+
+/// Hosts a secret may be sent to as a bearer token.
+fn allowed_host(name: &str, host: &str) -> bool {
+    if name.starts_with("oauth.lichess.") {
+        host == "lichess.org" || host.ends_with(".lichess.org") || host == "explorer.lichess.ovh"
+    } else {
+        false
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum KeySource {
+    Machine,
+    Passphrase,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsStatus {
+    pub key_source: KeySource,
+    /// Waiting for the passphrase, see `secrets_unlock`
+    pub locked: bool,
+}
+
+/// The file on disk. The secrets map is serialized to JSON and encrypted
+/// as a whole.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    version: u32,
+    key_source: KeySource,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct Unlocked {
+    key: [u8; 32],
+    salt: [u8; 16],
+    secrets: BTreeMap<String, String>,
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> Result<[u8; 32], Error> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| Error::Secrets(e.to_string()))?;
+    Ok(key)
+}
+
+fn decode(field: &str) -> Result<Vec<u8>, Error> {
+    STANDARD
+        .decode(field)
+        .map_err(|e| Error::Secrets(format!("Corrupt secrets file: {}", e)))
+}
+
+pub struct SecretStore {
+    path: PathBuf,
+    machine_id: Vec<u8>,
+    key_source: KeySource,
+    unlocked: Option<Unlocked>,
+}
+
+impl SecretStore {
+    /// Opens the store at `path`. A store under the machine key is
+    /// unlocked right away; one under a passphrase stays locked until
+    /// [`SecretStore::unlock`].
+    pub fn open(path: PathBuf, machine_id: Vec<u8>) -> Result<Self, Error> {
+        let mut store = Self {
+            path,
+            machine_id,
+            key_source: KeySource::Machine,
+            unlocked: None,
+        };
+        match std::fs::read(&store.path) {
+            Ok(data) => {
+                let envelope: Envelope = serde_json::from_slice(&data)
+                    .map_err(|e| Error::Secrets(format!("Corrupt secrets file: {}", e)))?;
+                if envelope.version != FORMAT_VERSION {
+                    return Err(Error::Secrets(format!(
+                        "Unsupported secrets file version {}",
+                        envelope.version
+                    )));
+                }
+                store.key_source = envelope.key_source;
+                if store.key_source == KeySource::Machine {
+                    let machine_id = store.machine_id.clone();
+                    store.unlock_with(&machine_id)?;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                store.unlocked = Some(Unlocked {
+                    key: derive_key(&store.machine_id, &salt)?,
+                    salt,
+                    secrets: BTreeMap::new(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+        Ok(store)
+    }
+
+    fn unlock_with(&mut self, secret: &[u8]) -> Result<(), Error> {
+        let data = std::fs::read(&self.path)?;
+        let envelope: Envelope = serde_json::from_slice(&data)
+            .map_err(|e| Error::Secrets(format!("Corrupt secrets file: {}", e)))?;
+        let salt: [u8; 16] = decode(&envelope.salt)?
+            .try_into()
+            .map_err(|_| Error::Secrets("Corrupt secrets file: bad salt".to_string()))?;
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != 24 {
+            return Err(Error::Secrets(
+                "Corrupt secrets file: bad nonce".to_string(),
+            ));
+        }
+        let key = derive_key(secret, &salt)?;
+        let plaintext = XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                decode(&envelope.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| match self.key_source {
+                KeySource::Passphrase => Error::Secrets("Wrong passphrase".to_string()),
+                KeySource::Machine => Error::Secrets(
+                    "Secrets were encrypted on another machine; reset the store".to_string(),
+                ),
+            })?;
+        let secrets = serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::Secrets(format!("Corrupt secrets file: {}", e)))?;
+        self.unlocked = Some(Unlocked { key, salt, secrets });
+        Ok(())
+    }
+
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), Error> {
+        if self.unlocked.is_some() {
+            return Ok(());
+        }
+        self.unlock_with(passphrase.as_bytes())
+    }
+
+    /// Re-encrypts the store under `passphrase`, or under the machine key
+    /// when `None`.
+    pub fn set_passphrase(&mut self, passphrase: Option<&str>) -> Result<(), Error> {
+        let unlocked = self.unlocked.as_mut().ok_or(Error::SecretsLocked)?;
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let (source, secret) = match passphrase {
+            Some(passphrase) => (KeySource::Passphrase, passphrase.as_bytes()),
+            None => (KeySource::Machine, self.machine_id.as_slice()),
+        };
+        unlocked.key = derive_key(secret, &salt)?;
+        unlocked.salt = salt;
+        self.key_source = source;
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        let unlocked = self.unlocked.as_ref().ok_or(Error::SecretsLocked)?;
+        let plaintext = serde_json::to_vec(&unlocked.secrets).map_err(std::io::Error::from)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&unlocked.key.into())
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| Error::Secrets(e.to_string()))?;
+        let envelope = Envelope {
+            version: FORMAT_VERSION,
+            key_source: self.key_source,
+            salt: STANDARD.encode(unlocked.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        let data = serde_json::to_vec_pretty(&envelope).map_err(std::io::Error::from)?;
+        write_private(&self.path, &data)
+    }
+
+    pub fn status(&self) -> SecretsStatus {
+        SecretsStatus {
+            key_source: self.key_source,
+            locked: self.unlocked.is_none(),
+        }
+    }
+
+    fn secrets(&self) -> Result<&BTreeMap<String, String>, Error> {
+        Ok(&self.unlocked.as_ref().ok_or(Error::SecretsLocked)?.secrets)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>, Error> {
+        Ok(self.secrets()?.get(name).cloned())
+    }
+
+    pub fn put(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let unlocked = self.unlocked.as_mut().ok_or(Error::SecretsLocked)?;
+        unlocked.secrets.insert(name.to_string(), value.to_string());
+        self.save()
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<bool, Error> {
+        let unlocked = self.unlocked.as_mut().ok_or(Error::SecretsLocked)?;
+        let removed = unlocked.secrets.remove(name).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn names(&self) -> Result<Vec<String>, Error> {
+        Ok(self.secrets()?.keys().cloned().collect())
+    }
+}
+
+/// Writes `data` via a temporary file, readable only by the user.
+fn write_private(path: &Path, data: &[u8]) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// An id that stays the same for this machine across runs.
+fn machine_id() -> Vec<u8> {
+    #[cfg(target_os = "linux")]
+    let id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok());
+
+    #[cfg(target_os = "macos")]
+    let id = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .find(|line| line.contains("IOPlatformUUID"))
+                .and_then(|line| line.split('"').nth(3))
+                .map(str::to_string)
+        });
+
+    #[cfg(target_os = "windows")]
+    let id = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        std::process::Command::new("reg")
+            .args([
+                "query",
+                r"HKLM\SOFTWARE\Microsoft\Cryptography",
+                "/v",
+                "MachineGuid",
+            ])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()
+            .and_then(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .last()
+                    .map(str::to_string)
+            })
+    };
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let id: Option<String> = None;
+
+    let id = id.map(|id| id.trim().to_string()).unwrap_or_else(|| {
+        log::warn!("No machine id found, binding secrets to the user name");
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default()
+    });
+    [b"en-parlant-secrets\0".as_slice(), id.as_bytes()].concat()
+}
+
+/// The secrets store, opened on first use.
+#[derive(Default)]
+pub struct SecretsState(Mutex<Option<SecretStore>>);
+
+impl SecretsState {
+    pub(crate) fn with<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut SecretStore) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut guard = self.0.lock().unwrap();
+        if guard.is_none() {
+            let path = app.path().app_data_dir()?.join(SECRETS_FILE);
+            *guard = Some(SecretStore::open(path, machine_id())?);
+        }
+        f(guard.as_mut().unwrap())
+    }
+}
+
+/// Reads a secret for use in the backend.
+pub fn secret(app: &AppHandle, name: &str) -> Result<Option<String>, Error> {
+    app.state::<SecretsState>()
+        .with(app, |store| store.get(name))
+}
+
+/// The secret `name` as a bearer token for `url`, refused unless `url`
+/// is on a host the secret belongs to.
+pub fn bearer_token(app: &AppHandle, name: &str, url: &str) -> Result<String, Error> {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    if !allowed_host(name, &host) {
+        return Err(Error::Secrets(format!(
+            "Secret {} can't be sent to {}",
+            name, host
+        )));
+    }
+    secret(app, name)?.ok_or_else(|| Error::Secrets(format!("No secret named {}", name)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_status(
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<SecretsStatus, Error> {
+    state.with(&app, |store| Ok(store.status()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_unlock(
+    passphrase: String,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<(), Error> {
+    state.with(&app, |store| store.unlock(&passphrase))
+}
+
+/// Protects the store with `passphrase`, or with the machine key when
+/// `None`.
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_set_passphrase(
+    passphrase: Option<String>,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<(), Error> {
+    state.with(&app, |store| store.set_passphrase(passphrase.as_deref()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_put(
+    name: String,
+    value: String,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<(), Error> {
+    state.with(&app, |store| store.put(&name, &value))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_get(
+    name: String,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<Option<String>, Error> {
+    if backend_only(&name) {
+        return Err(Error::SecretNotReadable(name));
+    }
+    state.with(&app, |store| store.get(&name))
+}
+
+/// Whether a secret is set, for names the webview can't read back.
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_has(
+    name: String,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<bool, Error> {
+    state.with(&app, |store| Ok(store.names()?.contains(&name)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_delete(
+    name: String,
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<bool, Error> {
+    state.with(&app, |store| store.delete(&name))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_list(
+    app: AppHandle,
+    state: tauri::State<'_, SecretsState>,
+) -> Result<Vec<String>, Error> {
+    state.with(&app, |store| store.names())
+}
+
+/// Deletes every secret, e.g. when the passphrase is lost or the store was
+/// copied from another machine.
+#[tauri::command]
+#[specta::specta]
+pub fn secrets_reset(app: AppHandle, state: tauri::State<'_, SecretsState>) -> Result<(), Error> {
+    let path = app.path().app_data_dir()?.join(SECRETS_FILE);
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    *state.0.lock().unwrap() = Some(SecretStore::open(path, machine_id())?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACHINE: &[u8] = b"test-machine";
+
+    #[test]
+    fn roundtrips_under_machine_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SECRETS_FILE);
+        let mut store = SecretStore::open(path.clone(), MACHINE.to_vec()).unwrap();
+        store.put("tts.elevenlabs", "sk_secret").unwrap();
+        store.put("oauth.lichess.alice", "lio_token").unwrap();
+        assert!(store.delete("oauth.lichess.alice").unwrap());
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("sk_secret"));
+
+        let store = SecretStore::open(path.clone(), MACHINE.to_vec()).unwrap();
+        assert_eq!(store.names().unwrap(), vec!["tts.elevenlabs"]);
+        assert_eq!(
+            store.get("tts.elevenlabs").unwrap().as_deref(),
+            Some("sk_secret")
+        );
+
+        // Copied to another machine
+        assert!(SecretStore::open(path, b"other-machine".to_vec()).is_err());
+    }
+
+    #[test]
+    fn passphrase_locks_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SECRETS_FILE);
+        let mut store = SecretStore::open(path.clone(), MACHINE.to_vec()).unwrap();
+        store.put("tts.google", "AIza").unwrap();
+        store.set_passphrase(Some("correct horse")).unwrap();
+
+        let mut store = SecretStore::open(path.clone(), MACHINE.to_vec()).unwrap();
+        assert!(store.status().locked);
+        assert!(matches!(store.get("tts.google"), Err(Error::SecretsLocked)));
+        assert!(store.unlock("wrong").is_err());
+        store.unlock("correct horse").unwrap();
+        assert_eq!(store.get("tts.google").unwrap().as_deref(), Some("AIza"));
+
+        store.set_passphrase(None).unwrap();
+        let store = SecretStore::open(path, MACHINE.to_vec()).unwrap();
+        assert_eq!(store.status().key_source, KeySource::Machine);
+        assert!(!store.status().locked);
+    }
+
+    #[test]
+    fn keys_and_tokens_stay_in_the_backend() {
+        assert!(backend_only("tts.elevenlabs"));
+        assert!(backend_only("oauth.lichess.alice"));
+        assert!(!backend_only("ui.theme"));
+    }
+
+    #[test]
+    fn lichess_tokens_stay_on_lichess() {
+        let name = lichess_token_name("Alice");
+        assert!(allowed_host(&name, "lichess.org"));
+        assert!(allowed_host(&name, "explorer.lichess.ovh"));
+        assert!(!allowed_host(&name, "lichess.org.example.com"));
+        assert!(!allowed_host("tts.elevenlabs", "lichess.org"));
+    }
+}
@@ -50,11 +50,22 @@ mod server {
         }
     }
 
+    #[derive(Clone)]
+    struct SoundDirs {
+        sound: PathBuf,
+        composed: PathBuf,
+    }
+
     async fn serve_sound(
         AxumPath(path): AxumPath<String>,
         headers: HeaderMap,
-        Extension(sound_dir): Extension<PathBuf>,
+        Extension(dirs): Extension<SoundDirs>,
     ) -> impl IntoResponse {
+        // Narration stitched from word clips lives outside the bundled resources
+        let (sound_dir, path) = match path.strip_prefix("composed/") {
+            Some(rest) => (dirs.composed, rest.to_string()),
+            None => (dirs.sound, path),
+        };
         let file_path = sound_dir.join(&path);
 
         let canonical = match file_path.canonicalize() {
@@ -111,13 +122,17 @@ mod server {
             .into_response()
     }
 
-    pub fn start_sound_server(sound_dir: PathBuf) -> u16 {
+    /// Serves `sound_dir`, and `composed_dir` under `/composed`.
+    pub fn start_sound_server(sound_dir: PathBuf, composed_dir: PathBuf) -> u16 {
         let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind sound server");
         let port = listener.local_addr().unwrap().port();
 
         let app = Router::new()
             .route("/*path", get(serve_sound))
-            .layer(Extension(sound_dir));
+            .layer(Extension(SoundDirs {
+                sound: sound_dir,
+                composed: composed_dir,
+            }));
 
         tauri::async_runtime::spawn(async move {
             axum::Server::from_tcp(listener)
@@ -1,9 +1,31 @@
+use futures_util::future::BoxFuture;
+use log::{info, warn};
 use serde::Serialize;
 use specta::Type;
-use std::sync::Mutex;
-use tts::Tts;
+use std::collections::VecDeque;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Mutex, MutexGuard};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+use tts::{Tts, UtteranceId};
 
-pub struct SystemTtsState(pub Mutex<Option<Tts>>);
+use crate::error::Error;
+use crate::tts_cache::{cache_lookup, cache_store, TtsCacheKey};
+use crate::tts_provider::{TtsOptions, TtsProvider, TtsVoice};
+use crate::tts_servers::DepCheck;
+
+/// The OS speech engine plus our own utterance queue. The queue is only
+/// used when the backend reports utterance callbacks; otherwise utterances
+/// go straight to the engine and only `started` events are emitted.
+#[derive(Default)]
+pub struct SystemTtsState {
+    tts: Mutex<Option<Tts>>,
+    queue: Mutex<SpeechQueue>,
+    worker: Mutex<Option<mpsc::Sender<QueueMessage>>>,
+    callbacks: AtomicBool,
+    next_id: AtomicU32,
+}
 
 #[derive(Serialize, Type)]
 pub struct SystemVoice {
@@ -12,6 +34,60 @@ pub struct SystemVoice {
     pub language: String,
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UtteranceState {
+    Started,
+    Ended,
+    Stopped,
+}
+
+#[derive(Clone, Debug, Serialize, Type, Event)]
+pub struct SystemTtsUtteranceEvent {
+    pub id: u32,
+    pub state: UtteranceState,
+}
+
+struct Utterance {
+    id: u32,
+    text: String,
+    rate: Option<f32>,
+    volume: Option<f32>,
+    pitch: Option<f32>,
+}
+
+#[derive(Default)]
+struct SpeechQueue {
+    pending: VecDeque<Utterance>,
+    /// Our id and the backend's id (in its debug form, since backend ids
+    /// aren't `Send` on every platform) of the utterance being spoken
+    current: Option<(u32, String)>,
+}
+
+enum QueueMessage {
+    /// New utterances were queued
+    Kick,
+    Begin(String),
+    End(String),
+    Stop(String),
+}
+
+fn utterance_key(id: UtteranceId) -> String {
+    format!("{id:?}")
+}
+
+fn emit_state(app: &AppHandle, id: u32, state: UtteranceState) {
+    if let Err(e) = (SystemTtsUtteranceEvent { id, state }).emit(app) {
+        warn!("Failed to emit utterance event: {e}");
+    }
+}
+
+impl SystemTtsState {
+    fn queue(&self) -> MutexGuard<'_, SpeechQueue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 fn get_or_init_tts(
     state: &Mutex<Option<Tts>>,
 ) -> Result<std::sync::MutexGuard<'_, Option<Tts>>, String> {
@@ -23,18 +99,7 @@ fn get_or_init_tts(
     Ok(guard)
 }
 
-#[tauri::command]
-#[specta::specta]
-pub fn system_tts_speak(
-    state: tauri::State<'_, SystemTtsState>,
-    text: String,
-    rate: Option<f32>,
-    volume: Option<f32>,
-    pitch: Option<f32>,
-) -> Result<(), String> {
-    let mut guard = get_or_init_tts(&state.0)?;
-    let tts = guard.as_mut().ok_or("TTS not initialized")?;
-
+fn apply_settings(tts: &mut Tts, rate: Option<f32>, volume: Option<f32>, pitch: Option<f32>) {
     // Rate is normalized 0.5-2.0 from the frontend (1.0 = normal).
     // Map it to the platform's actual range using the crate's own bounds.
     if let Some(r) = rate {
@@ -59,28 +124,179 @@ pub fn system_tts_speak(
     if let Some(p) = pitch {
         let _ = tts.set_pitch(p);
     }
+}
 
-    tts.speak(text, true)
-        .map_err(|e| format!("TTS speak error: {e}"))?;
+/// Starts the thread that turns backend callbacks into events and feeds
+/// the queue. Callbacks only forward to the channel, so they never contend
+/// for the engine lock the speaking thread may be holding.
+fn ensure_worker(app: &AppHandle, state: &SystemTtsState) -> Result<(), String> {
+    let mut worker = state
+        .worker
+        .lock()
+        .map_err(|e| format!("TTS lock error: {e}"))?;
+    if worker.is_some() {
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    {
+        let guard = get_or_init_tts(&state.tts)?;
+        let tts = guard.as_ref().ok_or("TTS not initialized")?;
+        if tts.supported_features().utterance_callbacks {
+            let begin = tx.clone();
+            let end = tx.clone();
+            let stop = tx.clone();
+            let registered = tts
+                .on_utterance_begin(Some(Box::new(move |id| {
+                    let _ = begin.send(QueueMessage::Begin(utterance_key(id)));
+                })))
+                .and_then(|_| {
+                    tts.on_utterance_end(Some(Box::new(move |id| {
+                        let _ = end.send(QueueMessage::End(utterance_key(id)));
+                    })))
+                })
+                .and_then(|_| {
+                    tts.on_utterance_stop(Some(Box::new(move |id| {
+                        let _ = stop.send(QueueMessage::Stop(utterance_key(id)));
+                    })))
+                });
+            match registered {
+                Ok(()) => state.callbacks.store(true, Ordering::SeqCst),
+                Err(e) => warn!("System TTS utterance callbacks unavailable: {e}"),
+            }
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let state = app.state::<SystemTtsState>();
+        for message in rx {
+            let (key, outcome) = match message {
+                QueueMessage::Kick => {
+                    if state.queue().current.is_none() {
+                        start_next(&app, &state);
+                    }
+                    continue;
+                }
+                QueueMessage::Begin(key) => (key, UtteranceState::Started),
+                QueueMessage::End(key) => (key, UtteranceState::Ended),
+                QueueMessage::Stop(key) => (key, UtteranceState::Stopped),
+            };
+
+            let mut queue = state.queue();
+            let Some((id, _)) = queue.current.as_ref().filter(|(_, k)| *k == key) else {
+                // Interrupted utterances were already reported when dropped
+                continue;
+            };
+            let id = *id;
+            if outcome == UtteranceState::Started {
+                drop(queue);
+                emit_state(&app, id, outcome);
+                continue;
+            }
+            queue.current = None;
+            drop(queue);
+            emit_state(&app, id, outcome);
+            start_next(&app, &state);
+        }
+    });
+
+    *worker = Some(tx);
     Ok(())
 }
 
-#[tauri::command]
-#[specta::specta]
-pub fn system_tts_stop(state: tauri::State<'_, SystemTtsState>) -> Result<(), String> {
-    let mut guard = state.0.lock().map_err(|e| format!("TTS lock error: {e}"))?;
-    if let Some(tts) = guard.as_mut() {
-        let _ = tts.stop();
+/// Speaks queued utterances until one is accepted by the engine.
+fn start_next(app: &AppHandle, state: &SystemTtsState) {
+    while let Some(next) = state.queue().pending.pop_front() {
+        let spoken = get_or_init_tts(&state.tts).and_then(|mut guard| {
+            let tts = guard.as_mut().ok_or("TTS not initialized")?;
+            apply_settings(tts, next.rate, next.volume, next.pitch);
+            tts.speak(next.text, false)
+                .map_err(|e| format!("TTS speak error: {e}"))
+        });
+        match spoken {
+            Ok(Some(key)) => {
+                state.queue().current = Some((next.id, utterance_key(key)));
+                return;
+            }
+            Ok(None) => emit_state(app, next.id, UtteranceState::Started),
+            Err(e) => {
+                warn!("{e}");
+                emit_state(app, next.id, UtteranceState::Stopped);
+            }
+        }
+    }
+}
+
+/// Queues `text` and returns its utterance id. With `interrupt`, the
+/// current utterance and everything queued behind it is stopped first.
+fn speak(
+    app: &AppHandle,
+    state: &SystemTtsState,
+    text: String,
+    interrupt: bool,
+    rate: Option<f32>,
+    volume: Option<f32>,
+    pitch: Option<f32>,
+) -> Result<u32, String> {
+    ensure_worker(app, state)?;
+    let id = state.next_id.fetch_add(1, Ordering::Relaxed);
+
+    if !state.callbacks.load(Ordering::SeqCst) {
+        let mut guard = get_or_init_tts(&state.tts)?;
+        let tts = guard.as_mut().ok_or("TTS not initialized")?;
+        apply_settings(tts, rate, volume, pitch);
+        tts.speak(text, interrupt)
+            .map_err(|e| format!("TTS speak error: {e}"))?;
+        emit_state(app, id, UtteranceState::Started);
+        return Ok(id);
+    }
+
+    if interrupt {
+        stop(app, state)?;
+    }
+    state.queue().pending.push_back(Utterance {
+        id,
+        text,
+        rate,
+        volume,
+        pitch,
+    });
+    if let Some(worker) = state.worker.lock().ok().and_then(|w| w.clone()) {
+        let _ = worker.send(QueueMessage::Kick);
+    }
+    Ok(id)
+}
+
+/// Stops the current utterance and drops everything queued.
+fn stop(app: &AppHandle, state: &SystemTtsState) -> Result<(), String> {
+    let dropped: Vec<u32> = {
+        let mut queue = state.queue();
+        let current = queue.current.take().map(|(id, _)| id);
+        current
+            .into_iter()
+            .chain(queue.pending.drain(..).map(|u| u.id))
+            .collect()
+    };
+
+    {
+        let mut guard = state
+            .tts
+            .lock()
+            .map_err(|e| format!("TTS lock error: {e}"))?;
+        if let Some(tts) = guard.as_mut() {
+            let _ = tts.stop();
+        }
+    }
+
+    for id in dropped {
+        emit_state(app, id, UtteranceState::Stopped);
     }
     Ok(())
 }
 
-#[tauri::command]
-#[specta::specta]
-pub fn system_tts_list_voices(
-    state: tauri::State<'_, SystemTtsState>,
-) -> Result<Vec<SystemVoice>, String> {
-    let mut guard = get_or_init_tts(&state.0)?;
+fn list_voices(state: &Mutex<Option<Tts>>) -> Result<Vec<SystemVoice>, String> {
+    let mut guard = get_or_init_tts(state)?;
     let tts = guard.as_mut().ok_or("TTS not initialized")?;
 
     let voices = tts
@@ -96,13 +312,8 @@ pub fn system_tts_list_voices(
         .collect())
 }
 
-#[tauri::command]
-#[specta::specta]
-pub fn system_tts_set_voice(
-    state: tauri::State<'_, SystemTtsState>,
-    voice_id: String,
-) -> Result<(), String> {
-    let mut guard = get_or_init_tts(&state.0)?;
+fn set_voice(state: &Mutex<Option<Tts>>, voice_id: &str) -> Result<(), String> {
+    let mut guard = get_or_init_tts(state)?;
     let tts = guard.as_mut().ok_or("TTS not initialized")?;
 
     let voices = tts
@@ -114,3 +325,245 @@ pub fn system_tts_set_voice(
     }
     Ok(())
 }
+
+// --- Rendering to WAV ---
+//
+// The `tts` crate only plays audio, so rendering goes through the platform's
+// command-line synthesizer: `say` on macOS, System.Speech on Windows and
+// espeak-ng elsewhere.
+
+#[cfg(target_os = "macos")]
+fn render_command(voice: &SystemVoice, text_file: &str, out: &str) -> Command {
+    let mut cmd = Command::new("say");
+    cmd.args([
+        "--file-format=WAVE",
+        "--data-format=LEI16@22050",
+        "-o",
+        out,
+        "-f",
+        text_file,
+    ]);
+    if !voice.name.is_empty() {
+        cmd.args(["-v", &voice.name]);
+    }
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn render_command(voice: &SystemVoice, text_file: &str, out: &str) -> Command {
+    // Paths and voice name go through the environment to avoid quoting issues
+    let script = "Add-Type -AssemblyName System.Speech; \
+        $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+        if ($env:TTS_VOICE) { try { $s.SelectVoice($env:TTS_VOICE) } catch {} }; \
+        $s.SetOutputToWaveFile($env:TTS_OUT); \
+        $s.Speak([IO.File]::ReadAllText($env:TTS_TEXT)); \
+        $s.Dispose()";
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("TTS_VOICE", &voice.name)
+        .env("TTS_TEXT", text_file)
+        .env("TTS_OUT", out);
+    cmd
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn render_command(voice: &SystemVoice, text_file: &str, out: &str) -> Command {
+    // Speech-dispatcher voice names don't map to espeak voices, the
+    // language does
+    let mut cmd = Command::new("espeak-ng");
+    cmd.args(["-w", out, "-f", text_file]);
+    if !voice.language.is_empty() {
+        cmd.args(["-v", &voice.language.to_lowercase()]);
+    }
+    cmd
+}
+
+/// Renders `text` with a system voice to a WAV buffer, if the platform has a
+/// synthesizer that can write files.
+fn render_wav(voice: &SystemVoice, text: &str) -> Result<Vec<u8>, Error> {
+    let dir = tempfile::tempdir()?;
+    let text_file = dir.path().join("text.txt");
+    let out = dir.path().join("speech.wav");
+    std::fs::write(&text_file, text)?;
+
+    let output = render_command(voice, &text_file.to_string_lossy(), &out.to_string_lossy())
+        .output()
+        .map_err(|e| Error::Tts(format!("System voice rendering not available: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Tts(format!(
+            "System voice rendering failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(std::fs::read(out)?)
+}
+
+/// Returns the utterance id used in `SystemTtsUtteranceEvent`s. Without
+/// `interrupt` the text is queued behind the current utterance.
+#[tauri::command]
+#[specta::specta]
+pub fn system_tts_speak(
+    app: AppHandle,
+    state: tauri::State<'_, SystemTtsState>,
+    text: String,
+    interrupt: Option<bool>,
+    rate: Option<f32>,
+    volume: Option<f32>,
+    pitch: Option<f32>,
+) -> Result<u32, String> {
+    speak(
+        &app,
+        &state,
+        text,
+        interrupt.unwrap_or(true),
+        rate,
+        volume,
+        pitch,
+    )
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn system_tts_stop(
+    app: AppHandle,
+    state: tauri::State<'_, SystemTtsState>,
+) -> Result<(), String> {
+    stop(&app, &state)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn system_tts_list_voices(
+    state: tauri::State<'_, SystemTtsState>,
+) -> Result<Vec<SystemVoice>, String> {
+    list_voices(&state.tts)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn system_tts_set_voice(
+    state: tauri::State<'_, SystemTtsState>,
+    voice_id: String,
+) -> Result<(), String> {
+    set_voice(&state.tts, &voice_id)
+}
+
+/// The operating system's speech engine. It plays audio itself; rendering
+/// to WAV is best effort through the platform synthesizer.
+pub struct SystemTts;
+
+impl TtsProvider for SystemTts {
+    fn id(&self) -> &str {
+        "system"
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        Box::pin(async move {
+            let voices = list_voices(&app.state::<SystemTtsState>().tts).map_err(Error::Tts)?;
+            Ok(voices
+                .into_iter()
+                .map(|v| TtsVoice {
+                    id: v.id,
+                    name: v.name,
+                    language: v.language,
+                    gender: None,
+                })
+                .collect())
+        })
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let key = TtsCacheKey::new("system", voice, options.language(), text);
+            if let Some(audio) = cache_lookup(app, &key) {
+                return Ok(audio);
+            }
+
+            let voices = list_voices(&app.state::<SystemTtsState>().tts).map_err(Error::Tts)?;
+            let voice = voices
+                .into_iter()
+                .find(|v| v.id == voice)
+                .unwrap_or_else(|| SystemVoice {
+                    id: String::new(),
+                    name: String::new(),
+                    language: options.language().to_string(),
+                });
+            let text = text.to_string();
+            let audio =
+                tauri::async_runtime::spawn_blocking(move || render_wav(&voice, &text)).await??;
+            info!("Rendered system voice clip ({} bytes)", audio.len());
+
+            cache_store(app, &key, &audio);
+            Ok(audio)
+        })
+    }
+
+    fn speak<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let state = app.state::<SystemTtsState>().inner();
+            if !voice.is_empty() {
+                set_voice(&state.tts, voice).map_err(Error::Tts)?;
+            }
+            speak(
+                app,
+                state,
+                text.to_string(),
+                !options.enqueue,
+                options.rate,
+                options.volume,
+                options.pitch,
+            )
+            .map_err(Error::Tts)?;
+            Ok(())
+        })
+    }
+
+    fn stop<'a>(&'a self, app: &'a AppHandle) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(
+            async move { stop(app, app.state::<SystemTtsState>().inner()).map_err(Error::Tts) },
+        )
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        Box::pin(async move {
+            match get_or_init_tts(&app.state::<SystemTtsState>().tts) {
+                Ok(_) => DepCheck {
+                    ok: true,
+                    label: "System TTS available".into(),
+                    detail: "The OS speech engine is initialized".into(),
+                    fix_hint: String::new(),
+                },
+                Err(e) => DepCheck {
+                    ok: false,
+                    label: "System TTS unavailable".into(),
+                    detail: e,
+                    fix_hint: if cfg!(target_os = "linux") {
+                        "sudo apt install speech-dispatcher".into()
+                    } else {
+                        String::new()
+                    },
+                },
+            }
+        })
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use tauri::Manager;
+
+use crate::error::Error;
+
+/// Default on-disk budget for cached narration audio.
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+const AUDIO_EXTENSION: &str = "audio";
+const CONFIG_FILE: &str = "config.json";
+
+/// Identifies one synthesized utterance. Two requests that differ only in
+/// whitespace share a cache entry.
+#[derive(Deserialize, Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsCacheKey {
+    pub provider: String,
+    pub voice: String,
+    pub language: String,
+    pub text: String,
+}
+
+impl TtsCacheKey {
+    pub fn new(provider: &str, voice: &str, language: &str, text: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            voice: voice.to_string(),
+            language: language.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    fn digest(&self) -> String {
+        let text = self.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut hasher = Sha256::new();
+        for part in [&self.provider, &self.voice, &self.language, &text] {
+            hasher.update(part.as_bytes());
+            // Separator so ("ab", "c") and ("a", "bc") hash differently
+            hasher.update([0u8]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsCacheStats {
+    pub entries: u32,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+    pub hits: u32,
+    pub misses: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheConfig {
+    max_bytes: u64,
+}
+
+struct CacheEntry {
+    size: u64,
+    last_access: SystemTime,
+}
+
+/// Narration audio stored as one file per utterance under the app data dir.
+///
+/// The least recently used entries are evicted once the total size exceeds
+/// `max_bytes`. Access times are kept in the files' modification times, so
+/// the LRU order survives restarts without a separate index.
+pub struct AudioCache {
+    dir: PathBuf,
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+    max_bytes: u64,
+    hits: u32,
+    misses: u32,
+}
+
+impl AudioCache {
+    pub fn open(dir: PathBuf) -> Result<Self, Error> {
+        fs::create_dir_all(&dir)?;
+
+        let max_bytes = fs::read(dir.join(CONFIG_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_slice::<CacheConfig>(&data).ok())
+            .map(|c| c.max_bytes)
+            .unwrap_or(DEFAULT_MAX_BYTES);
+
+        let mut entries = HashMap::new();
+        let mut total_bytes = 0;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(AUDIO_EXTENSION) {
+                continue;
+            }
+            let Some(digest) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let metadata = fs::metadata(&path)?;
+            total_bytes += metadata.len();
+            entries.insert(
+                digest.to_string(),
+                CacheEntry {
+                    size: metadata.len(),
+                    last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                },
+            );
+        }
+
+        info!(
+            "Opened TTS cache at {} ({} entries, {} bytes)",
+            dir.display(),
+            entries.len(),
+            total_bytes
+        );
+
+        let mut cache = Self {
+            dir,
+            entries,
+            total_bytes,
+            max_bytes,
+            hits: 0,
+            misses: 0,
+        };
+        cache.evict_to(cache.max_bytes);
+        Ok(cache)
+    }
+
+    fn path_for(&self, digest: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", digest, AUDIO_EXTENSION))
+    }
+
+    pub fn get(&mut self, key: &TtsCacheKey) -> Option<Vec<u8>> {
+        let digest = key.digest();
+        if !self.entries.contains_key(&digest) {
+            self.misses += 1;
+            return None;
+        }
+
+        let path = self.path_for(&digest);
+        match fs::read(&path) {
+            Ok(data) => {
+                self.hits += 1;
+                let now = SystemTime::now();
+                touch(&path, now);
+                if let Some(entry) = self.entries.get_mut(&digest) {
+                    entry.last_access = now;
+                }
+                Some(data)
+            }
+            Err(e) => {
+                // File removed behind our back; forget about it
+                warn!("TTS cache entry {} unreadable: {}", digest, e);
+                self.remove(&digest);
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn put(&mut self, key: &TtsCacheKey, data: &[u8]) -> Result<(), Error> {
+        let size = data.len() as u64;
+        if size == 0 || size > self.max_bytes {
+            return Ok(());
+        }
+
+        let digest = key.digest();
+        self.remove(&digest);
+
+        // Write then rename so a crash never leaves a truncated entry behind
+        let path = self.path_for(&digest);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, &path)?;
+
+        self.entries.insert(
+            digest,
+            CacheEntry {
+                size,
+                last_access: SystemTime::now(),
+            },
+        );
+        self.total_bytes += size;
+        self.evict_to(self.max_bytes);
+        Ok(())
+    }
+
+    fn remove(&mut self, digest: &str) {
+        if let Some(entry) = self.entries.remove(digest) {
+            self.total_bytes -= entry.size;
+            let _ = fs::remove_file(self.path_for(digest));
+        }
+    }
+
+    /// Evicts least recently used entries until at most `max_bytes` remain.
+    pub fn evict_to(&mut self, max_bytes: u64) -> u32 {
+        if self.total_bytes <= max_bytes {
+            return 0;
+        }
+
+        let mut by_age: Vec<(SystemTime, String)> = self
+            .entries
+            .iter()
+            .map(|(digest, entry)| (entry.last_access, digest.clone()))
+            .collect();
+        by_age.sort();
+
+        let mut evicted = 0;
+        for (_, digest) in by_age {
+            if self.total_bytes <= max_bytes {
+                break;
+            }
+            self.remove(&digest);
+            evicted += 1;
+        }
+        evicted
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: u64) -> Result<(), Error> {
+        self.max_bytes = max_bytes;
+        let config = serde_json::to_vec(&CacheConfig { max_bytes }).unwrap_or_default();
+        fs::write(self.dir.join(CONFIG_FILE), config)?;
+        self.evict_to(max_bytes);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        let digests: Vec<String> = self.entries.keys().cloned().collect();
+        for digest in digests {
+            self.remove(&digest);
+        }
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn stats(&self) -> TtsCacheStats {
+        TtsCacheStats {
+            entries: self.entries.len() as u32,
+            total_bytes: self.total_bytes,
+            max_bytes: self.max_bytes,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+fn touch(path: &Path, time: SystemTime) {
+    if let Ok(file) = File::options().write(true).open(path) {
+        let _ = file.set_modified(time);
+    }
+}
+
+pub struct TtsCacheState(pub Mutex<Option<AudioCache>>);
+
+impl Default for TtsCacheState {
+    fn default() -> Self {
+        Self(Mutex::new(None))
+    }
+}
+
+/// Runs `f` with the cache, opening it under the app data dir on first use.
+pub fn with_cache<T>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&mut AudioCache) -> T,
+) -> Result<T, Error> {
+    let state = app.state::<TtsCacheState>();
+    let mut guard = state.0.lock().unwrap();
+    if guard.is_none() {
+        let dir = app.path().app_data_dir()?.join("tts-cache");
+        *guard = Some(AudioCache::open(dir)?);
+    }
+    Ok(f(guard.as_mut().unwrap()))
+}
+
+/// Cached audio for `key`, if any. Cache failures are logged and treated as
+/// a miss so synthesis can always proceed.
+pub fn cache_lookup(app: &tauri::AppHandle, key: &TtsCacheKey) -> Option<Vec<u8>> {
+    with_cache(app, |cache| cache.get(key))
+        .map_err(|e| warn!("TTS cache unavailable: {}", e))
+        .ok()
+        .flatten()
+}
+
+pub fn cache_store(app: &tauri::AppHandle, key: &TtsCacheKey, data: &[u8]) {
+    match with_cache(app, |cache| cache.put(key, data)) {
+        Ok(Err(e)) | Err(e) => warn!("Failed to store TTS audio in cache: {}", e),
+        Ok(Ok(())) => {}
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn tts_cache_stats(app: tauri::AppHandle) -> Result<TtsCacheStats, Error> {
+    with_cache(&app, |cache| cache.stats())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn tts_cache_set_limit(app: tauri::AppHandle, max_bytes: u64) -> Result<TtsCacheStats, Error> {
+    with_cache(&app, |cache| {
+        cache.set_max_bytes(max_bytes)?;
+        Ok(cache.stats())
+    })?
+}
+
+/// Evicts least recently used entries down to `max_bytes`, or to the
+/// configured limit when not given.
+#[tauri::command]
+#[specta::specta]
+pub fn tts_cache_prune(
+    app: tauri::AppHandle,
+    max_bytes: Option<u64>,
+) -> Result<TtsCacheStats, Error> {
+    with_cache(&app, |cache| {
+        let target = max_bytes.unwrap_or(cache.max_bytes);
+        let evicted = cache.evict_to(target);
+        info!("Pruned {} entries from TTS cache", evicted);
+        cache.stats()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn tts_cache_clear(app: tauri::AppHandle) -> Result<(), Error> {
+    with_cache(&app, |cache| cache.clear())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> TtsCacheKey {
+        TtsCacheKey::new("kittentts", "expr-voice-2-m", "en", text)
+    }
+
+    #[test]
+    fn normalizes_whitespace() {
+        assert_eq!(
+            key("1, e4.  Good move.").digest(),
+            key(" 1, e4. Good\nmove. ").digest()
+        );
+        assert_ne!(
+            key("1, e4.").digest(),
+            TtsCacheKey::new("opentts", "expr-voice-2-m", "en", "1, e4.").digest()
+        );
+    }
+
+    #[test]
+    fn hit_and_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = AudioCache::open(dir.path().to_path_buf()).unwrap();
+
+        assert!(cache.get(&key("e4")).is_none());
+        cache.put(&key("e4"), b"RIFF....").unwrap();
+        assert_eq!(cache.get(&key("e4")).unwrap(), b"RIFF....");
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+        // Entries survive reopening
+        let mut cache = AudioCache::open(dir.path().to_path_buf()).unwrap();
+        assert_eq!(cache.get(&key("e4")).unwrap(), b"RIFF....");
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = AudioCache::open(dir.path().to_path_buf()).unwrap();
+        cache.set_max_bytes(25).unwrap();
+
+        cache.put(&key("a"), &[0; 10]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put(&key("b"), &[0; 10]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(cache.get(&key("a")).is_some());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put(&key("c"), &[0; 10]).unwrap();
+
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!(cache.stats().total_bytes, 20);
+    }
+}
@@ -0,0 +1,607 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::tts_servers::{
+    check_command_tts, check_docker_installed, check_docker_running, check_kittentts_packages,
+    check_kittentts_script, check_kittentts_venv, check_opentts_image, check_python_installed,
+    create_kittentts_venv, install_kittentts_packages, kittentts_healthy, opentts_status,
+    read_command_tts_config, DepCheck, TtsServerState, KITTENTTS_DEFAULT_PORT,
+    OPENTTS_DEFAULT_PORT,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckId {
+    DockerInstalled,
+    DockerRunning,
+    OpenttsImage,
+    OpenttsPort,
+    OpenttsServer,
+    PythonInstalled,
+    KittenttsScript,
+    KittenttsVenv,
+    KittenttsPackages,
+    KittenttsPort,
+    KittenttsServer,
+    CommandTts,
+}
+
+/// Every check, each after the checks it depends on.
+const CHECKS: [CheckId; 12] = [
+    CheckId::DockerInstalled,
+    CheckId::DockerRunning,
+    CheckId::OpenttsImage,
+    CheckId::OpenttsPort,
+    CheckId::OpenttsServer,
+    CheckId::PythonInstalled,
+    CheckId::KittenttsScript,
+    CheckId::KittenttsVenv,
+    CheckId::KittenttsPackages,
+    CheckId::KittenttsPort,
+    CheckId::KittenttsServer,
+    CheckId::CommandTts,
+];
+
+impl CheckId {
+    fn depends_on(self) -> &'static [CheckId] {
+        match self {
+            CheckId::DockerRunning => &[CheckId::DockerInstalled],
+            CheckId::OpenttsImage => &[CheckId::DockerRunning],
+            CheckId::OpenttsServer => &[CheckId::OpenttsImage],
+            CheckId::KittenttsVenv => &[CheckId::PythonInstalled],
+            CheckId::KittenttsPackages => &[CheckId::KittenttsVenv],
+            CheckId::KittenttsServer => &[CheckId::KittenttsScript, CheckId::KittenttsPackages],
+            _ => &[],
+        }
+    }
+
+    fn provider(self) -> &'static str {
+        match self {
+            CheckId::DockerInstalled
+            | CheckId::DockerRunning
+            | CheckId::OpenttsImage
+            | CheckId::OpenttsPort
+            | CheckId::OpenttsServer => "opentts",
+            CheckId::PythonInstalled
+            | CheckId::KittenttsScript
+            | CheckId::KittenttsVenv
+            | CheckId::KittenttsPackages
+            | CheckId::KittenttsPort
+            | CheckId::KittenttsServer => "kittentts",
+            CheckId::CommandTts => "command",
+        }
+    }
+
+    /// What resolves a failure of this check.
+    fn fix(self) -> Option<FixAction> {
+        match self {
+            CheckId::OpenttsImage => Some(FixAction::PullOpenttsImage),
+            CheckId::OpenttsServer => Some(FixAction::StartOpentts),
+            CheckId::KittenttsVenv => Some(FixAction::CreateKittenttsVenv),
+            CheckId::KittenttsPackages => Some(FixAction::InstallKittenttsPackages),
+            CheckId::KittenttsServer => Some(FixAction::StartKittentts),
+            CheckId::CommandTts => Some(FixAction::ConfigureCommandTts),
+            // KittenTTS moves to a free port by itself
+            CheckId::KittenttsPort => None,
+            _ => Some(FixAction::Manual),
+        }
+    }
+}
+
+/// A machine-readable fix step. Only the safe ones run automatically; the
+/// others are for the setup wizard to offer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FixAction {
+    CreateKittenttsVenv,
+    InstallKittenttsPackages,
+    StartKittentts,
+    /// Downloads a large image, so it is left to the user
+    PullOpenttsImage,
+    StartOpentts,
+    ConfigureCommandTts,
+    /// Needs the user, e.g. installing system packages; see `fix_hint`
+    Manual,
+}
+
+impl FixAction {
+    pub fn is_safe(self) -> bool {
+        matches!(
+            self,
+            FixAction::CreateKittenttsVenv | FixAction::InstallKittenttsPackages
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but not as configured
+    Warning,
+    Failed,
+    /// Not meaningful until a check it depends on passes
+    Blocked,
+    /// An optional provider that isn't set up
+    Skipped,
+}
+
+impl CheckStatus {
+    fn passed(self) -> bool {
+        matches!(self, CheckStatus::Ok | CheckStatus::Warning)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosisCheck {
+    pub id: CheckId,
+    pub provider: String,
+    pub status: CheckStatus,
+    pub label: String,
+    pub detail: String,
+    pub fix_hint: String,
+    pub depends_on: Vec<CheckId>,
+    pub fix: Option<FixAction>,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FixResult {
+    pub action: FixAction,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsDiagnosis {
+    /// In dependency order
+    pub checks: Vec<DiagnosisCheck>,
+    /// Providers whose checks all passed
+    pub ready: Vec<String>,
+    /// Fixes run by this diagnosis, in order
+    pub fixes: Vec<FixResult>,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum FixProgress {
+    Running,
+    /// A line of installer output, e.g. pip's "Collecting numpy"
+    Output {
+        line: String,
+    },
+    Done {
+        message: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Emitted while `tts_diagnose` runs a fix.
+#[derive(Serialize, Debug, Clone, Type, Event)]
+pub struct TtsFixEvent {
+    pub action: FixAction,
+    pub progress: FixProgress,
+}
+
+type Probe = (CheckStatus, DepCheck);
+
+fn probe(check: DepCheck) -> Probe {
+    let status = if check.ok {
+        CheckStatus::Ok
+    } else {
+        CheckStatus::Failed
+    };
+    (status, check)
+}
+
+/// Orders raw check results and blocks checks whose dependencies failed, so
+/// the report leads with the root cause.
+fn resolve(mut results: HashMap<CheckId, Probe>) -> TtsDiagnosis {
+    let mut done: HashMap<CheckId, (CheckStatus, String)> = HashMap::new();
+    let mut checks = Vec::new();
+    for id in CHECKS {
+        let Some((status, check)) = results.remove(&id) else {
+            continue;
+        };
+        let failed_dep = id.depends_on().iter().find_map(|dep| match done.get(dep) {
+            Some((status, _)) if status.passed() => None,
+            Some((_, label)) => Some(label.clone()),
+            None => Some(format!("{:?}", dep)),
+        });
+        let (status, detail, fix_hint) = match failed_dep {
+            Some(dep) => (
+                CheckStatus::Blocked,
+                format!("Blocked by: {}", dep),
+                String::new(),
+            ),
+            None => (status, check.detail, check.fix_hint),
+        };
+        done.insert(id, (status, check.label.clone()));
+        checks.push(DiagnosisCheck {
+            id,
+            provider: id.provider().to_string(),
+            status,
+            label: check.label,
+            detail,
+            fix_hint,
+            depends_on: id.depends_on().to_vec(),
+            fix: (status == CheckStatus::Failed).then(|| id.fix()).flatten(),
+        });
+    }
+
+    let mut ready: Vec<String> = Vec::new();
+    for provider in ["opentts", "kittentts", "command"] {
+        let mut provider_checks = checks.iter().filter(|c| c.provider == provider).peekable();
+        if provider_checks.peek().is_some() && provider_checks.all(|c| c.status.passed()) {
+            ready.push(provider.to_string());
+        }
+    }
+
+    TtsDiagnosis {
+        checks,
+        ready,
+        fixes: Vec::new(),
+    }
+}
+
+/// Whether `port` is free for the server, or already taken by it.
+fn port_check(name: &str, port: u16, server_healthy: bool, conflict: CheckStatus) -> Probe {
+    if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+        return probe(DepCheck {
+            ok: true,
+            label: format!("Port {} available", port),
+            detail: format!("{} can listen on port {}", name, port),
+            fix_hint: String::new(),
+        });
+    }
+    if server_healthy {
+        return probe(DepCheck {
+            ok: true,
+            label: format!("Port {} used by {}", port, name),
+            detail: format!("{} is already listening", name),
+            fix_hint: String::new(),
+        });
+    }
+    (
+        conflict,
+        DepCheck {
+            ok: false,
+            label: format!("Port {} in use", port),
+            detail: format!("Another program is listening on port {}", port),
+            fix_hint: if conflict == CheckStatus::Warning {
+                format!("{} will use another port", name)
+            } else {
+                format!(
+                    "Stop the program using port {} or choose another port",
+                    port
+                )
+            },
+        },
+    )
+}
+
+fn server_check(name: &str, port: u16, healthy: bool) -> Probe {
+    probe(if healthy {
+        DepCheck {
+            ok: true,
+            label: format!("{} server running", name),
+            detail: format!("http://127.0.0.1:{}", port),
+            fix_hint: String::new(),
+        }
+    } else {
+        DepCheck {
+            ok: false,
+            label: format!("{} server not responding", name),
+            detail: format!("Nothing answered on port {}", port),
+            fix_hint: format!("Start {} from Settings > Sound", name),
+        }
+    })
+}
+
+async fn opentts_probes() -> (Probe, Probe) {
+    let port = opentts_status()
+        .await
+        .ok()
+        .and_then(|status| status.host_port)
+        .unwrap_or(OPENTTS_DEFAULT_PORT);
+    let healthy = reqwest::Client::new()
+        .get(format!("http://127.0.0.1:{}/api/voices", port))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .is_ok_and(|r| r.status().is_success());
+    (
+        port_check("OpenTTS", port, healthy, CheckStatus::Failed),
+        server_check("OpenTTS", port, healthy),
+    )
+}
+
+async fn kittentts_probes(app: &AppHandle) -> (Probe, Probe) {
+    let port = app
+        .state::<TtsServerState>()
+        .kittentts_port()
+        .unwrap_or(KITTENTTS_DEFAULT_PORT);
+    let healthy = kittentts_healthy(&reqwest::Client::new(), port).await;
+    (
+        port_check("KittenTTS", port, healthy, CheckStatus::Warning),
+        server_check("KittenTTS", port, healthy),
+    )
+}
+
+/// The command provider is optional, so it is skipped rather than failed
+/// when no command is configured.
+fn command_probe(app: AppHandle) -> Probe {
+    if matches!(read_command_tts_config(&app), Ok(None)) {
+        return (
+            CheckStatus::Skipped,
+            DepCheck {
+                ok: false,
+                label: "No synthesizer command configured".into(),
+                detail: "Optional".into(),
+                fix_hint: String::new(),
+            },
+        );
+    }
+    probe(check_command_tts(app))
+}
+
+/// Runs a synchronous check off the async runtime.
+async fn blocking(check: impl FnOnce() -> Probe + Send + 'static) -> Probe {
+    tokio::task::spawn_blocking(check)
+        .await
+        .unwrap_or_else(|e| {
+            probe(DepCheck {
+                ok: false,
+                label: "Check failed".into(),
+                detail: e.to_string(),
+                fix_hint: String::new(),
+            })
+        })
+}
+
+/// Runs every check concurrently.
+async fn diagnose(app: &AppHandle, lang: Option<String>) -> TtsDiagnosis {
+    let (a, b, c, d, e) = (
+        app.clone(),
+        app.clone(),
+        app.clone(),
+        app.clone(),
+        app.clone(),
+    );
+    let (
+        docker_installed,
+        docker_running,
+        opentts_image,
+        (opentts_port, opentts_server),
+        python,
+        script,
+        venv,
+        packages,
+        (kittentts_port, kittentts_server),
+        command,
+    ) = tokio::join!(
+        async { probe(check_docker_installed().await) },
+        async { probe(check_docker_running().await) },
+        async { probe(check_opentts_image(lang).await) },
+        opentts_probes(),
+        blocking(|| probe(check_python_installed())),
+        blocking(move || probe(check_kittentts_script(a))),
+        blocking(move || probe(check_kittentts_venv(b))),
+        blocking(move || probe(check_kittentts_packages(c))),
+        kittentts_probes(&d),
+        blocking(move || command_probe(e)),
+    );
+    resolve(HashMap::from([
+        (CheckId::DockerInstalled, docker_installed),
+        (CheckId::DockerRunning, docker_running),
+        (CheckId::OpenttsImage, opentts_image),
+        (CheckId::OpenttsPort, opentts_port),
+        (CheckId::OpenttsServer, opentts_server),
+        (CheckId::PythonInstalled, python),
+        (CheckId::KittenttsScript, script),
+        (CheckId::KittenttsVenv, venv),
+        (CheckId::KittenttsPackages, packages),
+        (CheckId::KittenttsPort, kittentts_port),
+        (CheckId::KittenttsServer, kittentts_server),
+        (CheckId::CommandTts, command),
+    ]))
+}
+
+async fn run_fix(app: &AppHandle, action: FixAction) -> FixResult {
+    let _ = TtsFixEvent {
+        action,
+        progress: FixProgress::Running,
+    }
+    .emit(app);
+    let task_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || match action {
+        FixAction::CreateKittenttsVenv => create_kittentts_venv(&task_app),
+        FixAction::InstallKittenttsPackages => install_kittentts_packages(&task_app, &mut |line| {
+            let _ = TtsFixEvent {
+                action,
+                progress: FixProgress::Output {
+                    line: line.to_string(),
+                },
+            }
+            .emit(&task_app);
+        }),
+        other => Err(format!("{:?} can't run automatically", other)),
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+
+    let progress = match &result {
+        Ok(message) => FixProgress::Done {
+            message: message.clone(),
+        },
+        Err(error) => FixProgress::Failed {
+            error: error.clone(),
+        },
+    };
+    let _ = TtsFixEvent { action, progress }.emit(app);
+    FixResult {
+        action,
+        ok: result.is_ok(),
+        message: result.unwrap_or_else(|e| e),
+    }
+}
+
+/// Checks every TTS dependency and server at once and reports them in
+/// dependency order with fix actions. With `fix`, safe fixes are run one by
+/// one, rechecking after each, with progress reported as `TtsFixEvent`s.
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_diagnose(
+    lang: Option<String>,
+    fix: Option<bool>,
+    app: AppHandle,
+) -> Result<TtsDiagnosis, String> {
+    let mut diagnosis = diagnose(&app, lang.clone()).await;
+    if !fix.unwrap_or(false) {
+        return Ok(diagnosis);
+    }
+
+    let mut fixes: Vec<FixResult> = Vec::new();
+    loop {
+        // A fix is tried once; one that failed won't succeed on a rerun
+        let next = diagnosis
+            .checks
+            .iter()
+            .filter_map(|check| check.fix)
+            .find(|action| action.is_safe() && !fixes.iter().any(|f| f.action == *action));
+        let Some(action) = next else {
+            break;
+        };
+        let result = run_fix(&app, action).await;
+        let failed = !result.ok;
+        fixes.push(result);
+        if failed {
+            break;
+        }
+        diagnosis = diagnose(&app, lang.clone()).await;
+    }
+    diagnosis.fixes = fixes;
+    Ok(diagnosis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(ok: bool) -> Probe {
+        probe(DepCheck {
+            ok,
+            label: if ok { "Fine" } else { "Broken" }.into(),
+            detail: "detail".into(),
+            fix_hint: "hint".into(),
+        })
+    }
+
+    fn all_ok() -> HashMap<CheckId, Probe> {
+        CHECKS.iter().map(|&id| (id, check(true))).collect()
+    }
+
+    fn status(diagnosis: &TtsDiagnosis, id: CheckId) -> CheckStatus {
+        diagnosis.checks.iter().find(|c| c.id == id).unwrap().status
+    }
+
+    #[test]
+    fn failures_block_dependent_checks() {
+        let mut results = all_ok();
+        results.insert(CheckId::PythonInstalled, check(false));
+        results.insert(CheckId::KittenttsVenv, check(false));
+        results.insert(CheckId::KittenttsPackages, check(false));
+        let diagnosis = resolve(results);
+
+        assert_eq!(
+            diagnosis.checks.iter().map(|c| c.id).collect::<Vec<_>>(),
+            CHECKS.to_vec()
+        );
+        assert_eq!(
+            status(&diagnosis, CheckId::PythonInstalled),
+            CheckStatus::Failed
+        );
+        assert_eq!(
+            status(&diagnosis, CheckId::KittenttsVenv),
+            CheckStatus::Blocked
+        );
+        assert_eq!(
+            status(&diagnosis, CheckId::KittenttsPackages),
+            CheckStatus::Blocked
+        );
+        assert_eq!(
+            status(&diagnosis, CheckId::KittenttsServer),
+            CheckStatus::Blocked
+        );
+        assert_eq!(
+            status(&diagnosis, CheckId::KittenttsScript),
+            CheckStatus::Ok
+        );
+
+        let python = &diagnosis.checks[5];
+        assert_eq!(python.fix, Some(FixAction::Manual));
+        let venv = &diagnosis.checks[7];
+        assert_eq!(venv.fix, None);
+        assert_eq!(venv.detail, "Blocked by: Broken");
+        assert_eq!(diagnosis.ready, vec!["opentts", "command"]);
+    }
+
+    #[test]
+    fn skipped_provider_is_not_ready() {
+        let mut results = all_ok();
+        results.insert(
+            CheckId::CommandTts,
+            (
+                CheckStatus::Skipped,
+                DepCheck {
+                    ok: false,
+                    label: "No synthesizer command configured".into(),
+                    detail: "Optional".into(),
+                    fix_hint: String::new(),
+                },
+            ),
+        );
+        let diagnosis = resolve(results);
+        let command = diagnosis.checks.last().unwrap();
+        assert_eq!(command.status, CheckStatus::Skipped);
+        assert_eq!(command.fix, None);
+        assert_eq!(diagnosis.ready, vec!["opentts", "kittentts"]);
+    }
+
+    #[test]
+    fn first_safe_fix_comes_first() {
+        let mut results = all_ok();
+        results.insert(CheckId::KittenttsVenv, check(false));
+        results.insert(CheckId::KittenttsServer, check(false));
+        let diagnosis = resolve(results);
+        let fixes: Vec<FixAction> = diagnosis.checks.iter().filter_map(|c| c.fix).collect();
+        assert_eq!(fixes, vec![FixAction::CreateKittenttsVenv]);
+        assert!(fixes[0].is_safe());
+        assert!(!FixAction::PullOpenttsImage.is_safe());
+    }
+
+    #[test]
+    fn port_probe() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let (status, check) = port_check("KittenTTS", port, false, CheckStatus::Warning);
+        assert_eq!(status, CheckStatus::Warning);
+        assert_eq!(check.fix_hint, "KittenTTS will use another port");
+        let (status, _) = port_check("OpenTTS", port, true, CheckStatus::Failed);
+        assert_eq!(status, CheckStatus::Ok);
+
+        drop(listener);
+        let (status, check) = port_check("OpenTTS", port, false, CheckStatus::Failed);
+        assert_eq!(status, CheckStatus::Ok);
+        assert_eq!(check.label, format!("Port {} available", port));
+    }
+}
@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::{
+    audio::PostProcessed,
+    cloud_tts::CloudTts,
+    error::Error,
+    lexicon::WithLexicon,
+    system_tts::SystemTts,
+    tts_servers::{cancel_tts_fetch, CommandTts, DepCheck, HttpTtsServer, TtsFetchError},
+    AppState,
+};
+
+#[derive(Serialize, Debug, Clone, Type, PartialEq)]
+pub struct TtsVoice {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+    pub gender: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtsOptions {
+    /// Language of the text, e.g. "en" or "de"
+    pub language: Option<String>,
+    /// Normalized 0.5-2.0, 1.0 = normal
+    pub rate: Option<f32>,
+    /// 0.0-1.0
+    pub volume: Option<f32>,
+    pub pitch: Option<f32>,
+    /// Base URL of an HTTP provider, if not the provider's default
+    pub server_url: Option<String>,
+    /// Lets the request be cancelled or superseded, see `cancel_tts_fetch`
+    pub request_id: Option<String>,
+    /// For `speak`: queue behind the current utterance instead of
+    /// interrupting it
+    pub enqueue: bool,
+    /// The text is an SSML document; set by the lexicon for providers that
+    /// support it
+    pub ssml: bool,
+}
+
+impl TtsOptions {
+    pub fn language(&self) -> &str {
+        self.language.as_deref().unwrap_or("en")
+    }
+}
+
+/// A source of voices. Providers that produce audio implement `synthesize`
+/// and leave playback to the caller; providers that own an output device
+/// implement `speak` and `stop`.
+pub trait TtsProvider: Send + Sync {
+    fn id(&self) -> &str;
+
+    /// Whether the provider accepts SSML, including `<phoneme>` hints
+    fn supports_ssml(&self) -> bool {
+        false
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>>;
+
+    fn synthesize<'a>(
+        &'a self,
+        _app: &'a AppHandle,
+        _text: &'a str,
+        _voice: &'a str,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        let id = self.id().to_string();
+        Box::pin(async move { Err(Error::TtsUnsupported(id, "synthesize")) })
+    }
+
+    fn speak<'a>(
+        &'a self,
+        _app: &'a AppHandle,
+        _text: &'a str,
+        _voice: &'a str,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let id = self.id().to_string();
+        Box::pin(async move { Err(Error::TtsUnsupported(id, "speak")) })
+    }
+
+    fn stop<'a>(&'a self, _app: &'a AppHandle) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn health<'a>(&'a self, app: &'a AppHandle, options: &'a TtsOptions)
+        -> BoxFuture<'a, DepCheck>;
+}
+
+/// Providers by id. Built-in providers are registered up front; others can
+/// be added at runtime with [`TtsProviders::register`]. Every provider is
+/// wrapped so text goes through the pronunciation lexicon first and the
+/// resulting audio through the post-processing pipeline.
+pub struct TtsProviders(DashMap<String, Arc<dyn TtsProvider>>);
+
+impl Default for TtsProviders {
+    fn default() -> Self {
+        let providers = Self(DashMap::new());
+        providers.register(Arc::new(SystemTts));
+        providers.register(Arc::new(HttpTtsServer::kittentts()));
+        providers.register(Arc::new(HttpTtsServer::opentts()));
+        providers.register(Arc::new(CommandTts));
+        providers.register(Arc::new(CloudTts::elevenlabs()));
+        providers.register(Arc::new(CloudTts::google()));
+        providers.register(Arc::new(CloudTts::grok()));
+        providers
+    }
+}
+
+impl TtsProviders {
+    pub fn register(&self, provider: Arc<dyn TtsProvider>) {
+        let id = provider.id().to_string();
+        let provider = PostProcessed(Arc::new(WithLexicon(provider)));
+        self.0.insert(id, Arc::new(provider));
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<dyn TtsProvider>, Error> {
+        self.0
+            .get(id)
+            .map(|p| p.value().clone())
+            .ok_or_else(|| Error::TtsProviderNotFound(id.to_string()))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn tts_list_providers(providers: tauri::State<'_, TtsProviders>) -> Vec<String> {
+    let mut ids: Vec<String> = providers.0.iter().map(|p| p.key().clone()).collect();
+    ids.sort();
+    ids
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_list_voices(
+    provider: String,
+    options: Option<TtsOptions>,
+    app: AppHandle,
+    providers: tauri::State<'_, TtsProviders>,
+) -> Result<Vec<TtsVoice>, Error> {
+    let provider = providers.get(&provider)?;
+    provider
+        .list_voices(&app, &options.unwrap_or_default())
+        .await
+}
+
+/// Errors come back as [`TtsFetchError`]s so the frontend can tell a
+/// cancelled or timed out request from a failed one.
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_synthesize(
+    provider: String,
+    text: String,
+    voice: String,
+    options: Option<TtsOptions>,
+    app: AppHandle,
+    providers: tauri::State<'_, TtsProviders>,
+) -> Result<Vec<u8>, TtsFetchError> {
+    let provider = providers.get(&provider)?;
+    Ok(provider
+        .synthesize(&app, &text, &voice, &options.unwrap_or_default())
+        .await?)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_speak(
+    provider: String,
+    text: String,
+    voice: String,
+    options: Option<TtsOptions>,
+    app: AppHandle,
+    providers: tauri::State<'_, TtsProviders>,
+) -> Result<(), Error> {
+    let provider = providers.get(&provider)?;
+    provider
+        .speak(&app, &text, &voice, &options.unwrap_or_default())
+        .await
+}
+
+/// Stops playback and, with a `request_id`, aborts the matching in-flight
+/// synthesis request.
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_stop(
+    provider: String,
+    request_id: Option<String>,
+    app: AppHandle,
+    providers: tauri::State<'_, TtsProviders>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), Error> {
+    if let Some(request_id) = request_id {
+        cancel_tts_fetch(request_id, state);
+    }
+    let provider = providers.get(&provider)?;
+    provider.stop(&app).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn tts_health(
+    provider: String,
+    options: Option<TtsOptions>,
+    app: AppHandle,
+    providers: tauri::State<'_, TtsProviders>,
+) -> Result<DepCheck, Error> {
+    let provider = providers.get(&provider)?;
+    Ok(provider.health(&app, &options.unwrap_or_default()).await)
+}
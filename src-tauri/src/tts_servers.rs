@@ -1,37 +1,777 @@
+use futures_util::future::BoxFuture;
 use log::{error, info, warn};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::Path;
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::{Manager, State};
+use tauri_specta::Event;
+use tokio::io::AsyncBufReadExt;
+use tokio::sync::watch;
+
+use crate::docker::{ContainerInfo, Docker, PullProgress};
+use crate::error::Error;
+use crate::kittentts_setup::{install_locked, lock_resource, parse_lock, run_pip, LOCK_FILE};
+use crate::progress::update_progress;
+use crate::tts_cache::{cache_lookup, cache_store, TtsCacheKey};
+use crate::tts_provider::{TtsOptions, TtsProvider, TtsVoice};
+use crate::AppState;
 
 // --- Fetch audio from localhost TTS servers (bypasses browser fetch issues) ---
 
-#[tauri::command]
-#[specta::specta]
-pub fn fetch_tts_audio(url: String) -> Result<Vec<u8>, String> {
-    let client = reqwest::blocking::Client::new();
+/// Why a request to a local TTS server failed. Serialized as `{ kind, ... }`
+/// so the frontend can tell a server that is still starting from a real error.
+#[derive(Debug, Clone, thiserror::Error, Serialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TtsFetchError {
+    #[error("Invalid TTS URL: {message}")]
+    InvalidUrl { message: String },
+
+    #[error("TTS server at {url} refused the connection")]
+    ConnectionRefused { url: String },
+
+    #[error("TTS request timed out after {timeout_ms} ms")]
+    #[serde(rename_all = "camelCase")]
+    Timeout { timeout_ms: u32 },
+
+    #[error("TTS server error {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("TTS fetch error: {message}")]
+    Network { message: String },
+
+    #[error("TTS request cancelled")]
+    Cancelled,
+}
+
+impl TtsFetchError {
+    pub(crate) fn from_reqwest(e: reqwest::Error, url: &str, timeout_ms: u32) -> Self {
+        if e.is_timeout() {
+            TtsFetchError::Timeout { timeout_ms }
+        } else if e.is_connect() {
+            TtsFetchError::ConnectionRefused {
+                url: url.to_string(),
+            }
+        } else if e.is_builder() {
+            TtsFetchError::InvalidUrl {
+                message: e.to_string(),
+            }
+        } else {
+            TtsFetchError::Network {
+                message: e.to_string(),
+            }
+        }
+    }
+}
+
+/// Keeps fetch errors as they are and wraps anything else, so commands
+/// that synthesize speech can answer with a `{ kind, ... }` error throughout.
+impl From<Error> for TtsFetchError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::TtsFetch(e) => e,
+            e => TtsFetchError::Network {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtsFetchOptions {
+    pub timeout_ms: u32,
+    /// Retries when the connection is refused, e.g. while the server is
+    /// still loading its model
+    pub retries: u32,
+    /// Delay before the first retry, doubled on each further attempt
+    pub backoff_ms: u32,
+}
+
+impl Default for TtsFetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 30_000,
+            retries: 4,
+            backoff_ms: 250,
+        }
+    }
+}
+
+async fn request_once(
+    client: &reqwest::Client,
+    url: &str,
+    timeout_ms: u32,
+) -> Result<Vec<u8>, TtsFetchError> {
     let response = client
-        .get(&url)
-        .timeout(std::time::Duration::from_secs(30))
+        .get(url)
+        .timeout(Duration::from_millis(timeout_ms as u64))
         .send()
-        .map_err(|e| format!("TTS fetch error: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "TTS server error {}: {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
+        .await
+        .map_err(|e| TtsFetchError::from_reqwest(e, url, timeout_ms))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TtsFetchError::Status {
+            status: status.as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
     }
 
-    response
+    let audio = response
         .bytes()
-        .map(|b| b.to_vec())
-        .map_err(|e| format!("Failed to read TTS audio: {}", e))
+        .await
+        .map_err(|e| TtsFetchError::from_reqwest(e, url, timeout_ms))?;
+    Ok(audio.to_vec())
+}
+
+/// Retries with exponential backoff while the connection is refused.
+async fn request_audio(url: &str, options: &TtsFetchOptions) -> Result<Vec<u8>, TtsFetchError> {
+    let client = reqwest::Client::new();
+    let mut attempt = 0;
+    loop {
+        match request_once(&client, url, options.timeout_ms).await {
+            Err(TtsFetchError::ConnectionRefused { .. }) if attempt < options.retries => {
+                let delay = options.backoff_ms.saturating_mul(1 << attempt.min(10));
+                info!(
+                    "TTS server at {} not reachable, retrying in {} ms",
+                    url, delay
+                );
+                tokio::time::sleep(Duration::from_millis(delay as u64)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Fetches `url` from a local TTS server. When `cache` describes the
+/// utterance being synthesized, the on-disk audio cache is consulted first
+/// and filled on success.
+pub async fn fetch_audio(
+    app: &tauri::AppHandle,
+    url: &str,
+    cache: Option<&TtsCacheKey>,
+    options: &TtsFetchOptions,
+) -> Result<Vec<u8>, TtsFetchError> {
+    if let Some(audio) = cache.and_then(|key| cache_lookup(app, key)) {
+        return Ok(audio);
+    }
+
+    let audio = request_audio(url, options).await?;
+
+    if let Some(key) = cache {
+        cache_store(app, key, &audio);
+    }
+
+    Ok(audio)
+}
+
+static NEXT_FETCH: AtomicU64 = AtomicU64::new(0);
+
+/// Runs `fetch` so that, with a `request_id`, it can be aborted through
+/// `cancel_tts_fetch`, and a newer fetch under the same id supersedes the
+/// one in flight.
+pub async fn track_fetch<F>(
+    app: &tauri::AppHandle,
+    request_id: Option<String>,
+    fetch: F,
+) -> Result<Vec<u8>, TtsFetchError>
+where
+    F: Future<Output = Result<Vec<u8>, TtsFetchError>> + Send + 'static,
+{
+    let Some(id) = request_id else {
+        return fetch.await;
+    };
+
+    let state = app.state::<AppState>();
+    let task = tokio::spawn(fetch);
+    let generation = NEXT_FETCH.fetch_add(1, Ordering::Relaxed);
+    if let Some((_, previous)) = state
+        .tts_fetches
+        .insert(id.clone(), (generation, task.abort_handle()))
+    {
+        previous.abort();
+    }
+
+    let result = task.await;
+    state
+        .tts_fetches
+        .remove_if(&id, |_, (g, _)| *g == generation);
+
+    match result {
+        Ok(result) => result,
+        Err(e) if e.is_cancelled() => Err(TtsFetchError::Cancelled),
+        Err(e) => Err(TtsFetchError::Network {
+            message: e.to_string(),
+        }),
+    }
+}
+
+/// [`fetch_audio`] under [`track_fetch`].
+pub async fn fetch_audio_tracked(
+    app: &tauri::AppHandle,
+    request_id: Option<String>,
+    url: String,
+    cache: Option<TtsCacheKey>,
+    options: TtsFetchOptions,
+) -> Result<Vec<u8>, TtsFetchError> {
+    let task_app = app.clone();
+    track_fetch(app, request_id, async move {
+        fetch_audio(&task_app, &url, cache.as_ref(), &options).await
+    })
+    .await
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_tts_audio(
+    app: tauri::AppHandle,
+    url: String,
+    cache: Option<TtsCacheKey>,
+    request_id: Option<String>,
+    options: Option<TtsFetchOptions>,
+) -> Result<Vec<u8>, TtsFetchError> {
+    fetch_audio_tracked(&app, request_id, url, cache, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_tts_fetch(request_id: String, state: State<'_, AppState>) {
+    if let Some((_, (_, handle))) = state.tts_fetches.remove(&request_id) {
+        handle.abort();
+    }
+}
+
+/// A local server speaking the OpenTTS HTTP API (`/api/tts`, `/api/voices`).
+/// Both KittenTTS and OpenTTS are driven through this.
+pub struct HttpTtsServer {
+    id: &'static str,
+    name: &'static str,
+    default_url: &'static str,
+    /// Voices are English-only regardless of the requested language
+    english_only: bool,
+    ssml: bool,
+}
+
+impl HttpTtsServer {
+    pub fn kittentts() -> Self {
+        Self {
+            id: "kittentts",
+            name: "KittenTTS",
+            default_url: "http://localhost:8192",
+            english_only: true,
+            ssml: false,
+        }
+    }
+
+    pub fn opentts() -> Self {
+        Self {
+            id: "opentts",
+            name: "OpenTTS",
+            default_url: "http://localhost:5500",
+            english_only: false,
+            ssml: true,
+        }
+    }
+
+    fn base_url<'a>(&'a self, options: &'a TtsOptions) -> &'a str {
+        options
+            .server_url
+            .as_deref()
+            .unwrap_or(self.default_url)
+            .trim_end_matches('/')
+    }
+
+    fn language<'a>(&self, options: &'a TtsOptions) -> &'a str {
+        if self.english_only {
+            "en"
+        } else {
+            options.language()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ServerVoice {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    gender: String,
+    #[serde(default)]
+    language: String,
+}
+
+impl TtsProvider for HttpTtsServer {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn supports_ssml(&self) -> bool {
+        self.ssml
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        Box::pin(async move {
+            let mut url = reqwest::Url::parse(&format!("{}/api/voices", self.base_url(options)))
+                .map_err(|e| Error::Tts(e.to_string()))?;
+            if !self.english_only {
+                if let Some(language) = &options.language {
+                    url.query_pairs_mut().append_pair("language", language);
+                }
+            }
+            let body = fetch_audio(app, url.as_str(), None, &TtsFetchOptions::default()).await?;
+            let voices: BTreeMap<String, ServerVoice> = serde_json::from_slice(&body)
+                .map_err(|e| Error::Tts(format!("Invalid voice list: {e}")))?;
+            Ok(voices
+                .into_iter()
+                .map(|(id, v)| TtsVoice {
+                    name: if v.name.is_empty() {
+                        id.clone()
+                    } else {
+                        v.name
+                    },
+                    id,
+                    language: v.language,
+                    gender: Some(v.gender).filter(|g| !g.is_empty()),
+                })
+                .collect())
+        })
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let mut url = reqwest::Url::parse_with_params(
+                &format!("{}/api/tts", self.base_url(options)),
+                &[("voice", voice), ("text", text)],
+            )
+            .map_err(|e| Error::Tts(e.to_string()))?;
+            if options.ssml && self.ssml {
+                url.query_pairs_mut().append_pair("ssml", "true");
+            }
+            let key = TtsCacheKey::new(self.id, voice, self.language(options), text);
+            Ok(fetch_audio_tracked(
+                app,
+                options.request_id.clone(),
+                url.to_string(),
+                Some(key),
+                TtsFetchOptions::default(),
+            )
+            .await?)
+        })
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        Box::pin(async move {
+            let url = format!("{}/api/voices", self.base_url(options));
+            let check = TtsFetchOptions {
+                timeout_ms: 2000,
+                retries: 0,
+                ..Default::default()
+            };
+            match fetch_audio(app, &url, None, &check).await {
+                Ok(_) => DepCheck {
+                    ok: true,
+                    label: format!("{} server running", self.name),
+                    detail: self.base_url(options).to_string(),
+                    fix_hint: String::new(),
+                },
+                Err(e) => DepCheck {
+                    ok: false,
+                    label: format!("{} server not responding", self.name),
+                    detail: e.to_string(),
+                    fix_hint: format!("Start {} from Settings > Sound", self.name),
+                },
+            }
+        })
+    }
+}
+
+// --- Command-line synthesizers ---
+
+const COMMAND_TTS_CONFIG: &str = "command-tts.json";
+
+fn default_command_timeout_ms() -> u32 {
+    30_000
+}
+
+/// An offline synthesizer binary such as espeak-ng, piper or say.
+///
+/// `command` is the program and its arguments, quoted like a shell command
+/// line but never run through a shell. Placeholders in the arguments:
+/// `{text_file}` (the text, written to a file), `{voice}`, `{lang}`,
+/// `{rate}` (0.5-2.0), `{wpm}` (words per minute, 175 at normal rate),
+/// `{length_scale}` (1 / rate, as piper expects) and `{output}` (the WAV
+/// file to write). Without `{text_file}` the text goes to stdin; without
+/// `{output}` the audio is read from stdout.
+#[derive(Serialize, Deserialize, Debug, Clone, specta::Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTtsConfig {
+    pub command: String,
+    #[serde(default = "default_command_timeout_ms")]
+    pub timeout_ms: u32,
+    /// Voice names offered to the user, as the tool spells them
+    #[serde(default)]
+    pub voices: Vec<String>,
+}
+
+/// Splits a command line into arguments. Single and double quotes group
+/// words; a backslash escapes the next character outside single quotes.
+fn split_command(command: &str) -> Result<Vec<String>, Error> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => current.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| Error::Tts("Command ends with a backslash".to_string()))?;
+                current.get_or_insert_with(String::new).push(escaped);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (_, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(Error::Tts("Unterminated quote in command".to_string()));
+    }
+    args.extend(current);
+    if args.is_empty() {
+        return Err(Error::Tts("Empty command".to_string()));
+    }
+    Ok(args)
+}
+
+/// The program in `PATH`, or at `program` if it is a path.
+fn find_program(program: &str) -> Option<std::path::PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let extensions: &[&str] = if cfg!(target_os = "windows") {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{program}{ext}")))
+        })
+        .find(|candidate| candidate.is_file())
+}
+
+/// Runs the synthesizer described by `config` and returns the audio it
+/// produced. The process is killed once `timeout_ms` has passed, and its
+/// stderr goes to the log.
+pub async fn run_tts_command(
+    config: &CommandTtsConfig,
+    text: &str,
+    voice: &str,
+    options: &TtsOptions,
+) -> Result<Vec<u8>, Error> {
+    use tokio::io::AsyncWriteExt;
+
+    let args = split_command(&config.command)?;
+    let dir = tempfile::tempdir()?;
+    let text_file = dir.path().join("text.txt");
+    let output = dir.path().join("speech.wav");
+    std::fs::write(&text_file, text)?;
+
+    let rate = options.rate.unwrap_or(1.0).clamp(0.5, 2.0);
+    let voice = match voice {
+        "" => config
+            .voices
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default(),
+        voice => voice,
+    };
+    let placeholders = [
+        ("{text_file}", text_file.to_string_lossy().to_string()),
+        ("{output}", output.to_string_lossy().to_string()),
+        ("{voice}", voice.to_string()),
+        ("{lang}", options.language().to_string()),
+        ("{rate}", format!("{:.2}", rate)),
+        ("{wpm}", format!("{}", (175.0 * rate).round())),
+        ("{length_scale}", format!("{:.2}", 1.0 / rate)),
+    ];
+    let uses = |placeholder: &str| args.iter().any(|arg| arg.contains(placeholder));
+    let (stdin_text, stdout_audio) = (!uses("{text_file}"), !uses("{output}"));
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| {
+            placeholders
+                .iter()
+                .fold(arg.clone(), |arg, (name, value)| arg.replace(name, value))
+        })
+        .collect();
+
+    let program = &args[0];
+    let name = Path::new(program)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| program.clone());
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(&args[1..])
+        .stdin(if stdin_text {
+            std::process::Stdio::piped()
+        } else {
+            std::process::Stdio::null()
+        })
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| Error::Tts(format!("Failed to start {}: {}", name, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = text.to_string();
+        // Written from a task so a tool that talks before reading can't
+        // deadlock against us
+        tokio::spawn(async move {
+            let _ = stdin.write_all(text.as_bytes()).await;
+        });
+    }
+
+    let timeout = Duration::from_millis(config.timeout_ms as u64);
+    let result = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| Error::Tts(format!("{} timed out after {} ms", name, config.timeout_ms)))??;
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    for line in stderr.lines() {
+        log_process_line(&name, line);
+    }
+    if !result.status.success() {
+        return Err(Error::Tts(format!(
+            "{} failed ({}): {}",
+            name,
+            result.status,
+            stderr.lines().last().unwrap_or_default().trim()
+        )));
+    }
+
+    let audio = if stdout_audio {
+        result.stdout
+    } else {
+        std::fs::read(&output).map_err(|e| Error::Tts(format!("{} wrote no audio: {}", name, e)))?
+    };
+    if audio.is_empty() {
+        return Err(Error::Tts(format!("{} produced no audio", name)));
+    }
+    Ok(audio)
+}
+
+fn command_tts_config_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, Error> {
+    Ok(app.path().app_data_dir()?.join(COMMAND_TTS_CONFIG))
+}
+
+pub(crate) fn read_command_tts_config(
+    app: &tauri::AppHandle,
+) -> Result<Option<CommandTtsConfig>, Error> {
+    match std::fs::read(command_tts_config_path(app)?) {
+        Ok(data) => serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| Error::Tts(format!("Invalid command TTS config: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn command_tts_dep_check(config: Option<&CommandTtsConfig>) -> DepCheck {
+    let Some(config) = config else {
+        return DepCheck {
+            ok: false,
+            label: "No synthesizer command configured".into(),
+            detail: String::new(),
+            fix_hint: "Enter a command in Settings > Sound".into(),
+        };
+    };
+    let program = match split_command(&config.command) {
+        Ok(args) => args[0].clone(),
+        Err(e) => {
+            return DepCheck {
+                ok: false,
+                label: "Invalid synthesizer command".into(),
+                detail: e.to_string(),
+                fix_hint: "Check the quoting of the command".into(),
+            }
+        }
+    };
+    match find_program(&program) {
+        Some(path) => DepCheck {
+            ok: true,
+            label: format!("{} installed", program),
+            detail: path.to_string_lossy().to_string(),
+            fix_hint: String::new(),
+        },
+        None => DepCheck {
+            ok: false,
+            label: format!("{} not found", program),
+            detail: "The program is not in PATH".into(),
+            fix_hint: format!("Install {} or give its full path", program),
+        },
+    }
+}
+
+/// Speech from a configured command-line synthesizer, see
+/// [`CommandTtsConfig`].
+pub struct CommandTts;
+
+impl TtsProvider for CommandTts {
+    fn id(&self) -> &str {
+        "command"
+    }
+
+    fn list_voices<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<TtsVoice>, Error>> {
+        Box::pin(async move {
+            let voices = read_command_tts_config(app)?
+                .map(|config| config.voices)
+                .unwrap_or_default();
+            Ok(voices
+                .into_iter()
+                .map(|voice| TtsVoice {
+                    id: voice.clone(),
+                    name: voice,
+                    language: String::new(),
+                    gender: None,
+                })
+                .collect())
+        })
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        text: &'a str,
+        voice: &'a str,
+        options: &'a TtsOptions,
+    ) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let config = read_command_tts_config(app)?
+                .ok_or_else(|| Error::Tts("No synthesizer command configured".to_string()))?;
+            // The command is part of the key, so audio from a previous
+            // command isn't replayed after the template changes
+            let key = TtsCacheKey::new(
+                self.id(),
+                &format!("{}\0{}", voice, config.command),
+                options.language(),
+                text,
+            );
+            if let Some(audio) = cache_lookup(app, &key) {
+                return Ok(audio);
+            }
+            let audio = run_tts_command(&config, text, voice, options).await?;
+            cache_store(app, &key, &audio);
+            Ok(audio)
+        })
+    }
+
+    fn health<'a>(
+        &'a self,
+        app: &'a tauri::AppHandle,
+        _options: &'a TtsOptions,
+    ) -> BoxFuture<'a, DepCheck> {
+        Box::pin(async move { check_command_tts(app.clone()) })
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn command_tts_get_config(app: tauri::AppHandle) -> Result<Option<CommandTtsConfig>, Error> {
+    read_command_tts_config(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn command_tts_set_config(
+    config: CommandTtsConfig,
+    app: tauri::AppHandle,
+) -> Result<(), Error> {
+    split_command(&config.command)?;
+    let path = command_tts_config_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_vec_pretty(&config).map_err(std::io::Error::from)?;
+    std::fs::write(path, data)?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn check_command_tts(app_handle: tauri::AppHandle) -> DepCheck {
+    match read_command_tts_config(&app_handle) {
+        Ok(config) => command_tts_dep_check(config.as_ref()),
+        Err(e) => DepCheck {
+            ok: false,
+            label: "Invalid synthesizer command".into(),
+            detail: e.to_string(),
+            fix_hint: "Enter the command again in Settings > Sound".into(),
+        },
+    }
+}
+
+#[derive(Default)]
 pub struct TtsServerState {
-    pub kittentts_pid: Mutex<Option<u32>>,
+    kittentts: Mutex<Option<KittenTtsSupervisor>>,
+    /// Port of the last supervised KittenTTS server, kept after it is stopped
+    kittentts_last_port: Mutex<Option<u16>>,
+}
+
+impl TtsServerState {
+    /// Port of the supervised KittenTTS server, if one was started.
+    pub fn kittentts_port(&self) -> Option<u16> {
+        self.kittentts.lock().ok()?.as_ref().map(|s| s.port)
+    }
+
+    /// Stops supervised servers and kills their processes. Called on app exit.
+    pub fn shutdown(&self) {
+        if let Ok(mut kittentts) = self.kittentts.lock() {
+            if let Some(supervisor) = kittentts.take() {
+                supervisor.stop();
+            }
+        }
+    }
 }
 
 // --- Dependency checking ---
@@ -45,18 +785,18 @@ pub struct DepCheck {
 }
 
 /// Shared path-resolution logic for KittenTTS resources.
-struct KittenTtsPaths {
-    script: Option<String>,
-    python: Option<String>,
-    venv_dir: Option<String>,
-    models_dir: Option<String>,
+pub(crate) struct KittenTtsPaths {
+    pub script: Option<String>,
+    pub python: Option<String>,
+    pub venv_dir: Option<String>,
+    pub models_dir: Option<String>,
 }
 
 /// Python binary name within a venv, platform-aware.
 #[cfg(not(target_os = "windows"))]
-const VENV_PYTHON: &str = "bin/python";
+pub(crate) const VENV_PYTHON: &str = "bin/python";
 #[cfg(target_os = "windows")]
-const VENV_PYTHON: &str = "Scripts/python.exe";
+pub(crate) const VENV_PYTHON: &str = "Scripts/python.exe";
 
 /// Pip binary name within a venv, platform-aware.
 #[cfg(not(target_os = "windows"))]
@@ -64,7 +804,7 @@ const VENV_PIP: &str = "bin/pip";
 #[cfg(target_os = "windows")]
 const VENV_PIP: &str = "Scripts/pip.exe";
 
-fn find_kittentts_paths(app_handle: &tauri::AppHandle) -> KittenTtsPaths {
+pub(crate) fn find_kittentts_paths(app_handle: &tauri::AppHandle) -> KittenTtsPaths {
     let mut script_candidates: Vec<std::path::PathBuf> = Vec::new();
     let mut venv_candidates: Vec<std::path::PathBuf> = Vec::new();
     let mut models_candidates: Vec<std::path::PathBuf> = Vec::new();
@@ -138,8 +878,12 @@ fn find_kittentts_paths(app_handle: &tauri::AppHandle) -> KittenTtsPaths {
 
 #[tauri::command]
 #[specta::specta]
-pub fn check_docker_installed() -> DepCheck {
-    match Command::new("docker").arg("--version").output() {
+pub async fn check_docker_installed() -> DepCheck {
+    match tokio::process::Command::new("docker")
+        .arg("--version")
+        .output()
+        .await
+    {
         Ok(output) if output.status.success() => {
             let ver = String::from_utf8_lossy(&output.stdout).trim().to_string();
             DepCheck {
@@ -164,19 +908,22 @@ pub fn check_docker_installed() -> DepCheck {
 
 #[tauri::command]
 #[specta::specta]
-pub fn check_docker_running() -> DepCheck {
-    match Command::new("docker").arg("info").output() {
-        Ok(output) if output.status.success() => DepCheck {
+pub async fn check_docker_running() -> DepCheck {
+    match Docker::from_env().ping().await {
+        Ok(()) => DepCheck {
             ok: true,
             label: "Docker running".into(),
             detail: "Docker daemon is active".into(),
             fix_hint: String::new(),
         },
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        Err(e) => {
+            let permission_denied = matches!(
+                &e,
+                Error::Io(io) if io.kind() == std::io::ErrorKind::PermissionDenied
+            );
             let hint = if cfg!(target_os = "windows") {
                 "Start Docker Desktop from the Start menu"
-            } else if stderr.contains("permission denied") {
+            } else if permission_denied {
                 "sudo usermod -aG docker $USER && newgrp docker"
             } else {
                 "sudo systemctl start docker"
@@ -184,53 +931,41 @@ pub fn check_docker_running() -> DepCheck {
             DepCheck {
                 ok: false,
                 label: "Docker not running".into(),
-                detail: stderr
-                    .lines()
-                    .next()
-                    .unwrap_or("Docker daemon is not active")
-                    .to_string(),
+                detail: e.to_string(),
                 fix_hint: hint.into(),
             }
         }
-        _ => DepCheck {
-            ok: false,
-            label: "Docker not running".into(),
-            detail: "Could not communicate with Docker".into(),
-            fix_hint: if cfg!(target_os = "windows") {
-                "Start Docker Desktop from the Start menu".into()
-            } else {
-                "sudo systemctl start docker".into()
-            },
-        },
     }
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn check_opentts_image() -> DepCheck {
-    match Command::new("docker")
-        .args(["images", "-q", "synesthesiam/opentts:en"])
-        .output()
-    {
-        Ok(output) if output.status.success() => {
-            let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if id.is_empty() {
-                DepCheck {
-                    ok: false,
-                    label: "OpenTTS image not found".into(),
-                    detail: "Image synesthesiam/opentts:en is not pulled".into(),
-                    fix_hint: "Use the Setup Wizard to download the image".into(),
-                }
-            } else {
-                DepCheck {
-                    ok: true,
-                    label: "OpenTTS image ready".into(),
-                    detail: format!("Image ID: {}", id),
-                    fix_hint: String::new(),
-                }
+pub async fn check_opentts_image(lang: Option<String>) -> DepCheck {
+    let image = match opentts_image(lang.as_deref()) {
+        Ok(image) => image,
+        Err(e) => {
+            return DepCheck {
+                ok: false,
+                label: "OpenTTS image not found".into(),
+                detail: e,
+                fix_hint: "Use the Setup Wizard to download the image".into(),
             }
         }
-        _ => DepCheck {
+    };
+    match Docker::from_env().image_id(&image).await {
+        Ok(Some(id)) => DepCheck {
+            ok: true,
+            label: "OpenTTS image ready".into(),
+            detail: format!("Image ID: {}", id),
+            fix_hint: String::new(),
+        },
+        Ok(None) => DepCheck {
+            ok: false,
+            label: "OpenTTS image not found".into(),
+            detail: format!("Image {} is not pulled", image),
+            fix_hint: "Use the Setup Wizard to download the image".into(),
+        },
+        Err(_) => DepCheck {
             ok: false,
             label: "Could not check images".into(),
             detail: "Docker may not be running".into(),
@@ -389,6 +1124,13 @@ pub fn setup_kittentts_venv(app_handle: tauri::AppHandle) -> Result<String, Stri
         return Ok("Already installed".to_string());
     }
 
+    create_kittentts_venv(&app_handle)?;
+    install_kittentts_packages(&app_handle, &mut |_| {})
+}
+
+/// Creates the KittenTTS virtual environment in the app data dir, replacing
+/// an incomplete one.
+pub fn create_kittentts_venv(app_handle: &tauri::AppHandle) -> Result<String, String> {
     // Create venv in user-writable app data dir
     let kittentts_dir = app_handle
         .path()
@@ -447,162 +1189,317 @@ pub fn setup_kittentts_venv(app_handle: tauri::AppHandle) -> Result<String, Stri
             ));
         }
     }
+    Ok("Virtual environment created".to_string())
+}
 
-    // Find requirements.txt using same priority as other resources
-    let mut req_candidates: Vec<std::path::PathBuf> = Vec::new();
+/// A file shipped next to the KittenTTS server script, looked up with the
+/// same priority as the script itself.
+pub(crate) fn kittentts_resource(
+    app_handle: &tauri::AppHandle,
+    name: &str,
+) -> Option<std::path::PathBuf> {
+    let mut candidates: Vec<std::path::PathBuf> = Vec::new();
     if let Ok(res_dir) = app_handle
         .path()
         .resolve("scripts", tauri::path::BaseDirectory::Resource)
     {
-        req_candidates.push(res_dir.join("requirements.txt"));
+        candidates.push(res_dir.join(name));
     }
     #[cfg(target_os = "linux")]
-    req_candidates.push(std::path::PathBuf::from(
-        "/usr/lib/en-parlant/scripts/requirements.txt",
-    ));
-    req_candidates.push(std::path::PathBuf::from("scripts/requirements.txt"));
+    candidates.push(std::path::PathBuf::from("/usr/lib/en-parlant/scripts").join(name));
+    candidates.push(std::path::PathBuf::from("scripts").join(name));
+    candidates.into_iter().find(|p| p.exists())
+}
 
-    let requirements = req_candidates
-        .iter()
-        .find(|p| p.exists())
-        .map(|p| p.to_string_lossy().to_string());
+/// Installs the server's Python packages into the KittenTTS venv, from the
+/// hashed lock when one is shipped, passing pip's output lines to `on_output`.
+pub fn install_kittentts_packages(
+    app_handle: &tauri::AppHandle,
+    on_output: &mut dyn FnMut(&str),
+) -> Result<String, String> {
+    let venv_str = find_kittentts_paths(app_handle)
+        .venv_dir
+        .ok_or("Virtual environment not found")?;
+
+    if let Some(lock) = lock_resource(app_handle, LOCK_FILE) {
+        let python = Path::new(&venv_str).join(VENV_PYTHON);
+        let lock_text = std::fs::read_to_string(&lock).map_err(|e| e.to_string())?;
+        install_locked(&python, &parse_lock(&lock_text), None, false, on_output)?;
+        return Ok("Locked packages installed".to_string());
+    }
+
+    let requirements =
+        kittentts_resource(app_handle, "requirements.txt").map(|p| p.to_string_lossy().to_string());
 
     // Install packages
     info!("Installing Python packages (kittentts, flask, soundfile, numpy)...");
     let pip = format!("{}/{}", venv_str, VENV_PIP);
-    let install = if let Some(req_path) = requirements {
+    let mut install = Command::new(&pip);
+    if let Some(req_path) = requirements {
         info!("Using requirements.txt: {}", req_path);
-        Command::new(&pip)
-            .args(["install", "-r", &req_path])
-            .output()
-            .map_err(|e| format!("Failed to run pip: {}", e))?
+        install.args(["install", "-r", &req_path]);
     } else {
-        Command::new(&pip)
-            .args(["install", "kittentts", "flask", "soundfile", "numpy"])
-            .output()
-            .map_err(|e| format!("Failed to run pip: {}", e))?
-    };
-
-    if install.status.success() {
-        info!("KittenTTS packages installed successfully");
-        Ok("Packages installed successfully".to_string())
-    } else {
-        Err(format!(
-            "pip install failed: {}",
-            String::from_utf8_lossy(&install.stderr).trim()
-        ))
+        install.args(["install", "kittentts", "flask", "soundfile", "numpy"]);
     }
+    run_pip(&mut install, on_output)?;
+
+    info!("KittenTTS packages installed successfully");
+    Ok("Packages installed successfully".to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn setup_opentts_load(tarball_path: String) -> Result<String, String> {
-    let load = Command::new("docker")
-        .args(["load", "-i", &tarball_path])
-        .output()
-        .map_err(|e| format!("Failed to run docker load: {}", e))?;
-
-    if load.status.success() {
-        // Clean up tarball after successful load
-        let _ = std::fs::remove_file(&tarball_path);
-        Ok("Image loaded successfully".to_string())
-    } else {
-        Err(format!(
-            "docker load failed: {}",
-            String::from_utf8_lossy(&load.stderr).trim()
-        ))
-    }
+pub async fn setup_opentts_load(tarball_path: String) -> Result<String, String> {
+    Docker::from_env()
+        .load(Path::new(&tarball_path))
+        .await
+        .map_err(|e| format!("Loading the OpenTTS image failed: {}", e))?;
+    // Clean up tarball after successful load
+    let _ = std::fs::remove_file(&tarball_path);
+    Ok("Image loaded successfully".to_string())
 }
 
+/// Pulls the image for `lang`, reporting layer download progress under
+/// [`OPENTTS_PULL_PROGRESS_ID`].
 #[tauri::command]
 #[specta::specta]
-pub fn setup_opentts_pull() -> Result<String, String> {
-    let pull = Command::new("docker")
-        .args(["pull", "synesthesiam/opentts:en"])
-        .output()
-        .map_err(|e| format!("Failed to run docker pull: {}", e))?;
-
-    if pull.status.success() {
-        Ok("Image pulled successfully".to_string())
-    } else {
-        Err(format!(
-            "docker pull failed: {}",
-            String::from_utf8_lossy(&pull.stderr).trim()
-        ))
-    }
+pub async fn setup_opentts_pull(
+    lang: Option<String>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let image = opentts_image(lang.as_deref())?;
+    let id = OPENTTS_PULL_PROGRESS_ID.to_string();
+    let mut progress = PullProgress::default();
+    Docker::from_env()
+        .pull(&image, |event| {
+            progress.update(event);
+            let _ = update_progress(
+                &state.progress_state,
+                &app,
+                id.clone(),
+                progress.percent(),
+                false,
+            );
+        })
+        .await
+        .map_err(|e| format!("Pulling {} failed: {}", image, e))?;
+    let _ = update_progress(&state.progress_state, &app, id, 100.0, true);
+    Ok("Image pulled successfully".to_string())
 }
 
 // --- OpenTTS (Docker) ---
 
-#[tauri::command]
-#[specta::specta]
-pub fn opentts_start() -> Result<String, String> {
-    // Try starting an existing stopped container first
-    let start = Command::new("docker")
-        .args(["start", "opentts"])
-        .output()
-        .map_err(|e| format!("Failed to run docker: {}", e))?;
+const OPENTTS_CONTAINER: &str = "opentts";
+const OPENTTS_CONTAINER_PORT: &str = "5500/tcp";
+pub(crate) const OPENTTS_DEFAULT_PORT: u16 = 5500;
+pub const OPENTTS_PULL_PROGRESS_ID: &str = "opentts_image_pull";
 
-    if start.status.success() {
-        return Ok("started".to_string());
+/// `synesthesiam/opentts:<lang>`, English by default.
+fn opentts_image(lang: Option<&str>) -> Result<String, String> {
+    let lang = lang.unwrap_or("en");
+    if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid OpenTTS language: {:?}", lang));
     }
+    Ok(format!("synesthesiam/opentts:{}", lang))
+}
 
-    // Container doesn't exist — create it
-    let run = Command::new("docker")
-        .args([
-            "run",
-            "-d",
-            "--name",
-            "opentts",
-            "-p",
-            "5500:5500",
-            "synesthesiam/opentts:en",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run docker: {}", e))?;
+#[derive(Serialize, Debug, Clone, specta::Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenTtsStatus {
+    pub exists: bool,
+    pub running: bool,
+    /// Docker's container state, e.g. "running" or "exited"
+    pub state: Option<String>,
+    /// Healthcheck status, if the image defines one
+    pub health: Option<String>,
+    pub image: Option<String>,
+    pub host_port: Option<u16>,
+}
 
-    if run.status.success() {
-        Ok("started".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&run.stderr).trim().to_string())
+impl From<Option<ContainerInfo>> for OpenTtsStatus {
+    fn from(info: Option<ContainerInfo>) -> Self {
+        match info {
+            Some(info) => Self {
+                exists: true,
+                running: info.running,
+                host_port: info
+                    .port_bindings
+                    .get(OPENTTS_CONTAINER_PORT)
+                    .and_then(|ports| ports.first().copied()),
+                state: Some(info.status),
+                health: info.health,
+                image: Some(info.image),
+            },
+            None => Self {
+                exists: false,
+                running: false,
+                state: None,
+                health: None,
+                image: None,
+                host_port: None,
+            },
+        }
     }
 }
 
+/// Starts the OpenTTS container, recreating it when the requested language
+/// image or host port differs from the existing one.
 #[tauri::command]
 #[specta::specta]
-pub fn opentts_stop() -> Result<String, String> {
-    let output = Command::new("docker")
-        .args(["stop", "opentts"])
-        .output()
-        .map_err(|e| format!("Failed to run docker: {}", e))?;
-
-    if output.status.success() {
-        Ok("stopped".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+pub async fn opentts_start(lang: Option<String>, port: Option<u16>) -> Result<String, String> {
+    let image = opentts_image(lang.as_deref())?;
+    let port = port.unwrap_or(OPENTTS_DEFAULT_PORT);
+    let docker = Docker::from_env();
+
+    let current = OpenTtsStatus::from(
+        docker
+            .inspect_container(OPENTTS_CONTAINER)
+            .await
+            .map_err(|e| e.to_string())?,
+    );
+    let reusable =
+        current.image.as_deref() == Some(image.as_str()) && current.host_port == Some(port);
+    if current.exists && !reusable {
+        info!(
+            "Recreating OpenTTS container for {} on port {}",
+            image, port
+        );
+        docker
+            .remove_container(OPENTTS_CONTAINER)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    if !reusable {
+        if docker
+            .image_id(&image)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err(format!("Image {} is not pulled", image));
+        }
+        docker
+            .create_container(OPENTTS_CONTAINER, &image, OPENTTS_CONTAINER_PORT, port)
+            .await
+            .map_err(|e| e.to_string())?;
     }
+    docker
+        .start_container(OPENTTS_CONTAINER)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok("started".to_string())
 }
 
-// --- KittenTTS (Python server) ---
+#[tauri::command]
+#[specta::specta]
+pub async fn opentts_stop() -> Result<String, String> {
+    Docker::from_env()
+        .stop_container(OPENTTS_CONTAINER)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok("stopped".to_string())
+}
 
 #[tauri::command]
 #[specta::specta]
-pub fn kittentts_start(
-    app_handle: tauri::AppHandle,
-    state: State<'_, TtsServerState>,
-    threads: Option<u32>,
-) -> Result<String, String> {
-    let mut pid_lock = state.kittentts_pid.lock().map_err(|e| e.to_string())?;
+pub async fn opentts_status() -> Result<OpenTtsStatus, String> {
+    Docker::from_env()
+        .inspect_container(OPENTTS_CONTAINER)
+        .await
+        .map(OpenTtsStatus::from)
+        .map_err(|e| e.to_string())
+}
 
-    // Check if already running
-    if let Some(pid) = *pid_lock {
-        if is_process_alive(pid) {
-            return Ok("already running".to_string());
+// --- KittenTTS (Python server) ---
+
+pub(crate) const KITTENTTS_DEFAULT_PORT: u16 = 8192;
+/// First start downloads the model from HuggingFace, so be generous
+const KITTENTTS_READY_TIMEOUT: Duration = Duration::from_secs(300);
+const KITTENTTS_MAX_RESTARTS: u32 = 5;
+/// A server that stayed up this long gets a fresh restart budget
+const KITTENTTS_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Serialize, specta::Type, PartialEq)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum KittenTtsStatus {
+    Starting,
+    Ready,
+    #[serde(rename_all = "camelCase")]
+    Crashed {
+        exit_code: Option<i32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Restarting {
+        attempt: u32,
+        delay_ms: u32,
+    },
+    Stopped,
+}
+
+#[derive(Clone, Debug, Serialize, specta::Type, Event)]
+pub struct KittenTtsStatusEvent {
+    pub port: u16,
+    pub status: KittenTtsStatus,
+}
+
+struct KittenTtsSupervisor {
+    port: u16,
+    status: Arc<Mutex<KittenTtsStatus>>,
+    pid: Arc<Mutex<Option<u32>>>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl KittenTtsSupervisor {
+    fn status(&self) -> KittenTtsStatus {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(KittenTtsStatus::Stopped)
+    }
+
+    fn stop(&self) {
+        let _ = self.shutdown.send(true);
+        // Don't rely on the supervisor task getting to run, e.g. on app exit
+        if let Some(pid) = self.pid.lock().ok().and_then(|mut pid| pid.take()) {
+            kill_process(pid);
         }
-        *pid_lock = None;
     }
+}
 
-    let paths = find_kittentts_paths(&app_handle);
+/// The preferred port if it's free, otherwise any free port.
+fn pick_port(preferred: u16) -> u16 {
+    if std::net::TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
+        return preferred;
+    }
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .map(|a| a.port())
+        .unwrap_or(preferred)
+}
+
+/// HuggingFace cache the model is downloaded into: a bundled or dev models
+/// dir if there is one, else one under the app data dir (user-writable).
+pub(crate) fn kittentts_models_dir(app_handle: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    if let Some(models) = find_kittentts_paths(app_handle).models_dir {
+        return Some(models.into());
+    }
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .ok()?
+        .join("kittentts")
+        .join("models");
+    let _ = std::fs::create_dir_all(&dir);
+    Some(dir)
+}
+
+fn kittentts_command(
+    app_handle: &tauri::AppHandle,
+    threads: Option<u32>,
+    port: u16,
+) -> Result<Command, String> {
+    let paths = find_kittentts_paths(app_handle);
 
     let script = paths.script.ok_or("KittenTTS server script not found")?;
 
@@ -627,23 +1524,14 @@ pub fn kittentts_start(
         c
     };
 
+    cmd.args(["--port", &port.to_string()]);
     if let Some(t) = threads {
         if t > 0 {
             cmd.args(["--threads", &t.to_string()]);
         }
     }
 
-    // Point HuggingFace cache at app data models dir (user-writable)
-    let models_dir = if let Some(ref models) = paths.models_dir {
-        models.clone()
-    } else if let Ok(app_data) = app_handle.path().app_data_dir() {
-        let dir = app_data.join("kittentts").join("models");
-        let _ = std::fs::create_dir_all(&dir);
-        dir.to_string_lossy().to_string()
-    } else {
-        String::new()
-    };
-    if !models_dir.is_empty() {
+    if let Some(models_dir) = kittentts_models_dir(app_handle) {
         cmd.env("HF_HOME", &models_dir);
     }
 
@@ -655,64 +1543,228 @@ pub fn kittentts_start(
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
 
-    info!("Starting KittenTTS server");
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped());
+    Ok(cmd)
+}
 
-    let mut child = cmd
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start KittenTTS: {}", e))?;
-
-    let pid = child.id();
-    *pid_lock = Some(pid);
-
-    // Log stderr in a background thread so crashes are visible
-    if let Some(stderr) = child.stderr.take() {
-        std::thread::spawn(move || {
-            use std::io::BufRead;
-            let reader = std::io::BufReader::new(stderr);
-            for line in reader.lines() {
-                match line {
-                    Ok(line)
-                        if line.contains("Error")
-                            || line.contains("error")
-                            || line.contains("Traceback") =>
-                    {
-                        error!("KittenTTS: {}", line);
-                    }
-                    Ok(line) if line.contains("Warning") || line.contains("warning") => {
-                        warn!("KittenTTS: {}", line);
-                    }
-                    Ok(line) if !line.trim().is_empty() => {
-                        info!("KittenTTS: {}", line);
+/// Logs a line of a TTS process's stderr at a level guessed from its text.
+fn log_process_line(name: &str, line: &str) {
+    if line.contains("Error") || line.contains("error") || line.contains("Traceback") {
+        error!("{}: {}", name, line);
+    } else if line.contains("Warning") || line.contains("warning") {
+        warn!("{}: {}", name, line);
+    } else if !line.trim().is_empty() {
+        info!("{}: {}", name, line);
+    }
+}
+
+pub(crate) async fn kittentts_healthy(client: &reqwest::Client, port: u16) -> bool {
+    client
+        .get(format!("http://127.0.0.1:{}/api/health", port))
+        .timeout(Duration::from_secs(1))
+        .send()
+        .await
+        .is_ok_and(|r| r.status().is_success())
+}
+
+enum Readiness {
+    Ready,
+    Exited(Option<i32>),
+    TimedOut,
+    Shutdown,
+}
+
+async fn wait_until_ready(
+    child: &mut tokio::process::Child,
+    port: u16,
+    shutdown: &mut watch::Receiver<bool>,
+) -> Readiness {
+    let client = reqwest::Client::new();
+    let deadline = tokio::time::Instant::now() + KITTENTTS_READY_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::select! {
+            exit = child.wait() => {
+                return Readiness::Exited(exit.ok().and_then(|s| s.code()));
+            }
+            _ = shutdown.changed() => return Readiness::Shutdown,
+            _ = tokio::time::sleep(Duration::from_millis(500)) => {
+                if kittentts_healthy(&client, port).await {
+                    return Readiness::Ready;
+                }
+            }
+        }
+    }
+    Readiness::TimedOut
+}
+
+/// Runs the server until shutdown, restarting it with exponential backoff
+/// when it exits or never becomes ready.
+async fn supervise_kittentts(
+    app: tauri::AppHandle,
+    threads: Option<u32>,
+    port: u16,
+    status: Arc<Mutex<KittenTtsStatus>>,
+    pid: Arc<Mutex<Option<u32>>>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let set_status = |new: KittenTtsStatus| {
+        if let Ok(mut status) = status.lock() {
+            *status = new.clone();
+        }
+        let _ = KittenTtsStatusEvent { port, status: new }.emit(&app);
+    };
+
+    let mut attempt = 0;
+    loop {
+        set_status(KittenTtsStatus::Starting);
+        info!("Starting KittenTTS server on port {}", port);
+
+        let spawned = kittentts_command(&app, threads, port).and_then(|cmd| {
+            tokio::process::Command::from(cmd)
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(|e| format!("Failed to start KittenTTS: {}", e))
+        });
+        let mut exit_code = None;
+        match spawned {
+            Ok(mut child) => {
+                if let Ok(mut pid) = pid.lock() {
+                    *pid = child.id();
+                }
+                // Log stderr so crashes are visible
+                if let Some(stderr) = child.stderr.take() {
+                    tokio::spawn(async move {
+                        let mut lines = tokio::io::BufReader::new(stderr).lines();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            log_process_line("KittenTTS", &line);
+                        }
+                    });
+                }
+
+                let started = tokio::time::Instant::now();
+                match wait_until_ready(&mut child, port, &mut shutdown).await {
+                    Readiness::Ready => {
+                        set_status(KittenTtsStatus::Ready);
+                        info!("KittenTTS server ready on port {}", port);
+                        tokio::select! {
+                            exit = child.wait() => {
+                                exit_code = exit.ok().and_then(|s| s.code());
+                            }
+                            _ = shutdown.changed() => {}
+                        }
+                        if started.elapsed() > KITTENTTS_STABLE_AFTER {
+                            attempt = 0;
+                        }
                     }
-                    Err(e) => {
-                        error!("KittenTTS stderr read error: {}", e);
-                        break;
+                    Readiness::Exited(code) => exit_code = code,
+                    Readiness::TimedOut => {
+                        warn!("KittenTTS server did not become ready, restarting");
                     }
-                    _ => {}
+                    Readiness::Shutdown => {}
+                }
+                let _ = child.kill().await;
+                if let Ok(mut pid) = pid.lock() {
+                    *pid = None;
                 }
             }
-        });
+            Err(e) => error!("{}", e),
+        }
+
+        if *shutdown.borrow() {
+            break;
+        }
+
+        set_status(KittenTtsStatus::Crashed { exit_code });
+        if attempt >= KITTENTTS_MAX_RESTARTS {
+            error!("KittenTTS server keeps crashing, giving up");
+            break;
+        }
+        attempt += 1;
+        let delay_ms = (1000u32 << (attempt - 1)).min(30_000);
+        set_status(KittenTtsStatus::Restarting { attempt, delay_ms });
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(delay_ms as u64)) => {}
+            _ = shutdown.changed() => break,
+        }
     }
 
-    Ok("started".to_string())
+    set_status(KittenTtsStatus::Stopped);
+    info!("KittenTTS supervisor stopped");
+}
+
+/// Starts the supervised KittenTTS server and returns its port. Readiness is
+/// reported through `KittenTtsStatusEvent`s.
+#[tauri::command]
+#[specta::specta]
+pub fn kittentts_start(
+    app_handle: tauri::AppHandle,
+    state: State<'_, TtsServerState>,
+    threads: Option<u32>,
+    port: Option<u16>,
+) -> Result<u16, String> {
+    let mut supervisor = state.kittentts.lock().map_err(|e| e.to_string())?;
+
+    // Check if already running
+    if let Some(running) = supervisor.as_ref() {
+        if running.status() != KittenTtsStatus::Stopped {
+            return Ok(running.port);
+        }
+    }
+
+    let port = pick_port(port.unwrap_or(KITTENTTS_DEFAULT_PORT));
+    // Fail early if the server can't be found at all
+    kittentts_command(&app_handle, threads, port)?;
+
+    let status = Arc::new(Mutex::new(KittenTtsStatus::Starting));
+    let pid = Arc::new(Mutex::new(None));
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(supervise_kittentts(
+        app_handle,
+        threads,
+        port,
+        status.clone(),
+        pid.clone(),
+        shutdown_rx,
+    ));
+
+    if let Ok(mut last_port) = state.kittentts_last_port.lock() {
+        *last_port = Some(port);
+    }
+    *supervisor = Some(KittenTtsSupervisor {
+        port,
+        status,
+        pid,
+        shutdown,
+    });
+    Ok(port)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn kittentts_status(state: State<'_, TtsServerState>) -> Option<KittenTtsStatusEvent> {
+    let supervisor = state.kittentts.lock().ok()?;
+    supervisor.as_ref().map(|s| KittenTtsStatusEvent {
+        port: s.port,
+        status: s.status(),
+    })
 }
 
 #[tauri::command]
 #[specta::specta]
 pub fn kittentts_stop(state: State<'_, TtsServerState>) -> Result<String, String> {
-    let mut pid_lock = state.kittentts_pid.lock().map_err(|e| e.to_string())?;
+    let mut supervisor = state.kittentts.lock().map_err(|e| e.to_string())?;
 
-    if let Some(pid) = pid_lock.take() {
-        kill_process(pid);
+    if let Some(running) = supervisor.take() {
+        running.stop();
         Ok("stopped".to_string())
     } else {
-        // Try to kill by port as fallback
+        // Try to kill by port as fallback, but only a port we started a
+        // server on, so an unrelated process is never hit
         #[cfg(not(target_os = "windows"))]
-        {
+        if let Some(port) = state.kittentts_last_port.lock().ok().and_then(|p| *p) {
             let _ = Command::new("sh")
-                .args(["-c", "fuser -k 8192/tcp 2>/dev/null"])
+                .args(["-c", &format!("fuser -k {}/tcp 2>/dev/null", port)])
                 .output();
         }
         Ok("stopped".to_string())
@@ -721,28 +1773,6 @@ pub fn kittentts_stop(state: State<'_, TtsServerState>) -> Result<String, String
 
 // --- Platform helpers ---
 
-fn is_process_alive(pid: u32) -> bool {
-    #[cfg(not(target_os = "windows"))]
-    {
-        Command::new("kill")
-            .args(["-0", &pid.to_string()])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    }
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("tasklist")
-            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
-            .output()
-            .map(|o| {
-                let out = String::from_utf8_lossy(&o.stdout);
-                out.contains(&pid.to_string())
-            })
-            .unwrap_or(false)
-    }
-}
-
 fn kill_process(pid: u32) {
     #[cfg(not(target_os = "windows"))]
     {
@@ -755,3 +1785,219 @@ fn kill_process(pid: u32) {
             .output();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn options(retries: u32) -> TtsFetchOptions {
+        TtsFetchOptions {
+            timeout_ms: 500,
+            retries,
+            backoff_ms: 50,
+        }
+    }
+
+    /// A port nothing is listening on, at least for a moment.
+    async fn free_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    async fn serve_once(listener: TcpListener, response: &'static [u8]) {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0; 1024];
+        let _ = socket.read(&mut buf).await;
+        socket.write_all(response).await.unwrap();
+    }
+
+    #[test]
+    fn synthesis_errors_keep_their_kind() {
+        let cancelled = TtsFetchError::from(Error::TtsFetch(TtsFetchError::Cancelled));
+        assert_eq!(
+            serde_json::to_value(cancelled).unwrap(),
+            serde_json::json!({ "kind": "cancelled" })
+        );
+        let other = TtsFetchError::from(Error::NoStdin);
+        assert_eq!(
+            serde_json::to_value(other).unwrap(),
+            serde_json::json!({ "kind": "network", "message": "No stdin" })
+        );
+    }
+
+    #[tokio::test]
+    async fn refused_without_retries() {
+        let url = format!("http://127.0.0.1:{}/api/tts", free_port().await);
+        assert!(matches!(
+            request_audio(&url, &options(0)).await,
+            Err(TtsFetchError::ConnectionRefused { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn retries_until_server_starts() {
+        let port = free_port().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(120)).await;
+            let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+            serve_once(
+                listener,
+                b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nRIFF",
+            )
+            .await;
+        });
+        let url = format!("http://127.0.0.1:{}/api/tts", port);
+        assert_eq!(request_audio(&url, &options(5)).await.unwrap(), b"RIFF");
+    }
+
+    #[tokio::test]
+    async fn server_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/tts", listener.local_addr().unwrap());
+        tokio::spawn(serve_once(
+            listener,
+            b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\nConnection: close\r\n\r\noops",
+        ));
+        match request_audio(&url, &options(0)).await {
+            Err(TtsFetchError::Status { status, body }) => {
+                assert_eq!(status, 500);
+                assert_eq!(body, "oops");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/tts", listener.local_addr().unwrap());
+        // Accept but never answer
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        assert!(matches!(
+            request_audio(&url, &options(3)).await,
+            Err(TtsFetchError::Timeout { timeout_ms: 500 })
+        ));
+    }
+
+    #[test]
+    fn splits_quoted_commands() {
+        assert_eq!(
+            split_command(r#"piper --model "/opt/My Voices/en.onnx" --output_file {output}"#)
+                .unwrap(),
+            vec![
+                "piper",
+                "--model",
+                "/opt/My Voices/en.onnx",
+                "--output_file",
+                "{output}"
+            ]
+        );
+        assert_eq!(
+            split_command(r"say -v 'Good News' a\ b ''").unwrap(),
+            vec!["say", "-v", "Good News", "a b", ""]
+        );
+        assert!(split_command("espeak-ng \"unterminated").is_err());
+        assert!(split_command("   ").is_err());
+    }
+
+    /// A synthesizer stub that checks its arguments and copies a WAV file.
+    #[cfg(unix)]
+    fn stub_script(dir: &Path, body: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("synth.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_writes_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let wav = crate::audio::encode_wav(&crate::audio::Pcm::silence(16000, 50)).unwrap();
+        std::fs::write(dir.path().join("in.wav"), &wav).unwrap();
+        let script = stub_script(
+            dir.path(),
+            &format!(
+                "echo \"voice $1 at $2 wpm\" >&2\n\
+                 grep -q 'Knight takes e5' \"$3\" || exit 3\n\
+                 cp '{}' \"$4\"",
+                dir.path().join("in.wav").display()
+            ),
+        );
+        let config = CommandTtsConfig {
+            command: format!("'{}' {{voice}} {{wpm}} {{text_file}} {{output}}", script),
+            timeout_ms: 5000,
+            voices: vec!["en-us".to_string()],
+        };
+        let options = TtsOptions {
+            rate: Some(1.2),
+            ..Default::default()
+        };
+        let audio = run_tts_command(&config, "Knight takes e5", "", &options)
+            .await
+            .unwrap();
+        assert_eq!(audio, wav);
+
+        let err = run_tts_command(&config, "Queen h5", "", &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("synth.sh failed"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_reads_stdin_and_writes_stdout() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = stub_script(dir.path(), "printf RIFF; cat");
+        let config = CommandTtsConfig {
+            command: script,
+            timeout_ms: 5000,
+            voices: Vec::new(),
+        };
+        let audio = run_tts_command(&config, "e4", "", &TtsOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(audio, b"RIFFe4");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = stub_script(dir.path(), "sleep 5");
+        let config = CommandTtsConfig {
+            command: format!("{} {{output}}", script),
+            timeout_ms: 200,
+            voices: Vec::new(),
+        };
+        let started = std::time::Instant::now();
+        let err = run_tts_command(&config, "e4", "", &TtsOptions::default())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("timed out after 200 ms"),
+            "{}",
+            err
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn dep_check_finds_program() {
+        assert!(!command_tts_dep_check(None).ok);
+        let config = |command: &str| CommandTtsConfig {
+            command: command.to_string(),
+            timeout_ms: 1000,
+            voices: Vec::new(),
+        };
+        assert!(!command_tts_dep_check(Some(&config("no-such-synthesizer-xyz {output}"))).ok);
+        #[cfg(unix)]
+        assert!(command_tts_dep_check(Some(&config("sh -c true"))).ok);
+    }
+}
@@ -0,0 +1,527 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::{Path, PathBuf},
+};
+
+use pgn_reader::Nag;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use shakmaty::{
+    san::{San, SanPlus, Suffix},
+    CastlingSide, Role,
+};
+use specta::Type;
+use tauri::Manager;
+
+use crate::{
+    audio::{decode, encode_wav, Pcm, PostProcess, PostProcessState},
+    error::Error,
+    narration::{nags_to_spoken, vocab},
+    sound::SoundServerPort,
+    tts_provider::{TtsOptions, TtsProviders},
+};
+
+/// One word of a narration that a pre-recorded clip can stand in for.
+#[derive(Serialize, Deserialize, Debug, Clone, Type, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum ClipToken {
+    /// Lowercase English piece name, e.g. "knight"
+    Piece(String),
+    File(char),
+    Rank(char),
+    Square(String),
+    Takes,
+    Check,
+    Checkmate,
+    PromotesTo,
+    CastlesKingside,
+    CastlesQueenside,
+    MoveNumber(u32),
+    Nag(u8),
+    /// Free text, only ever synthesized unless the manifest has it verbatim
+    Text(String),
+}
+
+fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::King => "king",
+        Role::Queen => "queen",
+        Role::Rook => "rook",
+        Role::Bishop => "bishop",
+        Role::Knight => "knight",
+        Role::Pawn => "pawn",
+    }
+}
+
+impl ClipToken {
+    /// Manifest key, e.g. "piece:knight", "square:e4" or "takes".
+    pub fn key(&self) -> String {
+        match self {
+            ClipToken::Piece(name) => format!("piece:{}", name),
+            ClipToken::File(file) => format!("file:{}", file),
+            ClipToken::Rank(rank) => format!("rank:{}", rank),
+            ClipToken::Square(square) => format!("square:{}", square),
+            ClipToken::Takes => "takes".to_string(),
+            ClipToken::Check => "check".to_string(),
+            ClipToken::Checkmate => "checkmate".to_string(),
+            ClipToken::PromotesTo => "promotes_to".to_string(),
+            ClipToken::CastlesKingside => "castles_kingside".to_string(),
+            ClipToken::CastlesQueenside => "castles_queenside".to_string(),
+            ClipToken::MoveNumber(n) => format!("number:{}", n),
+            ClipToken::Nag(nag) => format!("nag:{}", nag),
+            ClipToken::Text(text) => format!("text:{}", text.trim().to_lowercase()),
+        }
+    }
+
+    /// Text to synthesize when the pack has no clip for this token.
+    pub fn spoken(&self, lang: &str) -> String {
+        let vocab = vocab(lang);
+        match self {
+            ClipToken::Piece(name) => {
+                let role = match name.as_str() {
+                    "king" => Role::King,
+                    "queen" => Role::Queen,
+                    "rook" => Role::Rook,
+                    "bishop" => Role::Bishop,
+                    "knight" => Role::Knight,
+                    _ => return name.clone(),
+                };
+                vocab.piece(role).to_string()
+            }
+            ClipToken::File(c) | ClipToken::Rank(c) => c.to_string(),
+            ClipToken::Square(square) => square.clone(),
+            ClipToken::Takes => vocab.takes.to_string(),
+            ClipToken::Check => vocab.check.to_string(),
+            ClipToken::Checkmate => vocab.checkmate.to_string(),
+            ClipToken::PromotesTo => vocab.promotes_to.to_string(),
+            ClipToken::CastlesKingside => vocab.castles_kingside.to_string(),
+            ClipToken::CastlesQueenside => vocab.castles_queenside.to_string(),
+            ClipToken::MoveNumber(n) => format!("{},", n),
+            ClipToken::Nag(nag) => nags_to_spoken(&[Nag(*nag)], lang),
+            ClipToken::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// Tokens for a move the way `narrate_move` speaks it: move number, the
+/// move itself, then the quality NAGs.
+pub fn move_tokens(san: &SanPlus, nags: &[Nag], ply: u32) -> Vec<ClipToken> {
+    let mut tokens = vec![ClipToken::MoveNumber(ply.div_ceil(2))];
+    match &san.san {
+        San::Castle(CastlingSide::KingSide) => tokens.push(ClipToken::CastlesKingside),
+        San::Castle(CastlingSide::QueenSide) => tokens.push(ClipToken::CastlesQueenside),
+        San::Normal {
+            role,
+            file,
+            rank,
+            capture,
+            to,
+            promotion,
+        } => {
+            if *role != Role::Pawn {
+                tokens.push(ClipToken::Piece(role_name(*role).to_string()));
+            }
+            match (file, rank) {
+                (Some(file), Some(rank)) => {
+                    tokens.push(ClipToken::Square(format!("{}{}", file.char(), rank.char())))
+                }
+                (Some(file), None) => tokens.push(ClipToken::File(file.char())),
+                (None, Some(rank)) => tokens.push(ClipToken::Rank(rank.char())),
+                (None, None) => {}
+            }
+            if *capture {
+                tokens.push(ClipToken::Takes);
+            }
+            tokens.push(ClipToken::Square(to.to_string()));
+            if let Some(promotion) = promotion {
+                tokens.push(ClipToken::PromotesTo);
+                tokens.push(ClipToken::Piece(role_name(*promotion).to_string()));
+            }
+        }
+        San::Put { role, to } => {
+            if *role != Role::Pawn {
+                tokens.push(ClipToken::Piece(role_name(*role).to_string()));
+            }
+            tokens.push(ClipToken::Square(to.to_string()));
+        }
+        San::Null => {}
+    }
+    match san.suffix {
+        Some(Suffix::Check) => tokens.push(ClipToken::Check),
+        Some(Suffix::Checkmate) => tokens.push(ClipToken::Checkmate),
+        None => {}
+    }
+    tokens.extend(
+        nags.iter()
+            .filter(|nag| (1..=6).contains(&nag.0))
+            .map(|nag| ClipToken::Nag(nag.0)),
+    );
+    tokens
+}
+
+/// `manifest.json` of a clip pack: token keys mapped to WAV files relative
+/// to the pack directory.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClipManifest {
+    pub language: String,
+    pub clips: HashMap<String, PathBuf>,
+}
+
+pub struct ClipPack {
+    dir: PathBuf,
+    manifest: ClipManifest,
+    post_process: Option<PostProcess>,
+}
+
+impl ClipPack {
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        let manifest = std::fs::read_to_string(dir.join("manifest.json"))?;
+        let manifest = serde_json::from_str(&manifest)
+            .map_err(|e| Error::Tts(format!("Invalid clip manifest: {}", e)))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            manifest,
+            post_process: None,
+        })
+    }
+
+    /// Runs every clip through `settings`, so recorded clips match the
+    /// level and rate of synthesized ones.
+    pub fn with_post_process(mut self, settings: PostProcess) -> Self {
+        self.post_process = Some(settings);
+        self
+    }
+
+    /// Whether the pack has a clip for `token` that decodes, i.e. whether
+    /// `compose` uses the clip rather than the fallback.
+    pub fn has(&self, token: &ClipToken) -> bool {
+        self.decoded(token).is_some()
+    }
+
+    /// The decoded clip, `None` if the pack doesn't have a usable one.
+    fn clip(&self, token: &ClipToken) -> Option<Pcm> {
+        let pcm = self.decoded(token)?;
+        Some(match &self.post_process {
+            Some(settings) => settings.apply(&pcm),
+            None => pcm,
+        })
+    }
+
+    fn decoded(&self, token: &ClipToken) -> Option<Pcm> {
+        let file = self.manifest.clips.get(&token.key())?;
+        let bytes = std::fs::read(self.dir.join(file)).ok()?;
+        match decode(&bytes) {
+            Ok(pcm) => Some(pcm),
+            Err(e) => {
+                log::warn!("Skipping clip {}: {}", file.display(), e);
+                None
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipFallback {
+    pub provider: String,
+    pub voice: String,
+    pub server_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeOptions {
+    /// Clip pack directory name
+    pub pack: String,
+    /// Silence between clips
+    #[serde(default = "default_gap_ms")]
+    pub gap_ms: u32,
+    /// Overlap of each clip with the audio before it, when there is no gap
+    #[serde(default = "default_crossfade_ms")]
+    pub crossfade_ms: u32,
+    /// Voice for tokens the pack has no clip for; the system voice if unset
+    pub fallback: Option<ClipFallback>,
+}
+
+fn default_gap_ms() -> u32 {
+    80
+}
+
+fn default_crossfade_ms() -> u32 {
+    10
+}
+
+/// Concatenates the clips for `tokens`. Runs of tokens without a clip are
+/// spoken together by `fallback`, so the synthesized part keeps its
+/// natural prosody.
+pub async fn compose<F, Fut>(
+    pack: &ClipPack,
+    tokens: &[ClipToken],
+    options: &ComposeOptions,
+    mut fallback: F,
+) -> Result<Pcm, Error>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Pcm, Error>>,
+{
+    let lang = pack.manifest.language.as_str();
+    let mut parts = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
+    for token in tokens {
+        match pack.clip(token) {
+            Some(clip) => {
+                if !missing.is_empty() {
+                    parts.push(fallback(missing.join(" ")).await?);
+                    missing.clear();
+                }
+                parts.push(clip);
+            }
+            None => {
+                let text = token.spoken(lang);
+                if !text.is_empty() {
+                    missing.push(text);
+                }
+            }
+        }
+    }
+    if !missing.is_empty() {
+        parts.push(fallback(missing.join(" ")).await?);
+    }
+
+    let mut parts = parts.into_iter();
+    let mut out = parts
+        .next()
+        .ok_or_else(|| Error::Tts("Nothing to compose".to_string()))?;
+    for part in parts {
+        // Cross-fading after a gap would only fade the silence into the clip
+        if options.gap_ms == 0 {
+            out.append_crossfade(&part, options.crossfade_ms);
+        } else {
+            out.append_silence(options.gap_ms);
+            out.append(&part);
+        }
+    }
+    Ok(out)
+}
+
+/// Packs are looked up in the app data dir first, so downloaded packs can
+/// override the bundled ones under `sound/clips`.
+fn find_pack(app: &tauri::AppHandle, pack: &str) -> Result<PathBuf, Error> {
+    if pack.is_empty() || pack.contains(['/', '\\', '.']) {
+        return Err(Error::Tts(format!("Invalid clip pack name: {}", pack)));
+    }
+    let candidates = [
+        app.path().app_data_dir()?.join("clips"),
+        app.path()
+            .resolve("sound/clips", tauri::path::BaseDirectory::Resource)?,
+    ];
+    candidates
+        .into_iter()
+        .map(|dir| dir.join(pack))
+        .find(|dir| dir.join("manifest.json").exists())
+        .ok_or_else(|| Error::Tts(format!("Clip pack not found: {}", pack)))
+}
+
+/// Directory of composed narrations, served under `/composed` by the sound
+/// server.
+pub fn composed_dir(app: &tauri::AppHandle) -> Result<PathBuf, Error> {
+    Ok(app.path().app_data_dir()?.join("tts-composed"))
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposedClip {
+    pub path: PathBuf,
+    /// Sound server URL, if the sound server is running
+    pub url: Option<String>,
+    pub duration_ms: u32,
+    /// Tokens that were synthesized because the pack has no clip for them
+    pub missing: Vec<ClipToken>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn move_clip_tokens(
+    san: String,
+    annotations: Vec<String>,
+    ply: u32,
+) -> Result<Vec<ClipToken>, Error> {
+    let san = SanPlus::from_ascii(san.as_bytes())?;
+    let nags: Vec<Nag> = annotations
+        .iter()
+        .filter_map(|a| Nag::from_ascii(a.as_bytes()).ok())
+        .collect();
+    Ok(move_tokens(&san, &nags, ply))
+}
+
+/// Stitches the clips for `tokens` into one WAV file. Results are kept by
+/// content, so composing the same narration again reuses the file.
+#[tauri::command]
+#[specta::specta]
+pub async fn compose_clips(
+    tokens: Vec<ClipToken>,
+    options: ComposeOptions,
+    app: tauri::AppHandle,
+    port: tauri::State<'_, SoundServerPort>,
+) -> Result<ComposedClip, Error> {
+    let post_process = app.state::<PostProcessState>().get(&app);
+    let pack =
+        ClipPack::load(&find_pack(&app, &options.pack)?)?.with_post_process(post_process.clone());
+    let missing: Vec<ClipToken> = tokens.iter().filter(|t| !pack.has(t)).cloned().collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "{}\0{}\0{}\0{:?}\0{:?}\0",
+        options.pack, options.gap_ms, options.crossfade_ms, options.fallback, post_process
+    ));
+    for token in &tokens {
+        hasher.update(token.key());
+        hasher.update([0u8]);
+    }
+    let name: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let name = format!("{}.wav", name);
+
+    let dir = composed_dir(&app)?;
+    let path = dir.join(&name);
+    let duration_ms = match std::fs::read(&path).ok().and_then(|b| decode(&b).ok()) {
+        Some(pcm) => pcm.duration_ms(),
+        None => {
+            let fallback = options.fallback.clone().unwrap_or(ClipFallback {
+                provider: "system".to_string(),
+                voice: String::new(),
+                server_url: None,
+            });
+            let tts_options = TtsOptions {
+                language: Some(pack.manifest.language.clone()),
+                server_url: fallback.server_url.clone(),
+                ..Default::default()
+            };
+            let provider = app.state::<TtsProviders>().get(&fallback.provider)?;
+            let (app, provider, voice, tts_options) =
+                (&app, &provider, fallback.voice.as_str(), &tts_options);
+            let pcm = compose(&pack, &tokens, &options, move |text| async move {
+                decode(&provider.synthesize(app, &text, voice, tts_options).await?)
+            })
+            .await?;
+            std::fs::create_dir_all(&dir)?;
+            std::fs::write(&path, encode_wav(&pcm)?)?;
+            pcm.duration_ms()
+        }
+    };
+
+    Ok(ComposedClip {
+        url: (port.0 != 0).then(|| format!("http://127.0.0.1:{}/composed/{}", port.0, name)),
+        path,
+        duration_ms,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(san: &str, nags: &[u8], ply: u32) -> Vec<ClipToken> {
+        let nags: Vec<Nag> = nags.iter().map(|&n| Nag(n)).collect();
+        move_tokens(&san.parse().unwrap(), &nags, ply)
+    }
+
+    #[test]
+    fn tokens_for_moves() {
+        assert_eq!(
+            tokens("Nbxd7+", &[2], 21),
+            vec![
+                ClipToken::MoveNumber(11),
+                ClipToken::Piece("knight".to_string()),
+                ClipToken::File('b'),
+                ClipToken::Takes,
+                ClipToken::Square("d7".to_string()),
+                ClipToken::Check,
+                ClipToken::Nag(2),
+            ]
+        );
+        assert_eq!(
+            tokens("exd8=Q#", &[], 40),
+            vec![
+                ClipToken::MoveNumber(20),
+                ClipToken::File('e'),
+                ClipToken::Takes,
+                ClipToken::Square("d8".to_string()),
+                ClipToken::PromotesTo,
+                ClipToken::Piece("queen".to_string()),
+                ClipToken::Checkmate,
+            ]
+        );
+        assert_eq!(
+            tokens("O-O", &[10], 9),
+            vec![ClipToken::MoveNumber(5), ClipToken::CastlesKingside]
+        );
+    }
+
+    #[test]
+    fn spoken_fallback_text() {
+        assert_eq!(
+            ClipToken::Piece("knight".to_string()).spoken("de"),
+            "Springer"
+        );
+        assert_eq!(ClipToken::Nag(4).spoken("en"), "Blunder.");
+        assert_eq!(ClipToken::MoveNumber(3).spoken("en"), "3,");
+    }
+
+    fn write_clip(dir: &Path, name: &str, ms: u32) {
+        let pcm = Pcm {
+            sample_rate: 8000,
+            samples: vec![0.5; 8 * ms as usize],
+        };
+        std::fs::write(dir.join(name), encode_wav(&pcm).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn composes_clips_with_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        write_clip(dir.path(), "knight.wav", 300);
+        write_clip(dir.path(), "f3.wav", 200);
+        std::fs::write(dir.path().join("broken.wav"), b"not audio").unwrap();
+        std::fs::write(
+            dir.path().join("manifest.json"),
+            r#"{"language":"en","clips":{"piece:knight":"knight.wav","square:f3":"f3.wav","check":"broken.wav"}}"#,
+        )
+        .unwrap();
+        let pack = ClipPack::load(dir.path()).unwrap();
+        let mut options = ComposeOptions {
+            pack: "test".to_string(),
+            gap_ms: 50,
+            crossfade_ms: 10,
+            fallback: None,
+        };
+        // A clip that doesn't decode counts as missing
+        assert!(!pack.has(&ClipToken::Check));
+
+        let mut synthesized = Vec::new();
+        let pcm = compose(&pack, &tokens("Nf3+", &[1], 3), &options, |text| {
+            synthesized.push(text);
+            async { Ok(Pcm::silence(16000, 100)) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(synthesized, vec!["2,", "check Good move."]);
+        // 100 + 300 + 200 + 100 ms of audio and three 50 ms gaps
+        assert_eq!(pcm.duration_ms(), 700 + 150);
+        assert_eq!(pcm.sample_rate, 16000);
+
+        // Without a gap, each of the three joins overlaps by 10 ms
+        options.gap_ms = 0;
+        let pcm = compose(&pack, &tokens("Nf3+", &[1], 3), &options, |_| async {
+            Ok(Pcm::silence(16000, 100))
+        })
+        .await
+        .unwrap();
+        assert_eq!(pcm.duration_ms(), 700 - 30);
+    }
+}
@@ -10,6 +10,7 @@ import {
 } from "@mantine/core";
 import { Notifications } from "@mantine/notifications";
 import { createRouter, RouterProvider } from "@tanstack/react-router";
+import { invoke } from "@tauri-apps/api/core";
 import { getMatches } from "@tauri-apps/plugin-cli";
 import { attachConsole, error, info, warn } from "@tauri-apps/plugin-log";
 import { getDefaultStore, useAtom, useAtomValue } from "jotai";
@@ -19,10 +20,12 @@ import { DndProvider } from "react-dnd";
 import { HTML5Backend } from "react-dnd-html5-backend";
 import {
   activeTabAtom,
+  enginePoolConfigAtom,
   fontSizeAtom,
   pieceSetAtom,
   primaryColorAtom,
   referenceDbAtom,
+  sessionsAtom,
   spellCheckAtom,
   storedDatabasesDirAtom,
   storedDocumentDirAtom,
@@ -55,6 +58,7 @@ const colorSchemeManager = localStorageColorSchemeManager({
 import ErrorComponent from "@/components/ErrorComponent";
 import { getDatabasesDir, getDocumentDir, getEnginesDir, getPuzzlesDir } from "@/utils/directories";
 import { initUserAgent } from "@/utils/http";
+import { lichessTokenSecret } from "@/utils/lichess/api";
 import { routeTree } from "./routeTree.gen";
 
 export type Dirs = {
@@ -118,6 +122,44 @@ const preloadReferenceDb = async (store: ReturnType<typeof getDefaultStore>) =>
   }
 };
 
+const applyEnginePoolConfig = async (store: ReturnType<typeof getDefaultStore>) => {
+  const config = store.get(enginePoolConfigAtom);
+  if (!config) return;
+  const result = await commands.enginePoolConfigure(config);
+  if (result.status === "error") {
+    warn(`Failed to configure the engine pool: ${result.error}`);
+  }
+};
+
+/** Moves lichess tokens that older versions kept in the saved sessions into the secrets store. */
+const migrateLichessTokens = async (store: ReturnType<typeof getDefaultStore>) => {
+  const legacy = store.get(sessionsAtom).filter((s) => s.lichess?.accessToken);
+  if (legacy.length === 0) return;
+  try {
+    await Promise.all(
+      legacy.map((s) =>
+        invoke("secrets_put", {
+          name: lichessTokenSecret(s.lichess!.username),
+          value: s.lichess!.accessToken,
+        }),
+      ),
+    );
+  } catch (e) {
+    warn(`Failed to move lichess tokens to the secrets store: ${e}`);
+    return;
+  }
+  store.set(sessionsAtom, (sessions) =>
+    sessions.map((s) => {
+      if (!s.lichess?.accessToken) return s;
+      const { accessToken: _, ...lichess } = s.lichess;
+      return {
+        ...s,
+        lichess: { ...lichess, tokenSecret: lichessTokenSecret(lichess.username) },
+      };
+    }),
+  );
+};
+
 function useAppStartup() {
   const initialized = useRef(false);
   const [, setTabs] = useAtom(tabsAtom);
@@ -149,6 +191,8 @@ function useAppStartup() {
         warn(`Failed to parse CLI args: ${e}`);
       }
 
+      await migrateLichessTokens(store);
+      await applyEnginePoolConfig(store);
       await preloadReferenceDb(store);
 
       return detach;
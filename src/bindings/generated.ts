@@ -21,6 +21,18 @@ async getBestMoves(id: string, engine: string, tab: string, goMode: GoMode, opti
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Narrates the current top line of a running `get_best_moves` session, or
+ * returns nothing when the engine has not reported a line yet.
+ */
+async narrateBestLine(id: string, tab: string, options: EvalNarrationOptions) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("narrate_best_line", { id, tab, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async analyzeGame(id: string, engine: string, goMode: GoMode, options: AnalysisOptions, uciOptions: EngineOption[]) : Promise<Result<MoveAnalysis[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("analyze_game", { id, engine, goMode, options, uciOptions }) };
@@ -45,6 +57,18 @@ async stopEngine(engine: string, tab: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Tells a pondering session that the expected move was played. The caller
+ * should pass the same go mode without `ponder` from then on.
+ */
+async ponderHit(engine: string, tab: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ponder_hit", { engine, tab }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async killEngine(engine: string, tab: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("kill_engine", { engine, tab }) };
@@ -69,6 +93,17 @@ async getEngineLogs(engine: string, tab: string) : Promise<Result<EngineLog[], s
     else return { status: "error", error: e  as any };
 }
 },
+async enginePoolStatus() : Promise<EnginePoolStatus> {
+    return await TAURI_INVOKE("engine_pool_status");
+},
+async enginePoolConfigure(config: EnginePoolConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("engine_pool_configure", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async memorySize() : Promise<number> {
     return await TAURI_INVOKE("memory_size");
 },
@@ -128,6 +163,41 @@ async getEngineConfig(path: string) : Promise<Result<EngineConfig, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Checks options against what the engine at `path` declares, returning
+ * every problem found; none means the options can be used as they are.
+ */
+async validateEngineOptions(path: string, options: EngineOption[]) : Promise<Result<OptionProblem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_engine_options", { path, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async enginePresetsList(path: string) : Promise<EnginePreset[]> {
+    return await TAURI_INVOKE("engine_presets_list", { path });
+},
+/**
+ * Saves a preset for the engine at `path`. The options are checked against
+ * the engine's declared options first and stored in its spelling.
+ */
+async enginePresetSave(path: string, name: string, options: EngineOption[]) : Promise<Result<EnginePreset, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("engine_preset_save", { path, name, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async enginePresetDelete(path: string, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("engine_preset_delete", { path, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async fileExists(path: string) : Promise<Result<boolean, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("file_exists", { path }) };
@@ -192,6 +262,39 @@ async lexPgn(pgn: string) : Promise<Result<Token[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async buildNarration(request: NarrationRequest) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("build_narration", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Describes a position for blindfold play or a screen reader. The text is
+ * plain, so it can be spoken by any TTS provider.
+ */
+async describePosition(source: PositionSource, query: PositionQuery, lang: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("describe_position", { source, query, lang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Matches a typed or dictated move against the legal moves of a position.
+ * When several moves fit, they are all returned so the player can be asked
+ * which one they meant.
+ */
+async parseSpokenMove(source: PositionSource, text: string, lang: string) : Promise<Result<SpokenMoveMatch, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("parse_spoken_move", { source, text, lang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async isBmi2Compatible() : Promise<boolean> {
     return await TAURI_INVOKE("is_bmi2_compatible");
 },
@@ -286,6 +389,101 @@ async authenticate(username: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * GETs `url` with the bearer token stored under `auth_secret`, for API
+ * calls that need an account. Returns the response body.
+ */
+async authorizedFetch(url: string, authSecret: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("authorized_fetch", { url, authSecret }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsStatus() : Promise<Result<SecretsStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsUnlock(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_unlock", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Protects the store with `passphrase`, or with the machine key when
+ * `None`.
+ */
+async secretsSetPassphrase(passphrase: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_set_passphrase", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsPut(name: string, value: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_put", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsGet(name: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_get", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether a secret is set, for names the webview can't read back.
+ */
+async secretsHas(name: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_has", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsDelete(name: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_delete", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secretsList() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes every secret, e.g. when the passphrase is lost or the store was
+ * copied from another machine.
+ */
+async secretsReset() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secrets_reset") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async writeGame(filePath: string, n: number, pgn: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("write_game", { filePath, n, pgn }) };
@@ -294,9 +492,13 @@ async writeGame(filePath: string, n: number, pgn: string) : Promise<Result<null,
     else return { status: "error", error: e  as any };
 }
 },
-async downloadFile(id: string, url: string, path: string, token: string | null, finalize: boolean | null, totalSize: number | null) : Promise<Result<null, string>> {
+/**
+ * Downloads `url` to `path`, unpacking archives. With `auth_secret` the
+ * request carries the bearer token stored under that name.
+ */
+async downloadFile(id: string, url: string, path: string, authSecret: string | null, finalize: boolean | null, totalSize: number | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("download_file", { id, url, path, token, finalize, totalSize }) };
+    return { status: "ok", data: await TAURI_INVOKE("download_file", { id, url, path, authSecret, finalize, totalSize }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -398,9 +600,13 @@ async getGameState(gameId: string) : Promise<Result<GameState, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async makeGameMove(gameId: string, uci: string) : Promise<Result<GameState, string>> {
+/**
+ * Plays a move given as UCI, SAN or a phrase like "knight takes f3" in the
+ * narration language `lang` (English when unset).
+ */
+async makeGameMove(gameId: string, input: string, lang: string | null) : Promise<Result<GameState, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("make_game_move", { gameId, uci }) };
+    return { status: "ok", data: await TAURI_INVOKE("make_game_move", { gameId, input, lang }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -460,9 +666,13 @@ async getSoundServerPort() : Promise<Result<number, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async systemTtsSpeak(text: string, rate: number | null, volume: number | null, pitch: number | null) : Promise<Result<null, string>> {
+/**
+ * Returns the utterance id used in `SystemTtsUtteranceEvent`s. Without
+ * `interrupt` the text is queued behind the current utterance.
+ */
+async systemTtsSpeak(text: string, interrupt: boolean | null, rate: number | null, volume: number | null, pitch: number | null) : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("system_tts_speak", { text, rate, volume, pitch }) };
+    return { status: "ok", data: await TAURI_INVOKE("system_tts_speak", { text, interrupt, rate, volume, pitch }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -492,9 +702,13 @@ async systemTtsSetVoice(voiceId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async openttsStart() : Promise<Result<string, string>> {
+/**
+ * Starts the OpenTTS container, recreating it when the requested language
+ * image or host port differs from the existing one.
+ */
+async openttsStart(lang: string | null, port: number | null) : Promise<Result<string, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("opentts_start") };
+    return { status: "ok", data: await TAURI_INVOKE("opentts_start", { lang, port }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -508,9 +722,21 @@ async openttsStop() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async kittenttsStart(threads: number | null) : Promise<Result<string, string>> {
+async openttsStatus() : Promise<Result<OpenTtsStatus, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("kittentts_start", { threads }) };
+    return { status: "ok", data: await TAURI_INVOKE("opentts_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Starts the supervised KittenTTS server and returns its port. Readiness is
+ * reported through `KittenTtsStatusEvent`s.
+ */
+async kittenttsStart(threads: number | null, port: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kittentts_start", { threads, port }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -524,116 +750,552 @@ async kittenttsStop() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async fetchTtsAudio(url: string) : Promise<Result<number[], string>> {
+async kittenttsStatus() : Promise<KittenTtsStatusEvent | null> {
+    return await TAURI_INVOKE("kittentts_status");
+},
+async moveClipTokens(san: string, annotations: string[], ply: number) : Promise<Result<ClipToken[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("fetch_tts_audio", { url }) };
+    return { status: "ok", data: await TAURI_INVOKE("move_clip_tokens", { san, annotations, ply }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async checkDockerInstalled() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_docker_installed");
+async lexiconList(lang: string) : Promise<Result<LexiconEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lexicon_list", { lang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkDockerRunning() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_docker_running");
+/**
+ * Adds `entry`, or replaces the entry at `index`. Returns the updated list.
+ */
+async lexiconPut(lang: string, index: number | null, entry: LexiconEntry) : Promise<Result<LexiconEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lexicon_put", { lang, index, entry }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkOpenttsImage() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_opentts_image");
+async lexiconDelete(lang: string, index: number) : Promise<Result<LexiconEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lexicon_delete", { lang, index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkPythonInstalled() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_python_installed");
+/**
+ * Shows what `text` turns into with the saved lexicon, plus an unsaved
+ * `draft` entry when given.
+ */
+async lexiconTest(lang: string, text: string, draft: LexiconEntry | null) : Promise<Result<LexiconTest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lexicon_test", { lang, text, draft }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkKittenttsVenv() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_kittentts_venv");
+/**
+ * Stitches the clips for `tokens` into one WAV file. Results are kept by
+ * content, so composing the same narration again reuses the file.
+ */
+async composeClips(tokens: ClipToken[], options: ComposeOptions) : Promise<Result<ComposedClip, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compose_clips", { tokens, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkKittenttsPackages() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_kittentts_packages");
+async fetchTtsAudio(url: string, cache: TtsCacheKey | null, requestId: string | null, options: TtsFetchOptions | null) : Promise<Result<number[], TtsFetchError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_tts_audio", { url, cache, requestId, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async checkKittenttsScript() : Promise<DepCheck> {
-    return await TAURI_INVOKE("check_kittentts_script");
+async cancelTtsFetch(requestId: string) : Promise<void> {
+    await TAURI_INVOKE("cancel_tts_fetch", { requestId });
 },
-async setupKittenttsVenv() : Promise<Result<string, string>> {
+async ttsListProviders() : Promise<string[]> {
+    return await TAURI_INVOKE("tts_list_providers");
+},
+async ttsListVoices(provider: string, options: TtsOptions | null) : Promise<Result<TtsVoice[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("setup_kittentts_venv") };
+    return { status: "ok", data: await TAURI_INVOKE("tts_list_voices", { provider, options }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setupOpenttsLoad(tarballPath: string) : Promise<Result<string, string>> {
+/**
+ * Errors come back as [`TtsFetchError`]s so the frontend can tell a
+ * cancelled or timed out request from a failed one.
+ */
+async ttsSynthesize(provider: string, text: string, voice: string, options: TtsOptions | null) : Promise<Result<number[], TtsFetchError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("setup_opentts_load", { tarballPath }) };
+    return { status: "ok", data: await TAURI_INVOKE("tts_synthesize", { provider, text, voice, options }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setupOpenttsPull() : Promise<Result<string, string>> {
+async ttsSpeak(provider: string, text: string, voice: string, options: TtsOptions | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("setup_opentts_pull") };
+    return { status: "ok", data: await TAURI_INVOKE("tts_speak", { provider, text, voice, options }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Linux system-install updater: downloads the .deb from GitHub and installs
- * via pkexec dpkg -i (shows one polkit auth prompt).
+ * Stops playback and, with a `request_id`, aborts the matching in-flight
+ * synthesis request.
  */
-async downloadAndInstallLinux(version: string) : Promise<Result<null, string>> {
+async ttsStop(provider: string, requestId: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("download_and_install_linux", { version }) };
+    return { status: "ok", data: await TAURI_INVOKE("tts_stop", { provider, requestId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async ttsHealth(provider: string, options: TtsOptions | null) : Promise<Result<DepCheck, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_health", { provider, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
+},
+async ttsCacheStats() : Promise<Result<TtsCacheStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_cache_stats") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
-
-/** user-defined events **/
-
-
-export const events = __makeEvents__<{
-bestMovesPayload: BestMovesPayload,
-clockUpdateEvent: ClockUpdateEvent,
-databaseProgress: DatabaseProgress,
-gameMoveEvent: GameMoveEvent,
-gameOverEvent: GameOverEvent,
-progressEvent: ProgressEvent
-}>({
-bestMovesPayload: "best-moves-payload",
-clockUpdateEvent: "clock-update-event",
-databaseProgress: "database-progress",
-gameMoveEvent: "game-move-event",
-gameOverEvent: "game-over-event",
-progressEvent: "progress-event"
-})
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
-export type AnalysisOptions = { fen: string; moves: string[]; annotateNovelties: boolean; referenceDb: string | null; reversed: boolean }
-export type BestMoves = { nodes: number; depth: number; score: Score; uciMoves: string[]; sanMoves: string[]; multipv: number; nps: number }
-export type BestMovesPayload = { bestLines: BestMoves[]; engine: string; tab: string; fen: string; moves: string[]; progress: number }
-export type ClockUpdateEvent = { gameId: string; whiteTime: bigint | null; blackTime: bigint | null }
-export type DatabaseInfo = { title: string; description: string; player_count: number; event_count: number; game_count: number; storage_size: bigint; filename: string; indexed: boolean }
-export type DatabaseProgress = { id: string; progress: number }
-export type DepCheck = { ok: boolean; label: string; detail: string; fix_hint: string }
-export type DrawReason = "stalemate" | "insufficientMaterial" | "threefoldRepetition" | "fiftyMoveRule" | "agreement"
-export type EngineConfig = { name: string; options: UciOptionConfig[] }
-export type EngineLog = { type: "gui"; value: string } | { type: "engine"; value: string }
+},
+async ttsCacheSetLimit(maxBytes: bigint) : Promise<Result<TtsCacheStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_cache_set_limit", { maxBytes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Evicts least recently used entries down to `max_bytes`, or to the
+ * configured limit when not given.
+ */
+async ttsCachePrune(maxBytes: bigint | null) : Promise<Result<TtsCacheStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_cache_prune", { maxBytes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async ttsCacheClear() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_cache_clear") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async audioPostProcessGet() : Promise<PostProcess> {
+    return await TAURI_INVOKE("audio_post_process_get");
+},
+async audioPostProcessSet(settings: PostProcess) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("audio_post_process_set", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Usage per provider for `month` ("2026-10"), by default the current one.
+ */
+async ttsUsage(month: string | null) : Promise<UsageReport> {
+    return await TAURI_INVOKE("tts_usage", { month });
+},
+/**
+ * Sets or, with no budget, removes a provider's monthly budget.
+ */
+async ttsUsageSetBudget(provider: string, budget: UsageBudget | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_usage_set_budget", { provider, budget }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Renders a whole game as one narrated WAV file: header intro, every move
+ * with its comment, variations set off by longer pauses, and the result.
+ */
+async exportAudiobook(id: string, source: AudiobookSource, options: AudiobookOptions, dest: string) : Promise<Result<AudiobookSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_audiobook", { id, source, options, dest }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelTtsJob(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_tts_job", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Synthesizes the narration of every annotated node of a game into the
+ * audio cache; clips that are already cached are not requested again. Progress is reported under `id`, and the job can be
+ * cancelled with `cancel_tts_job`. Stops at the first provider error.
+ */
+async precacheGame(id: string, pgn: string, options: PrecacheOptions) : Promise<Result<PrecacheSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("precache_game", { id, pgn, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks every TTS dependency and server at once and reports them in
+ * dependency order with fix actions. With `fix`, safe fixes are run one by
+ * one, rechecking after each, with progress reported as `TtsFixEvent`s.
+ */
+async ttsDiagnose(lang: string | null, fix: boolean | null) : Promise<Result<TtsDiagnosis, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tts_diagnose", { lang, fix }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Installs or, with `repair`, verifies and fixes the KittenTTS environment
+ * step by step. Progress is reported under [`KITTENTTS_SETUP_PROGRESS_ID`].
+ */
+async kittenttsSetup(options: KittenTtsSetupOptions | null) : Promise<Result<KittenTtsSetupReport, KittenTtsSetupError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kittentts_setup", { options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkDockerInstalled() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_docker_installed");
+},
+async checkCommandTts() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_command_tts");
+},
+async commandTtsGetConfig() : Promise<Result<CommandTtsConfig | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("command_tts_get_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async commandTtsSetConfig(config: CommandTtsConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("command_tts_set_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkDockerRunning() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_docker_running");
+},
+async checkOpenttsImage(lang: string | null) : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_opentts_image", { lang });
+},
+async checkPythonInstalled() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_python_installed");
+},
+async checkKittenttsVenv() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_kittentts_venv");
+},
+async checkKittenttsPackages() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_kittentts_packages");
+},
+async checkKittenttsScript() : Promise<DepCheck> {
+    return await TAURI_INVOKE("check_kittentts_script");
+},
+async setupKittenttsVenv() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("setup_kittentts_venv") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setupOpenttsLoad(tarballPath: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("setup_opentts_load", { tarballPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pulls the image for `lang`, reporting layer download progress under
+ * [`OPENTTS_PULL_PROGRESS_ID`].
+ */
+async setupOpenttsPull(lang: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("setup_opentts_pull", { lang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Linux system-install updater: downloads the .deb from GitHub and installs
+ * via pkexec dpkg -i (shows one polkit auth prompt).
+ */
+async downloadAndInstallLinux(version: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_and_install_linux", { version }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+bestMovesPayload: BestMovesPayload,
+clockUpdateEvent: ClockUpdateEvent,
+databaseProgress: DatabaseProgress,
+gameMoveEvent: GameMoveEvent,
+gameNarrationEvent: GameNarrationEvent,
+gameOverEvent: GameOverEvent,
+kittenTtsStatusEvent: KittenTtsStatusEvent,
+progressEvent: ProgressEvent,
+systemTtsUtteranceEvent: SystemTtsUtteranceEvent,
+ttsBudgetEvent: TtsBudgetEvent,
+ttsFixEvent: TtsFixEvent
+}>({
+bestMovesPayload: "best-moves-payload",
+clockUpdateEvent: "clock-update-event",
+databaseProgress: "database-progress",
+gameMoveEvent: "game-move-event",
+gameNarrationEvent: "game-narration-event",
+gameOverEvent: "game-over-event",
+kittenTtsStatusEvent: "kitten-tts-status-event",
+progressEvent: "progress-event",
+systemTtsUtteranceEvent: "system-tts-utterance-event",
+ttsBudgetEvent: "tts-budget-event",
+ttsFixEvent: "tts-fix-event"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type AnalysisOptions = { fen: string; moves: string[]; annotateNovelties: boolean; referenceDb: string | null; reversed: boolean }
+export type AudiobookOptions = { voice: AudiobookVoice; lang: string; movePauseMs: number; variationPauseMs: number; includeVariations: boolean; 
+/**
+ * Also write SRT, WebVTT and JSON caption files next to the audio
+ */
+captions?: boolean }
+export type AudiobookSource = { type: "pgn"; pgn: string } | { type: "database"; file: string; gameId: number }
+export type AudiobookSummary = { clips: number; durationMs: number; captions: CaptionTrack }
+/**
+ * Local TTS server used to render the clips.
+ */
+export type AudiobookVoice = { type: "kittenTts"; url: string; voice: string } | { type: "openTts"; url: string; voice: string }
+export type BestMoves = { nodes: number; depth: number; score: Score; uciMoves: string[]; sanMoves: string[]; multipv: number; nps: number }
+export type BestMovesPayload = { bestLines: BestMoves[]; engine: string; tab: string; fen: string; moves: string[]; progress: number }
+export type BudgetAction = 
+/**
+ * Refuse requests that would go over the budget
+ */
+"block" | 
+/**
+ * Go over, but tell the user once
+ */
+"warn"
+export type CaptionTrack = { cues: Cue[]; chapters: Chapter[] }
+export type Chapter = { startMs: number; title: string }
+export type CheckId = "dockerInstalled" | "dockerRunning" | "openttsImage" | "openttsPort" | "openttsServer" | "pythonInstalled" | "kittenttsScript" | "kittenttsVenv" | "kittenttsPackages" | "kittenttsPort" | "kittenttsServer" | "commandTts"
+export type CheckStatus = "ok" | 
+/**
+ * Works, but not as configured
+ */
+"warning" | "failed" | 
+/**
+ * Not meaningful until a check it depends on passes
+ */
+"blocked" | 
+/**
+ * An optional provider that isn't set up
+ */
+"skipped"
+export type ClipFallback = { provider: string; voice: string; serverUrl: string | null }
+/**
+ * One word of a narration that a pre-recorded clip can stand in for.
+ */
+export type ClipToken = 
+/**
+ * Lowercase English piece name, e.g. "knight"
+ */
+{ type: "piece"; value: string } | { type: "file"; value: string } | { type: "rank"; value: string } | { type: "square"; value: string } | { type: "takes" } | { type: "check" } | { type: "checkmate" } | { type: "promotesTo" } | { type: "castlesKingside" } | { type: "castlesQueenside" } | { type: "moveNumber"; value: number } | { type: "nag"; value: number } | 
+/**
+ * Free text, only ever synthesized unless the manifest has it verbatim
+ */
+{ type: "text"; value: string }
+export type ClockUpdateEvent = { gameId: string; whiteTime: bigint | null; blackTime: bigint | null }
+/**
+ * An offline synthesizer binary such as espeak-ng, piper or say.
+ * 
+ * `command` is the program and its arguments, quoted like a shell command
+ * line but never run through a shell. Placeholders in the arguments:
+ * `{text_file}` (the text, written to a file), `{voice}`, `{lang}`,
+ * `{rate}` (0.5-2.0), `{wpm}` (words per minute, 175 at normal rate),
+ * `{length_scale}` (1 / rate, as piper expects) and `{output}` (the WAV
+ * file to write). Without `{text_file}` the text goes to stdin; without
+ * `{output}` the audio is read from stdout.
+ */
+export type CommandTtsConfig = { command: string; timeoutMs?: number; 
+/**
+ * Voice names offered to the user, as the tool spells them
+ */
+voices?: string[] }
+export type ComposeOptions = { 
+/**
+ * Clip pack directory name
+ */
+pack: string; 
+/**
+ * Silence between clips
+ */
+gapMs?: number; 
+/**
+ * Overlap of each clip with the audio before it, when there is no gap
+ */
+crossfadeMs?: number; 
+/**
+ * Voice for tokens the pack has no clip for; the system voice if unset
+ */
+fallback: ClipFallback | null }
+export type ComposedClip = { path: string; 
+/**
+ * Sound server URL, if the sound server is running
+ */
+url: string | null; durationMs: number; 
+/**
+ * Tokens that were synthesized because the pack has no clip for them
+ */
+missing: ClipToken[] }
+/**
+ * One timed subtitle. Move cues also carry the move and resulting position
+ * so a player can sync the board to the audio.
+ */
+export type Cue = { startMs: number; endMs: number; text: string; ply: number | null; san: string | null; fen: string | null }
+export type DatabaseInfo = { title: string; description: string; player_count: number; event_count: number; game_count: number; storage_size: bigint; filename: string; indexed: boolean }
+export type DatabaseProgress = { id: string; progress: number }
+export type DepCheck = { ok: boolean; label: string; detail: string; fix_hint: string }
+export type DiagnosisCheck = { id: CheckId; provider: string; status: CheckStatus; label: string; detail: string; fixHint: string; dependsOn: CheckId[]; fix: FixAction | null }
+export type DrawReason = "stalemate" | "insufficientMaterial" | "threefoldRepetition" | "fiftyMoveRule" | "agreement"
+export type EngineConfig = { name: string; options: UciOptionConfig[] }
+export type EngineLog = { type: "gui"; value: string } | { type: "engine"; value: string }
 export type EngineOption = { name: string; value: string }
 export type EngineOptions = { fen: string; moves: string[]; extraOptions: EngineOption[] }
+export type EnginePoolConfig = { 
+/**
+ * Running engines, leased and idle together
+ */
+maxEngines: number; 
+/**
+ * Sum of the `Threads` option over running engines
+ */
+maxThreads: number; 
+/**
+ * Sum of the `Hash` option over running engines
+ */
+maxHashMb: number; 
+/**
+ * Idle engines are shut down after this long
+ */
+idleTimeoutSecs: number }
+export type EnginePoolStatus = { config: EnginePoolConfig; engines: PooledEngine[]; threadsInUse: number; hashInUseMb: number }
+/**
+ * A named set of UCI options for one engine, e.g. "Blitz" or "Deep".
+ */
+export type EnginePreset = { name: string; options: EngineOption[] }
+export type EvalNarrationOptions = { lang: string; 
+/**
+ * Also speak the win, draw and loss percentages when the engine reports
+ * them
+ */
+wdl?: boolean; 
+/**
+ * Moves of the principal variation to speak
+ */
+lineLength?: number }
 export type Event = { id: number; name: string | null }
 export type FileMetadata = { last_modified: number }
-export type GameConfig = { white: PlayerConfig; black: PlayerConfig; whiteTimeControl: TimeControl | null; blackTimeControl: TimeControl | null; initialFen: string | null; initialMoves?: string[] }
+/**
+ * A machine-readable fix step. Only the safe ones run automatically; the
+ * others are for the setup wizard to offer.
+ */
+export type FixAction = { type: "createKittenttsVenv" } | { type: "installKittenttsPackages" } | { type: "startKittentts" } | 
+/**
+ * Downloads a large image, so it is left to the user
+ */
+{ type: "pullOpenttsImage" } | { type: "startOpentts" } | { type: "configureCommandTts" } | 
+/**
+ * Needs the user, e.g. installing system packages; see `fix_hint`
+ */
+{ type: "manual" }
+export type FixProgress = { state: "running" } | 
+/**
+ * A line of installer output, e.g. pip's "Collecting numpy"
+ */
+{ state: "output"; line: string } | { state: "done"; message: string } | { state: "failed"; error: string }
+export type FixResult = { action: FixAction; ok: boolean; message: string }
+export type GameConfig = { white: PlayerConfig; black: PlayerConfig; whiteTimeControl: TimeControl | null; blackTimeControl: TimeControl | null; initialFen: string | null; initialMoves?: string[]; 
+/**
+ * Spoken narration of the game, off when unset
+ */
+narration?: GameNarrationConfig | null }
 export type GameEndReason = "checkmate" | "timeout" | "resignation" | "abandonment"
 export type GameMove = { uci: string; san: string; fenAfter: string; clock: bigint | null; whiteTime: bigint | null; blackTime: bigint | null }
 export type GameMoveEvent = { gameId: string; moves: GameMove[]; fen: string; whiteTime: bigint | null; blackTime: bigint | null }
+export type GameNarrationConfig = { lang: string; 
+/**
+ * Narrate the moves of human players too, not only the engines'
+ */
+humanMoves?: boolean; 
+/**
+ * Remaining times in milliseconds at which a player is warned
+ */
+clockWarnings?: bigint[] }
+/**
+ * Text to speak for a game with narration enabled in its `GameConfig`.
+ */
+export type GameNarrationEvent = { gameId: string; kind: NarrationKind; text: string }
 export type GameOutcome = "Won" | "Drawn" | "Lost"
 export type GameOverEvent = { gameId: string; result: GameResult; moves: GameMove[] }
 export type GameQuery = { options?: QueryOptions<GameSort> | null; include_moves?: boolean | null; player1?: number | null; player2?: number | null; tournament_id?: number | null; start_date?: string | null; end_date?: string | null; range1?: [number, number] | null; range2?: [number, number] | null; sides?: Sides | null; outcome?: string | null; position?: PositionQueryJs | null; wanted_result?: string | null }
@@ -641,9 +1303,99 @@ export type GameResult = { type: "whiteWins"; reason: GameEndReason } | { type:
 export type GameSort = "id" | "date" | "whiteElo" | "blackElo" | "ply_count"
 export type GameState = { gameId: string; status: GameStatus; initialFen: string; moves: GameMove[]; currentFen: string; ply: number; turn: string; whiteTime: bigint | null; blackTime: bigint | null; whitePlayer: string; blackPlayer: string }
 export type GameStatus = "playing" | { finished: { result: GameResult } }
-export type GoMode = { t: "PlayersTime"; c: PlayersTime } | { t: "Depth"; c: number } | { t: "Time"; c: number } | { t: "Nodes"; c: number } | { t: "Infinite" }
+/**
+ * Any mix of limits for one search; the engine stops at the first one it
+ * reaches, and searches until stopped when none is set.
+ */
+export type GoLimits = { depth?: number | null; nodes?: number | null; 
+/**
+ * Milliseconds
+ */
+movetime?: number | null; mate?: number | null; clock?: PlayersTime | null; 
+/**
+ * Only consider these moves, in UCI notation
+ */
+searchMoves: string[]; 
+/**
+ * Think on the opponent's time until `ponderhit` or `stop`
+ */
+ponder: boolean }
+export type GoMode = { t: "PlayersTime"; c: PlayersTime } | { t: "Depth"; c: number } | { t: "Time"; c: number } | { t: "Nodes"; c: number } | 
+/**
+ * Search for a mate in this many moves
+ */
+{ t: "Mate"; c: number } | { t: "Limits"; c: GoLimits } | { t: "Infinite" }
+export type KeySource = "machine" | "passphrase"
+/**
+ * The step that failed, so the wizard can say exactly what went wrong.
+ */
+export type KittenTtsSetupError = { step: SetupStep; message: string }
+export type KittenTtsSetupOptions = { 
+/**
+ * Verify every component and reinstall only the broken ones
+ */
+repair: boolean; 
+/**
+ * Offline bundle directory; nothing is downloaded when set
+ */
+bundle: string | null }
+export type KittenTtsSetupReport = { steps: StepReport[] }
+export type KittenTtsStatus = { state: "starting" } | { state: "ready" } | { state: "crashed"; exitCode: number | null } | { state: "restarting"; attempt: number; delayMs: number } | { state: "stopped" }
+export type KittenTtsStatusEvent = { port: number; status: KittenTtsStatus }
+/**
+ * One pronunciation fix. Plain patterns match whole words and phrases
+ * case-insensitively; regex patterns are used as written and may refer to
+ * capture groups (`$1`) in the replacement.
+ */
+export type LexiconEntry = { pattern: string; regex?: boolean; 
+/**
+ * Respelling spoken by every provider, e.g. "nepo-mnyash-chee"
+ */
+replacement: string; 
+/**
+ * IPA pronunciation for providers that accept SSML phonemes
+ */
+ipa: string | null }
+export type LexiconTest = { 
+/**
+ * What providers without phoneme support are sent
+ */
+plain: string; 
+/**
+ * What SSML-capable providers are sent, if it differs
+ */
+ssml: string | null; matched: string[] }
 export type MoveAnalysis = { best: BestMoves[]; novelty: boolean; is_sacrifice: boolean }
+export type MoveCandidate = { uci: string; san: string; 
+/**
+ * The move as narration would speak it, for asking which one was meant
+ */
+spoken: string }
+export type NarrationKind = "move" | "clockWarning" | "result"
+export type NarrationRequest = { san: string | null; annotations?: string[]; comment?: string; ply: number; continuation?: string[]; lang: string }
 export type NormalizedGame = { id: number; fen: string; event: string; event_id: number; site: string; site_id: number; date?: string | null; time?: string | null; round?: string | null; white: string; white_id: number; white_elo?: number | null; black: string; black_id: number; black_elo?: number | null; result: Outcome; time_control?: string | null; eco?: string | null; ply_count?: number | null; moves: string }
+export type OpenTtsStatus = { exists: boolean; running: boolean; 
+/**
+ * Docker's container state, e.g. "running" or "exited"
+ */
+state: string | null; 
+/**
+ * Healthcheck status, if the image defines one
+ */
+health: string | null; image: string | null; hostPort: number | null }
+/**
+ * Something wrong with one option, checked against what the engine declared
+ * in its `uci` reply.
+ */
+export type OptionProblem = 
+/**
+ * The engine has no option by this name
+ */
+{ kind: "unknown"; name: string; 
+/**
+ * A declared option with a similar name
+ */
+suggestion: string | null } | { kind: "notABoolean"; name: string; value: string } | { kind: "notAnInteger"; name: string; value: string } | { kind: "outOfRange"; name: string; value: bigint; min: bigint | null; max: bigint | null } | { kind: "notAChoice"; name: string; value: string; choices: string[] }
 export type OutOpening = { name: string; fen: string }
 export type Outcome = "1-0" | "0-1" | "1/2-1/2" | "*"
 export type Player = { id: number; name: string | null; elo: number | null }
@@ -651,11 +1403,89 @@ export type PlayerConfig = { type: "human"; name: string } | { type: "engine"; n
 export type PlayerGameInfo = { site_stats_data: SiteStatsData[] }
 export type PlayerQuery = { options: QueryOptions<PlayerSort>; name?: string | null; range?: [number, number] | null }
 export type PlayerSort = "id" | "name" | "elo"
-export type PlayersTime = { white: number; black: number; winc: number; binc: number }
+export type PlayersTime = { white: number; black: number; winc: number; binc: number; 
+/**
+ * Moves until the next time control, for classical games
+ */
+movestogo?: number | null }
+/**
+ * One running engine, as shown by `engine_pool_status`.
+ */
+export type PooledEngine = { slot: number; path: string; options: EngineOption[]; threads: number; hashMb: number; 
+/**
+ * Who is using the engine, `None` while idle
+ */
+holder: string | null; idleSecs: number | null; 
+/**
+ * Times the engine has been leased
+ */
+leases: number }
+export type PositionQuery = 
+/**
+ * Pieces grouped by side
+ */
+{ type: "pieces"; verbosity: Verbosity } | 
+/**
+ * Occupied squares of one file, or of every file from a to h
+ */
+{ type: "file"; file: string | null } | 
+/**
+ * Occupied squares of one rank, or of every rank from 8 to 1
+ */
+{ type: "rank"; rank: string | null } | 
+/**
+ * The piece on a square with its attackers and defenders
+ */
+{ type: "square"; square: string } | 
+/**
+ * Pieces attacked by the opponent and defended by nothing
+ */
+{ type: "hanging" }
 export type PositionQueryJs = { fen: string; type_: string }
+export type PositionSource = { type: "fen"; value: string } | 
+/**
+ * Current position of a game run by the game manager
+ */
+{ type: "game"; value: string }
 export type PositionStats = { move: string; white: number; draw: number; black: number }
+/**
+ * How synthesized speech is evened out before it is played or exported,
+ * so clips from different providers match in level, rate and pauses.
+ */
+export type PostProcess = { enabled: boolean; 
+/**
+ * Integrated loudness every clip is brought to
+ */
+targetLufs: number; 
+/**
+ * Leading and trailing audio below this level is cut
+ */
+silenceThresholdDb: number; 
+/**
+ * Silence kept before and after the speech
+ */
+paddingMs: number; 
+/**
+ * Every clip is resampled to this rate
+ */
+sampleRate: number }
+export type PrecacheOptions = { provider: string; voice: string; lang: string; serverUrl: string | null; 
+/**
+ * Requests in flight at once
+ */
+concurrency?: number }
+export type PrecacheSummary = { 
+/**
+ * Distinct narrations in the game
+ */
+total: number; 
+/**
+ * Narrations now in the cache, including ones that already were
+ */
+cached: number }
 export type ProgressEvent = { id: string; progress: number; finished: boolean }
 export type ProgressItem = { id: string; progress: number; finished: boolean }
+export type ProviderUsage = { provider: string; usage: Usage; budget: UsageBudget | null }
 export type Puzzle = { id: number; fen: string; moves: string; rating: number; rating_deviation: number; popularity: number; nb_plays: number }
 export type PuzzleDatabaseInfo = { title: string; description: string; puzzleCount: number; storageSize: bigint; path: string }
 export type QueryOptions<SortT> = { skipCount: boolean; page?: number | null; pageSize?: number | null; sort: SortT; direction: SortDirection }
@@ -674,15 +1504,108 @@ export type ScoreValue =
  * Mate coming up in this many moves. Negative value means the engine is getting mated.
  */
 { type: "mate"; value: number }
+export type SecretsStatus = { keySource: KeySource; 
+/**
+ * Waiting for the passphrase, see `secrets_unlock`
+ */
+locked: boolean }
+export type SetupStep = "python" | "venv" | "packages" | "models" | "script"
 export type Sides = "BlackWhite" | "WhiteBlack" | "Any"
 export type SiteStatsData = { site: string; player: string; data: StatsData[] }
 export type SortDirection = "asc" | "desc"
+export type SpokenMoveMatch = ({ type: "move" } & MoveCandidate) | { type: "ambiguous"; candidates: MoveCandidate[] } | { type: "noMatch" }
 export type StatsData = { date: string; is_player_white: boolean; player_elo: number; result: GameOutcome; time_control: string; opening: string }
+export type StepOutcome = 
+/**
+ * Already in place and verified
+ */
+"ok" | "installed" | "repaired" | 
+/**
+ * Nothing to verify against
+ */
+"skipped"
+export type StepReport = { step: SetupStep; outcome: StepOutcome; detail: string }
+export type SystemTtsUtteranceEvent = { id: number; state: UtteranceState }
 export type SystemVoice = { id: string; name: string; language: string }
 export type TimeControl = { initialTime: bigint; increment: bigint }
 export type Token = { type: "ParenOpen" } | { type: "ParenClose" } | { type: "Comment"; value: string } | { type: "San"; value: string } | { type: "Header"; value: { tag: string; value: string } } | { type: "Nag"; value: string } | { type: "Outcome"; value: string }
 export type TournamentQuery = { options: QueryOptions<TournamentSort>; name: string | null }
 export type TournamentSort = "id" | "name"
+/**
+ * Sent when a provider goes over its monthly budget, or a request is
+ * refused because of it.
+ */
+export type TtsBudgetEvent = { provider: string; used: number; budget: number; blocked: boolean }
+/**
+ * Identifies one synthesized utterance. Two requests that differ only in
+ * whitespace share a cache entry.
+ */
+export type TtsCacheKey = { provider: string; voice: string; language: string; text: string }
+export type TtsCacheStats = { entries: number; totalBytes: bigint; maxBytes: bigint; hits: number; misses: number }
+export type TtsDiagnosis = { 
+/**
+ * In dependency order
+ */
+checks: DiagnosisCheck[]; 
+/**
+ * Providers whose checks all passed
+ */
+ready: string[]; 
+/**
+ * Fixes run by this diagnosis, in order
+ */
+fixes: FixResult[] }
+/**
+ * Why a request to a local TTS server failed. Serialized as `{ kind, ... }`
+ * so the frontend can tell a server that is still starting from a real error.
+ */
+export type TtsFetchError = { kind: "invalidUrl"; message: string } | { kind: "connectionRefused"; url: string } | { kind: "timeout"; timeoutMs: number } | { kind: "status"; status: number; body: string } | { kind: "network"; message: string } | { kind: "cancelled" }
+export type TtsFetchOptions = { timeoutMs: number; 
+/**
+ * Retries when the connection is refused, e.g. while the server is
+ * still loading its model
+ */
+retries: number; 
+/**
+ * Delay before the first retry, doubled on each further attempt
+ */
+backoffMs: number }
+/**
+ * Emitted while `tts_diagnose` runs a fix.
+ */
+export type TtsFixEvent = { action: FixAction; progress: FixProgress }
+export type TtsOptions = { 
+/**
+ * Language of the text, e.g. "en" or "de"
+ */
+language: string | null; 
+/**
+ * Normalized 0.5-2.0, 1.0 = normal
+ */
+rate: number | null; 
+/**
+ * 0.0-1.0
+ */
+volume: number | null; pitch: number | null; 
+/**
+ * Base URL of an HTTP provider, if not the provider's default
+ */
+serverUrl: string | null; 
+/**
+ * Lets the request be cancelled or superseded, see `cancel_tts_fetch`
+ */
+requestId: string | null; 
+/**
+ * For `speak`: queue behind the current utterance instead of
+ * interrupting it
+ */
+enqueue: boolean; 
+/**
+ * The text is an SSML document; set by the lexicon for providers that
+ * support it
+ */
+ssml: boolean }
+export type TtsVoice = { id: string; name: string; language: string; gender: string | null }
 /**
  * Represents a UCI option definition.
  */
@@ -755,6 +1678,23 @@ name: string;
  * The default value of this string option.
  */
 default: string | null } }
+export type Usage = { characters: number; requests: number }
+export type UsageBudget = { monthlyCharacters: number; action: BudgetAction }
+export type UsageReport = { month: string; providers: ProviderUsage[] }
+export type UtteranceState = "started" | "ended" | "stopped"
+export type Verbosity = 
+/**
+ * Side to move and pieces, without pawns
+ */
+"brief" | 
+/**
+ * Side to move and every piece
+ */
+"normal" | 
+/**
+ * Like `Normal`, followed by the hanging pieces
+ */
+"full"
 
 /** tauri-specta globals **/
 
@@ -466,7 +466,7 @@ function BoardGame() {
       }
 
       try {
-        await commands.makeGameMove(gameId, uci);
+        await commands.makeGameMove(gameId, uci, null);
       } catch (err) {
         console.error("Failed to make move:", err);
       }
@@ -596,7 +596,7 @@ function BoardGame() {
 
     const cleanup1 = onPeerMove(async (uci, wt, bt) => {
       try {
-        await commands.makeGameMove(currentGameId, uci);
+        await commands.makeGameMove(currentGameId, uci, null);
         if (wt !== undefined) setWhiteTime(wt);
         if (bt !== undefined) setBlackTime(bt);
       } catch (err) {
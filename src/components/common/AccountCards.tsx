@@ -9,6 +9,7 @@ import {
   TextInput,
 } from "@mantine/core";
 import { IconCheck, IconEdit, IconX } from "@tabler/icons-react";
+import { invoke } from "@tauri-apps/api/core";
 import { useAtom, useAtomValue } from "jotai";
 import { useEffect, useRef, useState } from "react";
 import type { DatabaseInfo } from "@/bindings";
@@ -214,19 +215,22 @@ function LichessOrChessCom({
     return (
       <AccountCard
         key={account.id}
-        token={lichessSession.accessToken}
+        tokenSecret={lichessSession.tokenSecret}
         type="lichess"
         database={databases.find((db) => db.filename === `${account.username}_lichess.db3`) ?? null}
         title={account.username}
         updatedAt={session.updatedAt}
         total={totalGames}
         logout={() => {
+          if (lichessSession.tokenSecret) {
+            invoke("secrets_delete", { name: lichessSession.tokenSecret }).catch(() => {});
+          }
           setSessions((sessions) => sessions.filter((s) => s.lichess?.account.id !== account.id));
         }}
         setDatabases={setDatabases}
         reload={async () => {
           const account = await getLichessAccount({
-            token: lichessSession.accessToken,
+            tokenSecret: lichessSession.tokenSecret,
             username: lichessSession.username,
           });
           if (!account) return;
@@ -238,7 +242,7 @@ function LichessOrChessCom({
                     lichess: {
                       account: account,
                       username: lichessSession.username,
-                      accessToken: lichessSession.accessToken,
+                      tokenSecret: lichessSession.tokenSecret,
                     },
                     updatedAt: Date.now(),
                   }
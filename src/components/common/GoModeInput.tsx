@@ -1,8 +1,8 @@
-import { Group, NumberInput, SegmentedControl } from "@mantine/core";
-import { useRef } from "react";
+import { Group, NumberInput, SegmentedControl, TextInput } from "@mantine/core";
+import { useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { match } from "ts-pattern";
-import type { GoMode } from "@/bindings";
+import type { GoLimits, GoMode } from "@/bindings";
 import TimeInput from "./TimeInput";
 
 function GoModeInput({
@@ -20,6 +20,7 @@ function GoModeInput({
     Time: 8000,
     Depth: 20,
     Nodes: 1000000,
+    Mate: 5,
   });
 
   // Keep ref in sync with current value
@@ -29,7 +30,7 @@ function GoModeInput({
 
   const timeTypes = ["Time", "Depth", "Nodes"];
   if (!gameMode) {
-    timeTypes.push("Infinite");
+    timeTypes.push("Mate", "Limits", "Infinite");
   }
 
   return (
@@ -51,6 +52,22 @@ function GoModeInput({
               t: "Time",
               c: prevValues.current.Time,
             }))
+            .with("Mate", () => ({
+              t: "Mate",
+              c: prevValues.current.Mate,
+            }))
+            .with("Limits", () => ({
+              t: "Limits",
+              c: {
+                depth: prevValues.current.Depth,
+                nodes: null,
+                movetime: null,
+                mate: null,
+                clock: null,
+                searchMoves: [],
+                ponder: false,
+              },
+            }))
             .otherwise(() => ({ t: "Infinite" }));
 
           setGoMode(newGo);
@@ -71,10 +88,61 @@ function GoModeInput({
             onChange={(v) => setGoMode({ t: "Nodes", c: typeof v === "number" ? v : 1 })}
           />
         ))
+        .with({ t: "Mate" }, (v) => (
+          <NumberInput
+            min={1}
+            value={v.c}
+            onChange={(v) => setGoMode({ t: "Mate", c: typeof v === "number" ? v : 1 })}
+          />
+        ))
         .with({ t: "Time" }, (v) => <TimeInput value={v.c} setValue={setGoMode} />)
+        .with({ t: "Limits" }, (v) => (
+          <LimitsInput limits={v.c} setLimits={(c) => setGoMode({ t: "Limits", c })} />
+        ))
         .otherwise(() => null)}
     </Group>
   );
 }
 
+/** Any mix of limits; an empty field is no limit, and the engine stops at the first one reached. */
+function LimitsInput({
+  limits,
+  setLimits,
+}: {
+  limits: GoLimits;
+  setLimits: (v: GoLimits) => void;
+}) {
+  const { t } = useTranslation();
+  // kept as typed so a trailing space survives until the next move
+  const [searchMoves, setSearchMoves] = useState(limits.searchMoves.join(" "));
+
+  const limit = (key: "depth" | "nodes" | "movetime" | "mate", label: string) => (
+    <NumberInput
+      w="7rem"
+      min={1}
+      placeholder={label}
+      value={limits[key] ?? ""}
+      onChange={(v) => setLimits({ ...limits, [key]: typeof v === "number" ? v : null })}
+    />
+  );
+
+  return (
+    <Group gap="xs">
+      {limit("depth", t("GoMode.Depth"))}
+      {limit("nodes", t("GoMode.Nodes"))}
+      {limit("movetime", t("GoMode.MoveTime"))}
+      {limit("mate", t("GoMode.Mate"))}
+      <TextInput
+        w="10rem"
+        placeholder={t("GoMode.SearchMoves")}
+        value={searchMoves}
+        onChange={(e) => {
+          setSearchMoves(e.currentTarget.value);
+          setLimits({ ...limits, searchMoves: e.currentTarget.value.split(/\s+/).filter(Boolean) });
+        }}
+      />
+    </Group>
+  );
+}
+
 export default GoModeInput;
@@ -0,0 +1,105 @@
+import { Button, Group, Modal, NumberInput, SimpleGrid, Stack, Table, Text } from "@mantine/core";
+import { useAtom } from "jotai";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import useSWR from "swr";
+import { commands, type EnginePoolConfig } from "@/bindings";
+import { enginePoolConfigAtom } from "@/state/atoms";
+import { unwrap } from "@/utils/unwrap";
+
+/** Shows the engines the pool keeps running and edits its caps. */
+export default function EnginePoolModal({
+  opened,
+  onClose,
+}: {
+  opened: boolean;
+  onClose: () => void;
+}) {
+  const { t } = useTranslation();
+  const [, setSavedConfig] = useAtom(enginePoolConfigAtom);
+  const { data: status, mutate } = useSWR(
+    opened ? "engine-pool-status" : null,
+    () => commands.enginePoolStatus(),
+    { refreshInterval: 2000 },
+  );
+  const [config, setConfig] = useState<EnginePoolConfig | null>(null);
+
+  useEffect(() => {
+    if (opened && status && !config) setConfig(status.config);
+    if (!opened) setConfig(null);
+  }, [opened, status, config]);
+
+  async function apply() {
+    if (!config) return;
+    // unwrap reports a failure itself
+    unwrap(await commands.enginePoolConfigure(config));
+    setSavedConfig(config);
+    mutate();
+  }
+
+  const field = (key: keyof EnginePoolConfig, label: string, min: number) => (
+    <NumberInput
+      label={label}
+      min={min}
+      value={config?.[key] ?? ""}
+      onChange={(v) => config && typeof v === "number" && setConfig({ ...config, [key]: v })}
+    />
+  );
+
+  return (
+    <Modal opened={opened} onClose={onClose} title={t("Engines.Pool.Title")} size="lg">
+      <Stack>
+        <SimpleGrid cols={2}>
+          {field("maxEngines", t("Engines.Pool.MaxEngines"), 1)}
+          {field("maxThreads", t("Engines.Pool.MaxThreads"), 1)}
+          {field("maxHashMb", t("Engines.Pool.MaxHash"), 1)}
+          {field("idleTimeoutSecs", t("Engines.Pool.IdleTimeout"), 0)}
+        </SimpleGrid>
+        <Group justify="right">
+          <Button disabled={!config} onClick={apply}>
+            {t("Engines.Pool.Apply")}
+          </Button>
+        </Group>
+
+        {status && (
+          <Text size="sm" c="dimmed">
+            {t("Engines.Pool.InUse", {
+              threads: status.threadsInUse,
+              maxThreads: status.config.maxThreads,
+              hash: status.hashInUseMb,
+              maxHash: status.config.maxHashMb,
+            })}
+          </Text>
+        )}
+        {status?.engines.length ? (
+          <Table>
+            <Table.Thead>
+              <Table.Tr>
+                <Table.Th>{t("Engines.Pool.Engine")}</Table.Th>
+                <Table.Th>{t("Engines.Pool.Holder")}</Table.Th>
+                <Table.Th>{t("Engines.Settings.NumOfCores")}</Table.Th>
+                <Table.Th>{t("Engines.Settings.SizeOfHash")}</Table.Th>
+              </Table.Tr>
+            </Table.Thead>
+            <Table.Tbody>
+              {status.engines.map((e) => (
+                <Table.Tr key={e.slot}>
+                  <Table.Td>{e.path.split(/[\\/]/).pop()}</Table.Td>
+                  <Table.Td>
+                    {e.holder ?? t("Engines.Pool.Idle", { seconds: e.idleSecs ?? 0 })}
+                  </Table.Td>
+                  <Table.Td>{e.threads}</Table.Td>
+                  <Table.Td>{e.hashMb} MB</Table.Td>
+                </Table.Tr>
+              ))}
+            </Table.Tbody>
+          </Table>
+        ) : (
+          <Text size="sm" c="dimmed">
+            {t("Engines.Pool.Empty")}
+          </Text>
+        )}
+      </Stack>
+    </Modal>
+  );
+}
@@ -22,7 +22,15 @@ import {
   Title,
 } from "@mantine/core";
 import { useToggle } from "@mantine/hooks";
-import { IconCloud, IconCopy, IconCpu, IconPhotoPlus, IconPlus } from "@tabler/icons-react";
+import {
+  IconCloud,
+  IconCopy,
+  IconCpu,
+  IconPhotoPlus,
+  IconPlus,
+  IconStack2,
+  IconTrash,
+} from "@tabler/icons-react";
 import { useNavigate } from "@tanstack/react-router";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useAtom } from "jotai";
@@ -30,7 +38,7 @@ import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import useSWRImmutable from "swr/immutable";
 import { match, P } from "ts-pattern";
-import { commands } from "@/bindings";
+import { commands, type UciOptionConfig } from "@/bindings";
 import classes from "@/components/common/GenericCard.module.css";
 import { Route } from "@/routes/engines";
 import { enginesAtom } from "@/state/atoms";
@@ -48,12 +56,14 @@ import LocalImage from "../common/LocalImage";
 import OpenFolderButton from "../common/OpenFolderButton";
 import LinesSlider from "../panels/analysis/LinesSlider";
 import AddEngine from "./AddEngine";
+import EnginePoolModal from "./EnginePool";
 
 export default function EnginesPage() {
   const { t } = useTranslation();
 
   const [engines, setEngines] = useAtom(enginesAtom);
   const [opened, setOpened] = useState(false);
+  const [poolOpened, setPoolOpened] = useState(false);
   const { selected } = Route.useSearch();
   const navigate = useNavigate();
   const setSelected = (v: number | null) => {
@@ -67,9 +77,18 @@ export default function EnginesPage() {
   return (
     <Stack h="100%" px="lg" pb="lg">
       <AddEngine opened={opened} setOpened={setOpened} />
+      <EnginePoolModal opened={poolOpened} onClose={() => setPoolOpened(false)} />
       <Group align="baseline" py="sm">
         <Title>{t("Engines.Title")}</Title>
         <OpenFolderButton base="Engines" folder="engines" />
+        <Button
+          variant="default"
+          size="xs"
+          leftSection={<IconStack2 size="1rem" />}
+          onClick={() => setPoolOpened(true)}
+        >
+          {t("Engines.Pool.Title")}
+        </Button>
       </Group>
       <Group grow flex={1} style={{ overflow: "hidden" }} align="start">
         <ScrollArea h="100%" offsetScrollbars>
@@ -452,6 +471,8 @@ function EngineSettings({
             })}
         </SimpleGrid>
 
+        <EnginePresets engine={engine} config={options?.options} setEngine={setEngine} />
+
         <Group justify="end">
           <Button variant="default" onClick={() => toggleJSONModal(true)}>
             {t("Engines.Settings.EditJSON")}
@@ -526,6 +547,100 @@ function EngineSettings({
   );
 }
 
+
+/** Named option sets, saved in the backend per engine binary. */
+function EnginePresets({
+  engine,
+  config,
+  setEngine,
+}: {
+  engine: LocalEngine;
+  config: UciOptionConfig[] | undefined;
+  setEngine: (engine: LocalEngine) => void;
+}) {
+  const { t } = useTranslation();
+  const { data: presets, mutate } = useSWRImmutable(
+    ["engine-presets", engine.path],
+    ([, path]) => commands.enginePresetsList(path),
+  );
+  const [name, setName] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  function load(presetName: string | null) {
+    const preset = presets?.find((p) => p.name === presetName);
+    if (!preset) return;
+    // Presets store UCI strings; the inputs want numbers and booleans back
+    const settings = preset.options.map(({ name, value }) => {
+      const type = config?.find((o) => o.value.name === name)?.type;
+      return {
+        name,
+        value: type === "spin" ? Number(value) : type === "check" ? value === "true" : value,
+      };
+    });
+    setEngine({ ...engine, settings });
+  }
+
+  async function save() {
+    const options = (engine.settings || [])
+      .filter((s) => s.value !== null)
+      .map((s) => ({ name: s.name, value: String(s.value) }));
+    const result = await commands.enginePresetSave(engine.path, name, options);
+    if (result.status === "error") {
+      setError(result.error);
+      return;
+    }
+    setName("");
+    setError(null);
+    mutate();
+  }
+
+  async function remove(presetName: string) {
+    unwrap(await commands.enginePresetDelete(engine.path, presetName));
+    mutate();
+  }
+
+  return (
+    <Group align="end" wrap="nowrap">
+      <Select
+        flex={1}
+        label={t("Engines.Settings.Presets")}
+        placeholder={
+          presets?.length ? t("Engines.Settings.LoadPreset") : t("Engines.Settings.NoPresets")
+        }
+        data={presets?.map((p) => p.name) ?? []}
+        value={null}
+        onChange={load}
+        renderOption={({ option }) => (
+          <Group justify="space-between" w="100%" wrap="nowrap">
+            <Text size="sm">{option.value}</Text>
+            <ActionIcon
+              size="xs"
+              variant="subtle"
+              color="red"
+              onClick={(e) => {
+                e.stopPropagation();
+                remove(option.value);
+              }}
+            >
+              <IconTrash size="0.8rem" />
+            </ActionIcon>
+          </Group>
+        )}
+      />
+      <TextInput
+        flex={1}
+        placeholder={t("Engines.Settings.PresetName")}
+        value={name}
+        error={error}
+        onChange={(e) => setName(e.currentTarget.value)}
+      />
+      <Button variant="default" disabled={!name.trim()} onClick={save}>
+        {t("Engines.Settings.SavePreset")}
+      </Button>
+    </Group>
+  );
+}
+
 function JSONModal({
   opened,
   toggleOpened,
@@ -48,7 +48,8 @@ interface AccountCardProps {
   logout: () => void;
   reload: () => void;
   setDatabases: (databases: DatabaseInfo[]) => void;
-  token?: string;
+  /** Backend secret holding the lichess token, when logged in */
+  tokenSecret?: string;
 }
 
 export function AccountCard({
@@ -61,7 +62,7 @@ export function AccountCard({
   logout,
   reload,
   setDatabases,
-  token,
+  tokenSecret,
 }: AccountCardProps) {
   const { t } = useTranslation();
   const items = stats.map((stat) => {
@@ -180,7 +181,7 @@ export function AccountCard({
             <Text fw={600} size="sm">
               {title}
             </Text>
-            {type === "lichess" && token && (
+            {type === "lichess" && tokenSecret && (
               <Tooltip label={t("Home.Accounts.Authenticated")}>
                 <Text c="green" lh={0} style={{ cursor: "default" }}>
                   <IconCircleCheckFilled size="1.1rem" />
@@ -209,7 +210,7 @@ export function AccountCard({
                       lastGameDate,
                       total - downloadedGames,
                       setProgress,
-                      token,
+                      tokenSecret,
                       lichessIncludeUnrated,
                     );
                   } else {
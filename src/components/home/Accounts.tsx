@@ -18,7 +18,7 @@ import { commands } from "@/bindings";
 import { sessionsAtom } from "@/state/atoms";
 import { getChessComAccount } from "@/utils/chess.com/api";
 import { getDatabases } from "@/utils/db";
-import { getLichessAccount } from "@/utils/lichess/api";
+import { getLichessAccount, lichessTokenSecret } from "@/utils/lichess/api";
 import type { ChessComSession, LichessSession } from "@/utils/session";
 import AccountCards from "../common/AccountCards";
 import GenericCard from "../common/GenericCard";
@@ -78,14 +78,14 @@ function Accounts() {
     addLichessSession(p, { username, account });
   }
 
-  async function onLichessAuthentication(token: string) {
+  async function onLichessAuthentication(username: string) {
     const player = sessionStorage.getItem("lichess_player_alias") || "";
     sessionStorage.removeItem("lichess_player_alias");
-    const account = await getLichessAccount({ token });
+    const tokenSecret = lichessTokenSecret(username);
+    const account = await getLichessAccount({ tokenSecret });
     if (!account) return;
-    const username = account.username;
-    const p = player !== "" ? player : username;
-    addLichessSession(p, { accessToken: token, username: username, account });
+    const p = player !== "" ? player : account.username;
+    addLichessSession(p, { tokenSecret, username, account });
   }
 
   async function addLichess(player: string, username: string, withLogin: boolean) {
@@ -100,9 +100,8 @@ function Accounts() {
     async function listen_for_code() {
       if (isListening.current) return;
       isListening.current = true;
-      await listen<string>("access_token", async (event) => {
-        const token = event.payload;
-        await onLichessAuthentication(token);
+      await listen<string>("lichess_authenticated", async (event) => {
+        await onLichessAuthentication(event.payload);
       });
     }
 
@@ -119,8 +119,8 @@ function DatabasePanel() {
   const [masterOptions, setMasterOptions] = useAtom(masterOptionsAtom);
   const [localOptions, setLocalOptions] = useAtom(currentLocalOptionsAtom);
   const [db, setDb] = useAtom(currentDbTypeAtom);
-  const explorerToken = sessions.find((session) => session.lichess?.accessToken)?.lichess
-    ?.accessToken;
+  const explorerToken = sessions.find((session) => session.lichess?.tokenSecret)?.lichess
+    ?.tokenSecret;
   const missingExplorerToken = db !== "local" && !explorerToken;
 
   const { data: databases } = useSWR(db === "local" ? "databases" : null, () => getDatabases());
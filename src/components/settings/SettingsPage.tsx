@@ -84,6 +84,7 @@ import {
   TTSApiKeyInput,
   TTSAutoNarrateSwitch,
   TTSClearCacheButton,
+  TTSCommandInput,
   TTSEnabledSwitch,
   TTSGoogleApiKeyInput,
   TTSGrokApiKeyInput,
@@ -742,6 +743,15 @@ export default function Page() {
         keywords: ["tts", "kittentts", "threads", "cpu", "cores", "performance"],
         render: () => <TTSKittenTTSThreadsInput />,
       },
+      {
+        id: "tts-command",
+        category: "tts",
+        title: "Synthesizer Command",
+        description:
+          "Offline command-line synthesizer such as espeak-ng or piper. Placeholders: {text_file}, {voice}, {lang}, {rate}, {wpm}, {length_scale}, {output}. Without {output} the WAV is read from stdout.",
+        keywords: ["tts", "command", "espeak", "piper", "say", "offline", "synthesizer"],
+        render: () => <TTSCommandInput />,
+      },
       // Directories settings
       {
         id: "files-directory",
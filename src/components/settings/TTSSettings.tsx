@@ -10,11 +10,13 @@ import {
   Stack,
   Switch,
   Text,
+  TextInput,
 } from "@mantine/core";
 import { IconAlertTriangle } from "@tabler/icons-react";
 import { invoke } from "@tauri-apps/api/core";
 import { useAtom } from "jotai";
 import { useCallback, useEffect, useState } from "react";
+import { type CommandTtsConfig, commands, type DepCheck } from "@/bindings";
 import {
   ttsApiKeyAtom,
   ttsAutoNarrateAtom,
@@ -39,7 +41,10 @@ import {
 import { playCloudDemoClip } from "@/utils/cloudTts";
 import {
   clearAudioCache,
+  dependenciesReady,
+  diagnoseTts,
   type ElevenLabsVoice,
+  failedFix,
   GROK_VOICES,
   KITTENTTS_VOICES,
   listOpenTTSVoices,
@@ -47,18 +52,13 @@ import {
   listVoices,
   type OpenTTSVoice,
   type SystemVoice,
+  saveApiKey,
   speakText,
+  startOpenTTSContainer,
   stopSpeaking,
 } from "@/utils/tts";
 import TTSSetupWizard from "./TTSSetupWizard";
 
-interface DepCheck {
-  ok: boolean;
-  label: string;
-  detail: string;
-  fix_hint: string;
-}
-
 export function TTSEnabledSwitch() {
   const [enabled, setEnabled] = useAtom(ttsEnabledAtom);
   return <Switch checked={enabled} onChange={(e) => setEnabled(e.currentTarget.checked)} />;
@@ -110,46 +110,28 @@ export function TTSSetupButton() {
     let cancelled = false;
     const checkAndStart = async () => {
       try {
-        let depsOk = false;
-        if (provider === "kittentts") {
-          const [venv, packages] = await Promise.all([
-            invoke<DepCheck>("check_kittentts_venv"),
-            invoke<DepCheck>("check_kittentts_packages"),
-          ]);
-          depsOk = venv.ok && packages.ok;
-
-          // Auto-install if deps are missing
-          if (!depsOk && !cancelled) {
-            setInstalling(true);
-            setInstallError(null);
-            setInstallPhase("Creating Python environment and installing packages (1-3 minutes)...");
-            try {
-              await invoke("setup_kittentts_venv");
-              if (!cancelled) {
-                depsOk = true;
-                setDepsMissing(false);
-              }
-            } catch (e) {
-              console.error("KittenTTS auto-setup failed:", e);
-              if (!cancelled) {
-                setDepsMissing(true);
-                setInstallError(String(e));
-              }
-            } finally {
-              if (!cancelled) setInstalling(false);
-            }
-          } else {
-            if (!cancelled) setDepsMissing(!depsOk);
+        let depsOk = dependenciesReady(await diagnoseTts(), provider);
+
+        // Auto-install if deps are missing; only the safe fixes run
+        if (provider === "kittentts" && !depsOk && !cancelled) {
+          setInstalling(true);
+          setInstallError(null);
+          setInstallPhase("Creating Python environment and installing packages (1-3 minutes)...");
+          try {
+            const diagnosis = await diagnoseTts(true, ({ progress }) => {
+              if (!cancelled && progress.state === "output") setInstallPhase(progress.line);
+            });
+            const error = failedFix(diagnosis);
+            if (error) throw error;
+            depsOk = dependenciesReady(diagnosis, provider);
+          } catch (e) {
+            console.error("KittenTTS auto-setup failed:", e);
+            if (!cancelled) setInstallError(String(e));
+          } finally {
+            if (!cancelled) setInstalling(false);
           }
-        } else {
-          const [docker, running, image] = await Promise.all([
-            invoke<DepCheck>("check_docker_installed"),
-            invoke<DepCheck>("check_docker_running"),
-            invoke<DepCheck>("check_opentts_image"),
-          ]);
-          depsOk = docker.ok && running.ok && image.ok;
-          if (!cancelled) setDepsMissing(!depsOk);
         }
+        if (!cancelled) setDepsMissing(!depsOk);
 
         // Auto-start server if deps are ready
         if (depsOk && !cancelled) {
@@ -158,7 +140,7 @@ export function TTSSetupButton() {
             if (provider === "kittentts") {
               await invoke("kittentts_start", { threads: threads || null });
             } else {
-              await invoke("opentts_start");
+              await startOpenTTSContainer();
             }
             if (!cancelled) setServerStatus("running");
           } catch (e) {
@@ -242,67 +224,50 @@ export function TTSSetupButton() {
   );
 }
 
-export function TTSGoogleApiKeyInput() {
-  const [apiKey, setApiKey] = useAtom(ttsGoogleApiKeyAtom);
-  const [tempKey, setTempKey] = useState(apiKey);
-
-  useEffect(() => {
-    setTempKey(apiKey);
-  }, [apiKey]);
+/**
+ * API keys go to the backend secrets store; the setting only records that a
+ * key is saved, so the field stays blank until a new key is typed.
+ */
+function ApiKeyInput({
+  provider,
+  atom,
+  placeholder,
+}: {
+  provider: "elevenlabs" | "google" | "grok";
+  atom: typeof ttsApiKeyAtom;
+  placeholder: string;
+}) {
+  const [apiKey] = useAtom(atom);
+  const [tempKey, setTempKey] = useState("");
 
   return (
     <Group gap="xs">
       <PasswordInput
         w="20rem"
-        placeholder="AIza..."
+        placeholder={apiKey ? "Saved (type to replace)" : placeholder}
         value={tempKey}
         onChange={(e) => setTempKey(e.currentTarget.value)}
-        onBlur={() => setApiKey(tempKey)}
+        onBlur={() => {
+          if (!tempKey) return;
+          saveApiKey(provider, tempKey)
+            .then(() => setTempKey(""))
+            .catch((e) => console.error("Failed to save API key:", e));
+        }}
       />
     </Group>
   );
 }
 
-export function TTSGrokApiKeyInput() {
-  const [apiKey, setApiKey] = useAtom(ttsGrokApiKeyAtom);
-  const [tempKey, setTempKey] = useState(apiKey);
-
-  useEffect(() => {
-    setTempKey(apiKey);
-  }, [apiKey]);
+export function TTSGoogleApiKeyInput() {
+  return <ApiKeyInput provider="google" atom={ttsGoogleApiKeyAtom} placeholder="AIza..." />;
+}
 
-  return (
-    <Group gap="xs">
-      <PasswordInput
-        w="20rem"
-        placeholder="xai-..."
-        value={tempKey}
-        onChange={(e) => setTempKey(e.currentTarget.value)}
-        onBlur={() => setApiKey(tempKey)}
-      />
-    </Group>
-  );
+export function TTSGrokApiKeyInput() {
+  return <ApiKeyInput provider="grok" atom={ttsGrokApiKeyAtom} placeholder="xai-..." />;
 }
 
 export function TTSApiKeyInput() {
-  const [apiKey, setApiKey] = useAtom(ttsApiKeyAtom);
-  const [tempKey, setTempKey] = useState(apiKey);
-
-  useEffect(() => {
-    setTempKey(apiKey);
-  }, [apiKey]);
-
-  return (
-    <Group gap="xs">
-      <PasswordInput
-        w="20rem"
-        placeholder="sk_..."
-        value={tempKey}
-        onChange={(e) => setTempKey(e.currentTarget.value)}
-        onBlur={() => setApiKey(tempKey)}
-      />
-    </Group>
-  );
+  return <ApiKeyInput provider="elevenlabs" atom={ttsApiKeyAtom} placeholder="sk_..." />;
 }
 
 export function TTSKittenTTSUrlInput() {
@@ -326,11 +291,7 @@ export function TTSKittenTTSUrlInput() {
   const handleStart = async () => {
     // Check deps first — open wizard if missing
     try {
-      const [venv, packages] = await Promise.all([
-        invoke<DepCheck>("check_kittentts_venv"),
-        invoke<DepCheck>("check_kittentts_packages"),
-      ]);
-      if (!venv.ok || !packages.ok) {
+      if (!dependenciesReady(await diagnoseTts(), "kittentts")) {
         setWizardOpen(true);
         return;
       }
@@ -425,12 +386,7 @@ export function TTSOpenTTSUrlInput() {
   const handleStart = async () => {
     // Check deps first — open wizard if missing
     try {
-      const [docker, running, image] = await Promise.all([
-        invoke<DepCheck>("check_docker_installed"),
-        invoke<DepCheck>("check_docker_running"),
-        invoke<DepCheck>("check_opentts_image"),
-      ]);
-      if (!docker.ok || !running.ok || !image.ok) {
+      if (!dependenciesReady(await diagnoseTts(), "opentts")) {
         setWizardOpen(true);
         return;
       }
@@ -441,7 +397,7 @@ export function TTSOpenTTSUrlInput() {
     setStatus("starting");
     setServerStatus("starting");
     try {
-      await invoke("opentts_start");
+      await startOpenTTSContainer();
       showResult("Started");
       setServerStatus("running");
     } catch (e) {
@@ -556,7 +512,7 @@ export function TTSVoiceSelect() {
     if (!apiKey || provider !== "elevenlabs") return;
     setLoading(true);
     try {
-      const v = await listVoices(apiKey);
+      const v = await listVoices();
       setVoices(v);
     } catch (e) {
       console.error("Failed to fetch voices:", e);
@@ -962,3 +918,73 @@ export function TTSKittenTTSThreadsInput() {
     </Group>
   );
 }
+
+/** The command-line synthesizer used by the "command" provider, such as espeak-ng or piper. */
+export function TTSCommandInput() {
+  const [config, setConfig] = useState<CommandTtsConfig>({ command: "", voices: [] });
+  const [voices, setVoices] = useState("");
+  const [check, setCheck] = useState<DepCheck | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    commands.commandTtsGetConfig().then((res) => {
+      if (res.status === "ok" && res.data) {
+        setConfig(res.data);
+        setVoices((res.data.voices ?? []).join(", "));
+      }
+    });
+    commands.checkCommandTts().then(setCheck);
+  }, []);
+
+  const save = async () => {
+    const res = await commands.commandTtsSetConfig({
+      ...config,
+      voices: voices
+        .split(",")
+        .map((v) => v.trim())
+        .filter(Boolean),
+    });
+    setError(res.status === "error" ? res.error : null);
+    setCheck(await commands.checkCommandTts());
+  };
+
+  return (
+    <Stack gap="xs" w="24rem">
+      <TextInput
+        placeholder="espeak-ng -v {voice} -s {wpm} --stdout"
+        value={config.command}
+        onChange={(e) => setConfig({ ...config, command: e.currentTarget.value })}
+      />
+      <Group gap="xs">
+        <NumberInput
+          w="8rem"
+          min={1000}
+          step={1000}
+          suffix=" ms"
+          value={config.timeoutMs ?? 30000}
+          onChange={(v) => setConfig({ ...config, timeoutMs: typeof v === "number" ? v : 30000 })}
+        />
+        <TextInput
+          flex={1}
+          placeholder="Voices, comma separated"
+          value={voices}
+          onChange={(e) => setVoices(e.currentTarget.value)}
+        />
+        <Button size="xs" onClick={save}>
+          Save
+        </Button>
+      </Group>
+      {error ? (
+        <Text size="xs" c="red">
+          {error}
+        </Text>
+      ) : (
+        check && (
+          <Text size="xs" c={check.ok ? "green" : "dimmed"}>
+            {check.ok ? check.label : `${check.label}: ${check.fix_hint}`}
+          </Text>
+        )
+      )}
+    </Stack>
+  );
+}
@@ -15,17 +15,35 @@ import { IconAlertTriangle, IconCheck, IconCopy, IconDownload, IconX } from "@ta
 import { invoke } from "@tauri-apps/api/core";
 import { appDataDir, join } from "@tauri-apps/api/path";
 import { useCallback, useEffect, useState } from "react";
-import { commands } from "@/bindings";
+import { type CheckId, commands, type DiagnosisCheck } from "@/bindings";
 import { useProgress } from "@/hooks/useProgress";
+import { diagnoseTts, failedFix } from "@/utils/tts";
 
-interface DepCheck {
-  ok: boolean;
-  label: string;
-  detail: string;
-  fix_hint: string;
+type Provider = "kittentts" | "opentts";
+type Checks = Partial<Record<CheckId, DiagnosisCheck>>;
+
+/** Runs `tts_diagnose` and indexes its checks by id. */
+function useDiagnosis(steps: CheckId[]) {
+  const [checks, setChecks] = useState<Checks>({});
+  const [active, setActive] = useState(0);
+
+  const update = useCallback(
+    (list: DiagnosisCheck[]) => {
+      const byId: Checks = Object.fromEntries(list.map((c) => [c.id, c]));
+      setChecks(byId);
+      // Auto-advance to first failing step
+      const failing = steps.findIndex((id) => !passed(byId[id] ?? null));
+      setActive(failing === -1 ? steps.length : failing);
+    },
+    [steps],
+  );
+
+  return { checks, active, setActive, update };
 }
 
-type Provider = "kittentts" | "opentts";
+function passed(check: DiagnosisCheck | null): boolean {
+  return check?.status === "ok" || check?.status === "warning";
+}
 
 interface TTSSetupWizardProps {
   opened: boolean;
@@ -36,88 +54,91 @@ interface TTSSetupWizardProps {
 
 // --- KittenTTS wizard ---
 
+const KITTENTTS_STEPS: CheckId[] = [
+  "pythonInstalled",
+  "kittenttsVenv",
+  "kittenttsPackages",
+  "kittenttsScript",
+];
+
 function KittenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?: () => void }) {
-  const [active, setActive] = useState(0);
-  const [checks, setChecks] = useState<Record<string, DepCheck | null>>({
-    python: null,
-    venv: null,
-    packages: null,
-    script: null,
-  });
+  const { checks, active, setActive, update } = useDiagnosis(KITTENTTS_STEPS);
+  const python = checks.pythonInstalled ?? null;
+  const venv = checks.kittenttsVenv ?? null;
+  const packages = checks.kittenttsPackages ?? null;
+  const script = checks.kittenttsScript ?? null;
   const [loading, setLoading] = useState<string | null>(null);
   const [setupError, setSetupError] = useState<string | null>(null);
+  const [pipLine, setPipLine] = useState<string | null>(null);
 
   const runChecks = useCallback(async () => {
     setLoading("checking");
     setSetupError(null);
     try {
-      const [python, venv, packages, script] = await Promise.all([
-        invoke<DepCheck>("check_python_installed"),
-        invoke<DepCheck>("check_kittentts_venv"),
-        invoke<DepCheck>("check_kittentts_packages"),
-        invoke<DepCheck>("check_kittentts_script"),
-      ]);
-      setChecks({ python, venv, packages, script });
-
-      // Auto-advance to first failing step
-      if (!python.ok) setActive(0);
-      else if (!venv.ok) setActive(1);
-      else if (!packages.ok) setActive(2);
-      else if (!script.ok) setActive(3);
-      else setActive(4); // all passed
+      update((await diagnoseTts()).checks);
     } catch (e) {
       console.error("Check failed:", e);
     } finally {
       setLoading(null);
     }
-  }, []);
+  }, [update]);
 
   useEffect(() => {
     runChecks();
   }, [runChecks]);
 
-  const allPassed =
-    checks.python?.ok && checks.venv?.ok && checks.packages?.ok && checks.script?.ok;
+  const allPassed = KITTENTTS_STEPS.every((id) => passed(checks[id] ?? null));
 
   const handleSetupVenv = async () => {
     setLoading("venv");
     setSetupError(null);
     try {
-      await invoke("setup_kittentts_venv");
-      await runChecks();
+      const diagnosis = await diagnoseTts(true, ({ progress }) => {
+        if (progress.state === "output") setPipLine(progress.line);
+      });
+      update(diagnosis.checks);
+      setSetupError(failedFix(diagnosis));
     } catch (e) {
       setSetupError(String(e));
     } finally {
+      setPipLine(null);
       setLoading(null);
     }
   };
 
+  const installProgress = loading === "venv" && pipLine && (
+    <Text size="xs" c="dimmed" truncate>
+      {pipLine}
+    </Text>
+  );
+
   return (
     <Stack gap="md">
       <Stepper active={active} onStepClick={setActive} size="sm">
         <Stepper.Step
           label="Python 3"
-          description={checks.python?.ok ? checks.python.detail : "Required"}
-          icon={stepIcon(checks.python)}
-          color={stepColor(checks.python)}
+          description={passed(python) ? python?.detail : "Required"}
+          icon={stepIcon(python)}
+          color={stepColor(python)}
         >
-          <StepContent check={checks.python} loading={loading === "checking"}>
-            {checks.python && !checks.python.ok && <SudoHint command={checks.python.fix_hint} />}
+          <StepContent check={python} loading={loading === "checking"}>
+            {python?.status === "failed" && <SudoHint command={python.fixHint} />}
           </StepContent>
         </Stepper.Step>
 
         <Stepper.Step
           label="Virtual Environment"
-          description={checks.venv?.ok ? "Found" : "Not created"}
-          icon={stepIcon(checks.venv)}
-          color={stepColor(checks.venv)}
+          description={passed(venv) ? "Found" : "Not created"}
+          icon={stepIcon(venv)}
+          color={stepColor(venv)}
         >
-          <StepContent check={checks.venv} loading={loading === "checking"}>
-            {checks.venv && !checks.venv.ok && (
+          <StepContent check={venv} loading={loading === "checking"}>
+            {venv?.status === "failed" && (
               <Stack gap="xs">
                 <Button size="sm" loading={loading === "venv"} onClick={handleSetupVenv}>
                   Create Venv & Install Packages
                 </Button>
+                {installProgress}
                 {setupError && (
                   <Alert color="red" icon={<IconX size={16} />}>
                     {setupError}
@@ -130,16 +151,17 @@ function KittenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?:
 
         <Stepper.Step
           label="Python Packages"
-          description={checks.packages?.ok ? "Installed" : "Missing"}
-          icon={stepIcon(checks.packages)}
-          color={stepColor(checks.packages)}
+          description={passed(packages) ? "Installed" : "Missing"}
+          icon={stepIcon(packages)}
+          color={stepColor(packages)}
         >
-          <StepContent check={checks.packages} loading={loading === "checking"}>
-            {checks.packages && !checks.packages.ok && (
+          <StepContent check={packages} loading={loading === "checking"}>
+            {packages?.status === "failed" && (
               <Stack gap="xs">
                 <Button size="sm" loading={loading === "venv"} onClick={handleSetupVenv}>
                   Install Packages
                 </Button>
+                {installProgress}
                 {setupError && (
                   <Alert color="red" icon={<IconX size={16} />}>
                     {setupError}
@@ -152,12 +174,12 @@ function KittenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?:
 
         <Stepper.Step
           label="Server Script"
-          description={checks.script?.ok ? "Found" : "Missing"}
-          icon={stepIcon(checks.script)}
-          color={stepColor(checks.script)}
+          description={passed(script) ? "Found" : "Missing"}
+          icon={stepIcon(script)}
+          color={stepColor(script)}
         >
-          <StepContent check={checks.script} loading={loading === "checking"}>
-            {checks.script && !checks.script.ok && (
+          <StepContent check={script} loading={loading === "checking"}>
+            {script?.status === "failed" && (
               <Alert color="yellow" icon={<IconAlertTriangle size={16} />}>
                 The server script should be included with the application. Try reinstalling.
               </Alert>
@@ -202,13 +224,13 @@ function KittenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?:
 
 // --- OpenTTS wizard ---
 
+const OPENTTS_STEPS: CheckId[] = ["dockerInstalled", "dockerRunning", "openttsImage"];
+
 function OpenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?: () => void }) {
-  const [active, setActive] = useState(0);
-  const [checks, setChecks] = useState<Record<string, DepCheck | null>>({
-    docker: null,
-    running: null,
-    image: null,
-  });
+  const { checks, active, setActive, update } = useDiagnosis(OPENTTS_STEPS);
+  const docker = dockerInstalled ?? null;
+  const running = dockerRunning ?? null;
+  const image = checks.openttsImage ?? null;
   const [loading, setLoading] = useState<string | null>(null);
   const [setupError, setSetupError] = useState<string | null>(null);
 
@@ -216,29 +238,19 @@ function OpenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?: ()
     setLoading("checking");
     setSetupError(null);
     try {
-      const [docker, running, image] = await Promise.all([
-        invoke<DepCheck>("check_docker_installed"),
-        invoke<DepCheck>("check_docker_running"),
-        invoke<DepCheck>("check_opentts_image"),
-      ]);
-      setChecks({ docker, running, image });
-
-      if (!docker.ok) setActive(0);
-      else if (!running.ok) setActive(1);
-      else if (!image.ok) setActive(2);
-      else setActive(3); // all passed
+      update((await diagnoseTts()).checks);
     } catch (e) {
       console.error("Check failed:", e);
     } finally {
       setLoading(null);
     }
-  }, []);
+  }, [update]);
 
   useEffect(() => {
     runChecks();
   }, [runChecks]);
 
-  const allPassed = checks.docker?.ok && checks.running?.ok && checks.image?.ok;
+  const allPassed = OPENTTS_STEPS.every((id) => passed(checks[id] ?? null));
 
   const DOWNLOAD_ID = "opentts_image_download";
   const { progress: dlProgress, isActive: dlActive } = useProgress(DOWNLOAD_ID);
@@ -280,34 +292,34 @@ function OpenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?: ()
       <Stepper active={active} onStepClick={setActive} size="sm">
         <Stepper.Step
           label="Docker"
-          description={checks.docker?.ok ? checks.docker.detail : "Required"}
-          icon={stepIcon(checks.docker)}
-          color={stepColor(checks.docker)}
+          description={passed(docker) ? docker?.detail : "Required"}
+          icon={stepIcon(docker)}
+          color={stepColor(docker)}
         >
-          <StepContent check={checks.docker} loading={loading === "checking"}>
-            {checks.docker && !checks.docker.ok && <SudoHint command={checks.docker.fix_hint} />}
+          <StepContent check={docker} loading={loading === "checking"}>
+            {docker?.status === "failed" && <SudoHint command={docker.fixHint} />}
           </StepContent>
         </Stepper.Step>
 
         <Stepper.Step
           label="Docker Running"
-          description={checks.running?.ok ? "Active" : "Not running"}
-          icon={stepIcon(checks.running)}
-          color={stepColor(checks.running)}
+          description={passed(running) ? "Active" : "Not running"}
+          icon={stepIcon(running)}
+          color={stepColor(running)}
         >
-          <StepContent check={checks.running} loading={loading === "checking"}>
-            {checks.running && !checks.running.ok && <SudoHint command={checks.running.fix_hint} />}
+          <StepContent check={running} loading={loading === "checking"}>
+            {running?.status === "failed" && <SudoHint command={running.fixHint} />}
           </StepContent>
         </Stepper.Step>
 
         <Stepper.Step
           label="OpenTTS Image"
-          description={checks.image?.ok ? "Ready" : "Not found"}
-          icon={stepIcon(checks.image)}
-          color={stepColor(checks.image)}
+          description={passed(image) ? "Ready" : "Not found"}
+          icon={stepIcon(image)}
+          color={stepColor(image)}
         >
-          <StepContent check={checks.image} loading={loading === "checking"}>
-            {checks.image && !checks.image.ok && (
+          <StepContent check={image} loading={loading === "checking"}>
+            {image?.status === "failed" && (
               <Stack gap="xs">
                 <Alert color="yellow" icon={<IconAlertTriangle size={16} />}>
                   The OpenTTS Docker image is ~1.5 GB. This may take a few minutes to download.
@@ -379,14 +391,15 @@ function OpenTTSWizard({ onClose, onReady }: { onClose: () => void; onReady?: ()
 
 // --- Shared UI helpers ---
 
-function stepIcon(check: DepCheck | null) {
+function stepIcon(check: DiagnosisCheck | null) {
   if (!check) return <Loader size={16} />;
-  return check.ok ? <IconCheck size={16} /> : <IconX size={16} />;
+  return passed(check) ? <IconCheck size={16} /> : <IconX size={16} />;
 }
 
-function stepColor(check: DepCheck | null): string | undefined {
+function stepColor(check: DiagnosisCheck | null): string | undefined {
   if (!check) return undefined;
-  return check.ok ? "green" : "red";
+  if (check.status === "blocked") return "gray";
+  return passed(check) ? "green" : "red";
 }
 
 function StepContent({
@@ -394,7 +407,7 @@ function StepContent({
   loading,
   children,
 }: {
-  check: DepCheck | null;
+  check: DiagnosisCheck | null;
   loading: boolean;
   children: React.ReactNode;
 }) {
@@ -412,8 +425,8 @@ function StepContent({
   return (
     <Stack gap="sm" mt="sm">
       <Group gap="xs">
-        <ThemeIcon size="sm" color={check.ok ? "green" : "red"} variant="light" radius="xl">
-          {check.ok ? <IconCheck size={12} /> : <IconX size={12} />}
+        <ThemeIcon size="sm" color={stepColor(check)} variant="light" radius="xl">
+          {passed(check) ? <IconCheck size={12} /> : <IconX size={12} />}
         </ThemeIcon>
         <Text size="sm">{check.label}</Text>
       </Group>
@@ -14,7 +14,7 @@ import type {
 } from "jotai/vanilla/utils/atomWithStorage";
 import type { ReviewLog } from "ts-fsrs";
 import { z } from "zod";
-import type { BestMoves, GoMode } from "@/bindings";
+import type { BestMoves, EnginePoolConfig, GoMode } from "@/bindings";
 import type { OpponentSettings } from "@/components/boards/OpponentForm";
 import { DEFAULT_TIME_CONTROL } from "@/utils/clock";
 import { type Position, positionSchema } from "@/components/files/opening";
@@ -150,6 +150,14 @@ export const enginesAtom = unwrap(
     ),
 );
 
+/** Caps for the shared engine pool, applied at startup; `null` keeps the backend defaults. */
+export const enginePoolConfigAtom = atomWithStorage<EnginePoolConfig | null>(
+    "engine-pool-config",
+    null,
+    undefined,
+    { getOnInit: true },
+);
+
 // Settings
 
 export const tableViewAtom = atomWithStorage<boolean>("table-view", false);
@@ -290,7 +298,10 @@ export const docLangAtom = atomWithStorage<string>("doc-language", "en", undefin
 export const pieceSetAtom = atomWithStorage<string>("piece-set", "staunty");
 export const boardImageAtom = atomWithStorage<string>("board-image", "gray.svg");
 export const primaryColorAtom = atomWithStorage<MantineColor>("mantine-primary-color", "blue");
-export const sessionsAtom = atomWithStorage<Session[]>("sessions", []);
+// Read on init so startup can move legacy tokens out before any page mounts
+export const sessionsAtom = atomWithStorage<Session[]>("sessions", [], undefined, {
+    getOnInit: true,
+});
 export const nativeBarAtom = atomWithStorage<boolean>("native-bar", false);
 export const zenModeAtom = atomWithStorage<boolean>("zen-mode", false, undefined, {
     getOnInit: true,
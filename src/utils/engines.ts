@@ -7,6 +7,14 @@ import { unwrap } from "./unwrap";
 
 export const requiredEngineSettings = ["MultiPV", "Threads", "Hash"];
 
+const playersTimeSchema = z.object({
+    white: z.number(),
+    black: z.number(),
+    winc: z.number(),
+    binc: z.number(),
+    movestogo: z.number().nullish(),
+});
+
 const goModeSchema: z.ZodSchema<GoMode> = z.union([
     z.object({
         t: z.literal("Depth"),
@@ -20,6 +28,26 @@ const goModeSchema: z.ZodSchema<GoMode> = z.union([
         t: z.literal("Nodes"),
         c: z.number(),
     }),
+    z.object({
+        t: z.literal("Mate"),
+        c: z.number(),
+    }),
+    z.object({
+        t: z.literal("PlayersTime"),
+        c: playersTimeSchema,
+    }),
+    z.object({
+        t: z.literal("Limits"),
+        c: z.object({
+            depth: z.number().nullish(),
+            nodes: z.number().nullish(),
+            movetime: z.number().nullish(),
+            mate: z.number().nullish(),
+            clock: playersTimeSchema.nullish(),
+            searchMoves: z.array(z.string()),
+            ponder: z.boolean(),
+        }),
+    }),
     z.object({
         t: z.literal("Infinite"),
     }),
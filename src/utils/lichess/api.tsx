@@ -1,6 +1,7 @@
 import type { Color } from "@lichess-org/chessground/types";
 import { notifications } from "@mantine/notifications";
 import { IconX } from "@tabler/icons-react";
+import { invoke } from "@tauri-apps/api/core";
 import { resolve } from "@tauri-apps/api/path";
 import { fetch } from "@tauri-apps/plugin-http";
 import { error } from "@tauri-apps/plugin-log";
@@ -31,6 +32,17 @@ const baseURL = "https://lichess.org/api";
 const explorerURL = "https://explorer.lichess.org";
 const tablebaseURL = "https://tablebase.lichess.org";
 
+/** Name of the backend secret holding a lichess account's OAuth token. */
+export function lichessTokenSecret(username: string): string {
+  return `oauth.lichess.${username.toLowerCase()}`;
+}
+
+/** GETs `url` with the token stored under `tokenSecret`; the token stays in the backend. */
+async function authorizedGet<T>(url: string, tokenSecret: string): Promise<T> {
+  const body = await invoke<string>("authorized_fetch", { url, authSecret: tokenSecret });
+  return JSON.parse(body);
+}
+
 export const MIN_DATE = new Date(1952, 0, 1);
 
 export type TablebaseCategory =
@@ -206,22 +218,28 @@ type PositionData = {
 };
 
 export async function getLichessAccount({
-  token,
+  tokenSecret,
   username,
 }: {
-  token?: string;
+  tokenSecret?: string;
   username?: string;
 }): Promise<LichessAccount | null> {
-  let response: Response;
-  if (token) {
-    response = await fetch(`${baseURL}/account`, {
-      method: "GET",
-      headers: apiHeaders({ Authorization: `Bearer ${token}` }),
-    });
-  } else {
-    const url = `${baseURL}/user/${username}`;
-    response = await fetch(url, { headers: apiHeaders() });
+  if (tokenSecret) {
+    try {
+      return await authorizedGet<LichessAccount>(`${baseURL}/account`, tokenSecret);
+    } catch (e) {
+      error(`Failed to fetch Lichess account: ${e}`);
+      notifications.show({
+        title: "Failed to fetch Lichess account",
+        message: String(e),
+        color: "red",
+        icon: <IconX />,
+      });
+      return null;
+    }
   }
+  const url = `${baseURL}/user/${username}`;
+  const response = await fetch(url, { headers: apiHeaders() });
   if (!response.ok) {
     error(`Failed to fetch Lichess account: ${response.status} ${response.url}`);
     notifications.show({
@@ -331,7 +349,7 @@ async function getCloudEvaluation(fen: string, multipv: number): Promise<Lichess
 export async function getLichessGames(
   fen: string,
   options: LichessGamesOptions,
-  token?: string,
+  tokenSecret?: string,
 ): Promise<PositionData> {
   const url = match(options.player)
     .with(
@@ -339,14 +357,11 @@ export async function getLichessGames(
       () => `${explorerURL}/lichess?${getLichessGamesQueryParams(fen, options)}`,
     )
     .otherwise(() => `${explorerURL}/player?${getLichessGamesQueryParams(fen, options)}`);
+  if (tokenSecret) {
+    return authorizedGet(url, tokenSecret);
+  }
   const res = await fetch(url, {
-    headers: apiHeaders(
-      token
-        ? {
-            Authorization: `Bearer ${token}`,
-          }
-        : undefined,
-    ),
+    headers: apiHeaders(),
   });
   if (!res.ok) {
     throw new Error(`Lichess explorer: HTTP ${res.status}`);
@@ -357,17 +372,14 @@ export async function getLichessGames(
 export async function getMasterGames(
   fen: string,
   options: MasterGamesOptions,
-  token?: string,
+  tokenSecret?: string,
 ): Promise<PositionData> {
   const url = `${explorerURL}/masters?${getMasterGamesQueryParams(fen, options)}`;
+  if (tokenSecret) {
+    return authorizedGet(url, tokenSecret);
+  }
   const res = await fetch(url, {
-    headers: apiHeaders(
-      token
-        ? {
-            Authorization: `Bearer ${token}`,
-          }
-        : undefined,
-    ),
+    headers: apiHeaders(),
   });
   if (!res.ok) {
     throw new Error(`Lichess masters: HTTP ${res.status}`);
@@ -375,15 +387,18 @@ export async function getMasterGames(
   return await res.json();
 }
 
-export async function getPlayerGames(fen: string, player: string, color: Color, token?: string) {
-  const res = await fetch(`${explorerURL}/player?fen=${fen}&player=${player}&color=${color}`, {
-    headers: apiHeaders(
-      token
-        ? {
-            Authorization: `Bearer ${token}`,
-          }
-        : undefined,
-    ),
+export async function getPlayerGames(
+  fen: string,
+  player: string,
+  color: Color,
+  tokenSecret?: string,
+) {
+  const url = `${explorerURL}/player?fen=${fen}&player=${player}&color=${color}`;
+  if (tokenSecret) {
+    return authorizedGet(url, tokenSecret);
+  }
+  const res = await fetch(url, {
+    headers: apiHeaders(),
   });
   if (!res.ok) {
     throw new Error(`Lichess player explorer: HTTP ${res.status}`);
@@ -396,7 +411,7 @@ export async function downloadLichess(
   timestamp: number | null,
   games: number,
   setProgress: (progress: number) => void,
-  token?: string,
+  tokenSecret?: string,
   includeUnrated?: boolean,
 ) {
   let url = `${baseURL}/games/user/${player}?perfType=ultraBullet,bullet,blitz,rapid,classical,correspondence&sort=dateAsc`;
@@ -412,7 +427,7 @@ export async function downloadLichess(
     `lichess_${player}`,
     url,
     path,
-    token ?? null,
+    tokenSecret ?? null,
     null,
     games > 0 ? games * 900 : null, // approx. size of a game
   );
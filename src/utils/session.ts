@@ -2,6 +2,9 @@ import type { ChessComStats } from "@/utils/chess.com/api";
 import type { LichessAccount } from "@/utils/lichess/api";
 
 export type LichessSession = {
+    /** Backend secret holding the OAuth token, see `lichessTokenSecret` */
+    tokenSecret?: string;
+    /** Token kept here before the secrets store; moved there on load */
     accessToken?: string;
     username: string;
     account: LichessAccount;
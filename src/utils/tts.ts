@@ -1,7 +1,7 @@
 import { notifications } from "@mantine/notifications";
 import { invoke } from "@tauri-apps/api/core";
-import { fetch } from "@tauri-apps/plugin-http";
 import { getDefaultStore } from "jotai";
+import { type CheckId, commands, events, type TtsDiagnosis, type TtsFetchError, type TtsFixEvent } from "@/bindings";
 import {
     ttsApiKeyAtom,
     ttsAutoNarrateAtom,
@@ -22,6 +22,7 @@ import {
     ttsVoiceIdAtom,
     ttsVolumeAtom,
 } from "@/state/atoms";
+import { getPGN } from "@/utils/chess";
 import { playCloudNarration, playDemoNarration } from "@/utils/cloudTts";
 import type { GameHeaders, TreeNode } from "@/utils/treeReducer";
 
@@ -767,53 +768,108 @@ export function buildNarration(
 
 // --- ElevenLabs API ---
 
-const ELEVENLABS_API_URL = "https://api.elevenlabs.io/v1/text-to-speech";
 const DEFAULT_VOICE_ID = "pNInz6obpgDQGcFmaJgB"; // Adam
 
-async function generateSpeech(
+/** Kept in the API key setting once the key itself is in the secrets store. */
+export const API_KEY_STORED = "stored";
+
+const API_KEY_ATOMS = {
+    elevenlabs: ttsApiKeyAtom,
+    google: ttsGoogleApiKeyAtom,
+    grok: ttsGrokApiKeyAtom,
+};
+
+/**
+ * Saves a provider's API key in the backend secrets store, which is where
+ * the backend reads it from. An empty key removes it.
+ */
+export async function saveApiKey(provider: keyof typeof API_KEY_ATOMS, key: string) {
+    const name = `tts.${provider}`;
+    if (key) {
+        await invoke("secrets_put", { name, value: key });
+    } else {
+        await invoke("secrets_delete", { name });
+    }
+    getDefaultStore().set(API_KEY_ATOMS[provider], key ? API_KEY_STORED : "");
+}
+
+/**
+ * Whether a provider has an API key. Keys saved in settings by older
+ * versions are moved into the secrets store first.
+ */
+async function ensureApiKey(provider: keyof typeof API_KEY_ATOMS): Promise<boolean> {
+    const key = getDefaultStore().get(API_KEY_ATOMS[provider]);
+    if (key && key !== API_KEY_STORED) await saveApiKey(provider, key);
+    return !!key;
+}
+
+let cloudRequestCount = 0;
+
+/**
+ * Synthesizes through a cloud provider in the backend, which rate limits
+ * the request and records it in the usage ledger. Aborting `signal` cancels
+ * the request.
+ */
+async function generateSpeechCloud(
+    provider: "elevenlabs" | "google" | "grok",
     text: string,
-    apiKey: string,
-    voiceId: string,
+    voice: string,
     lang = "en",
     signal?: AbortSignal,
 ): Promise<ArrayBuffer> {
-    const body: Record<string, unknown> = {
-        text,
-        model_id: "eleven_turbo_v2_5",
-        voice_settings: {
-            stability: 0.5,
-            similarity_boost: 0.75,
-            style: 0.0,
-            use_speaker_boost: true,
-        },
+    // One id per call, so aborting a superseded call can't cancel a newer one
+    const requestId = signal ? `cloud-${++cloudRequestCount}` : undefined;
+    const cancel = () => {
+        invoke("cancel_tts_fetch", { requestId }).catch(() => {});
     };
-
-    // Add language_code for non-English to guide the TTS accent/pronunciation
-    if (lang !== "en") {
-        body.language_code = lang;
+    signal?.addEventListener("abort", cancel, { once: true });
+    try {
+        const bytes: number[] = await invoke("tts_synthesize", {
+            provider,
+            text,
+            voice,
+            options: { language: lang, requestId },
+        });
+        return new Uint8Array(bytes).buffer;
+    } finally {
+        signal?.removeEventListener("abort", cancel);
     }
+}
 
-    const response = await fetch(`${ELEVENLABS_API_URL}/${voiceId}`, {
-        method: "POST",
-        headers: {
-            "xi-api-key": apiKey,
-            "Content-Type": "application/json",
-        },
-        body: JSON.stringify(body),
-        signal,
-    });
-
-    if (!response.ok) {
-        const errorText = await response.text();
-        throw new Error(`ElevenLabs API error ${response.status}: ${errorText}`);
+/** Readable text for a `TtsFetchError` from the backend, or any other error. */
+function ttsErrorText(e: unknown): string {
+    if (typeof e !== "object" || e === null || !("kind" in e)) return String(e);
+    const err = e as TtsFetchError;
+    switch (err.kind) {
+        case "status":
+            return `Server error ${err.status}: ${err.body}`;
+        case "network":
+        case "invalidUrl":
+            return err.message;
+        case "connectionRefused":
+            return `Could not connect to ${err.url}`;
+        case "timeout":
+            return `Timed out after ${err.timeoutMs} ms`;
+        case "cancelled":
+            return "Request cancelled";
     }
+}
 
-    return response.arrayBuffer();
+/** Post-processing in the backend turns any provider's audio into WAV. */
+function isWav(data: ArrayBuffer): boolean {
+    return String.fromCharCode(...new Uint8Array(data.slice(0, 4))) === "RIFF";
 }
 
-// --- Google Cloud TTS API ---
+async function generateSpeech(
+    text: string,
+    voiceId: string,
+    lang = "en",
+    signal?: AbortSignal,
+): Promise<ArrayBuffer> {
+    return generateSpeechCloud("elevenlabs", text, voiceId, lang, signal);
+}
 
-const GOOGLE_TTS_URL = "https://texttospeech.googleapis.com/v1/text:synthesize";
+// --- Google Cloud TTS API ---
 
 type GoogleVoice = { languageCode: string; name: string };
 const GOOGLE_VOICES: Record<string, Record<string, GoogleVoice>> = {
@@ -955,46 +1011,16 @@ function getGoogleVoice(lang: string, gender: string): GoogleVoice {
 
 async function generateSpeechGoogle(
     text: string,
-    apiKey: string,
     lang = "en",
     gender = "MALE",
     signal?: AbortSignal,
 ): Promise<ArrayBuffer> {
-    const voiceConfig = getGoogleVoice(lang, gender);
-
-    const response = await fetch(`${GOOGLE_TTS_URL}?key=${apiKey}`, {
-        method: "POST",
-        headers: { "Content-Type": "application/json" },
-        body: JSON.stringify({
-            input: { text },
-            voice: {
-                languageCode: voiceConfig.languageCode,
-                name: voiceConfig.name,
-                ssmlGender: gender,
-            },
-            audioConfig: { audioEncoding: "MP3" },
-        }),
-        signal,
-    });
-
-    if (!response.ok) {
-        const errorText = await response.text();
-        throw new Error(`Google TTS API error ${response.status}: ${errorText}`);
-    }
-
-    const data = await response.json();
-    const binaryStr = atob(data.audioContent);
-    const bytes = new Uint8Array(binaryStr.length);
-    for (let i = 0; i < binaryStr.length; i++) {
-        bytes[i] = binaryStr.charCodeAt(i);
-    }
-    return bytes.buffer;
+    const voice = getGoogleVoice(lang, gender).name;
+    return generateSpeechCloud("google", text, voice, lang, signal);
 }
 
 // --- Grok (xAI) TTS API ---
 
-const GROK_TTS_URL = "https://api.x.ai/v1/tts";
-
 export const GROK_VOICES = [
     { id: "sal", label: "Sal (Neutral, balanced)" },
     { id: "eve", label: "Eve (Female, energetic)" },
@@ -1005,31 +1031,11 @@ export const GROK_VOICES = [
 
 async function generateSpeechGrok(
     text: string,
-    apiKey: string,
     voiceId: string,
     lang = "en",
     signal?: AbortSignal,
 ): Promise<ArrayBuffer> {
-    const response = await fetch(GROK_TTS_URL, {
-        method: "POST",
-        headers: {
-            Authorization: `Bearer ${apiKey}`,
-            "Content-Type": "application/json",
-        },
-        body: JSON.stringify({
-            text,
-            voice_id: voiceId,
-            language: lang,
-        }),
-        signal,
-    });
-
-    if (!response.ok) {
-        const errorText = await response.text();
-        throw new Error(`Grok TTS API error ${response.status}: ${errorText}`);
-    }
-
-    return response.arrayBuffer();
+    return generateSpeechCloud("grok", text, voiceId, lang, signal);
 }
 
 // --- OpenTTS API ---
@@ -1038,12 +1044,15 @@ async function generateSpeechOpenTTS(
     text: string,
     serverUrl: string,
     voice: string,
-    _lang = "en",
+    lang = "en",
+    requestId?: string,
 ): Promise<ArrayBuffer> {
-    const params = new URLSearchParams({ voice, text });
-    const url = `${serverUrl}/api/tts?${params}`;
-
-    const bytes: number[] = await invoke("fetch_tts_audio", { url });
+    const bytes: number[] = await invoke("tts_synthesize", {
+        provider: "opentts",
+        text,
+        voice,
+        options: { serverUrl, language: lang, requestId },
+    });
     return new Uint8Array(bytes).buffer;
 }
 
@@ -1081,14 +1090,65 @@ async function generateSpeechKittenTTS(
     text: string,
     serverUrl: string,
     voice: string,
+    requestId?: string,
 ): Promise<ArrayBuffer> {
-    const params = new URLSearchParams({ text, voice });
-    const url = `${serverUrl}/api/tts?${params}`;
-
-    const bytes: number[] = await invoke("fetch_tts_audio", { url });
+    const bytes: number[] = await invoke("tts_synthesize", {
+        provider: "kittentts",
+        text,
+        voice,
+        options: { serverUrl, requestId },
+    });
     return new Uint8Array(bytes).buffer;
 }
 
+/** Starts the OpenTTS container, publishing it on the port of the configured URL. */
+export async function startOpenTTSContainer(): Promise<void> {
+    const serverUrl = getDefaultStore().get(ttsOpenTTSUrlAtom) || "http://localhost:5500";
+    const port = Number(new URL(serverUrl).port) || null;
+    await invoke("opentts_start", { lang: openTTSLanguage(), port });
+}
+
+/** OpenTTS images are tagged by base language, e.g. "en" for "en-GB". */
+function openTTSLanguage(): string {
+    return (getDefaultStore().get(ttsLanguageAtom) || "en").split("-")[0];
+}
+
+// --- Diagnosis ---
+
+/** Checks that need a running server rather than an installed dependency. */
+const SERVER_CHECKS: CheckId[] = ["openttsPort", "openttsServer", "kittenttsPort", "kittenttsServer"];
+
+/**
+ * Checks every local TTS dependency at once. With `fix`, the backend also
+ * runs the safe fixes (creating the venv, installing packages), reporting
+ * each step to `onFix`.
+ */
+export async function diagnoseTts(
+    fix = false,
+    onFix?: (event: TtsFixEvent) => void,
+): Promise<TtsDiagnosis> {
+    const unlisten = onFix ? await events.ttsFixEvent.listen((e) => onFix(e.payload)) : null;
+    try {
+        const result = await commands.ttsDiagnose(openTTSLanguage(), fix);
+        if (result.status === "error") throw new Error(result.error);
+        return result.data;
+    } finally {
+        unlisten?.();
+    }
+}
+
+/** Whether everything `provider` needs is installed; its server may still be stopped. */
+export function dependenciesReady(diagnosis: TtsDiagnosis, provider: "kittentts" | "opentts"): boolean {
+    return diagnosis.checks
+        .filter((c) => c.provider === provider && !SERVER_CHECKS.includes(c.id))
+        .every((c) => c.status === "ok" || c.status === "warning");
+}
+
+/** The message of the first fix that failed, if any. */
+export function failedFix(diagnosis: TtsDiagnosis): string | null {
+    return diagnosis.fixes.find((f) => !f.ok)?.message ?? null;
+}
+
 export const KITTENTTS_VOICES = [
     { id: "expr-voice-2-m", label: "Voice 2 (Male)" },
     { id: "expr-voice-2-f", label: "Voice 2 (Female)" },
@@ -1118,23 +1178,30 @@ async function speakSystemTTS(
     rate: number,
     volume: number,
 ): Promise<void> {
-    if (voiceId) {
-        await invoke("system_tts_set_voice", { voiceId });
-    }
-    await invoke("system_tts_speak", {
+    await invoke("tts_speak", {
+        provider: "system",
         text,
-        rate, // normalized: 0.5-2.0, 1.0 = normal (Rust maps to platform range)
-        volume, // normalized: 0.0-1.0 (Rust maps to platform range)
-        pitch: null,
+        voice: voiceId,
+        options: {
+            rate, // normalized: 0.5-2.0, 1.0 = normal (Rust maps to platform range)
+            volume, // normalized: 0.0-1.0 (Rust maps to platform range)
+        },
     });
 }
 
 export async function stopSystemTTS(): Promise<void> {
-    await invoke("system_tts_stop");
+    await invoke("tts_stop", { provider: "system" });
 }
 
 // --- KittenTTS auto-start ---
 
+// Health checks should answer quickly; the polling loop does its own retrying
+const HEALTH_CHECK_FETCH = { timeoutMs: 2000, retries: 0 };
+
+// In-flight local server request for the utterance being spoken, cancelled
+// when the user moves on
+const SPEAK_REQUEST_ID = "speak";
+
 // Tracks whether the server is confirmed running (health check passed).
 // Reset to false on failure so subsequent calls can retry.
 let kittenTTSServerConfirmed = false;
@@ -1148,6 +1215,7 @@ async function ensureKittenTTSRunning(): Promise<boolean> {
             const serverUrl = store.get(ttsKittenTTSUrlAtom) || "http://localhost:8192";
             const bytes: number[] = await invoke("fetch_tts_audio", {
                 url: `${serverUrl}/api/voices`,
+                options: HEALTH_CHECK_FETCH,
             });
             if (bytes.length > 0) return true;
         } catch {
@@ -1175,6 +1243,7 @@ async function startKittenTTSServer(): Promise<boolean> {
     try {
         const bytes: number[] = await invoke("fetch_tts_audio", {
             url: `${serverUrl}/api/voices`,
+            options: HEALTH_CHECK_FETCH,
         });
         if (bytes.length > 0) {
             kittenTTSServerConfirmed = true;
@@ -1211,9 +1280,18 @@ async function startKittenTTSServer(): Promise<boolean> {
         console.error("KittenTTS dependency check failed:", e);
     }
 
+    let readyUrl = serverUrl;
     try {
         const threads = store.get(ttsKittenTTSThreadsAtom) || null;
-        await invoke("kittentts_start", { threads });
+        const requestedPort = Number(new URL(serverUrl).port) || null;
+        // The supervisor falls back to a free port if the configured one is taken
+        const port = await invoke<number>("kittentts_start", { threads, port: requestedPort });
+        if (port !== requestedPort) {
+            const url = new URL(serverUrl);
+            url.port = String(port);
+            readyUrl = url.origin;
+            store.set(ttsKittenTTSUrlAtom, readyUrl);
+        }
     } catch (e) {
         console.error("Failed to auto-start KittenTTS:", e);
         showTtsNotification(
@@ -1228,7 +1306,8 @@ async function startKittenTTSServer(): Promise<boolean> {
         await new Promise((r) => setTimeout(r, 1000));
         try {
             const bytes: number[] = await invoke("fetch_tts_audio", {
-                url: `${serverUrl}/api/voices`,
+                url: `${readyUrl}/api/voices`,
+                options: HEALTH_CHECK_FETCH,
             });
             if (bytes.length > 0) {
                 kittenTTSServerConfirmed = true;
@@ -1254,6 +1333,7 @@ export function stopSpeaking() {
         currentAbort.abort();
         currentAbort = null;
     }
+    invoke("cancel_tts_fetch", { requestId: SPEAK_REQUEST_ID }).catch(() => {});
     // Bump generation so any pending responses are discarded
     requestGeneration++;
     // Stop any playing audio
@@ -1291,7 +1371,6 @@ export async function speakText(text: string): Promise<void> {
         return;
     }
 
-    let apiKey = "";
     let voiceId: string;
     let openTTSUrl = "";
     let kittenTTSUrl = "";
@@ -1314,9 +1393,8 @@ export async function speakText(text: string): Promise<void> {
             return;
         }
     } else if (provider === "grok") {
-        apiKey = store.get(ttsGrokApiKeyAtom);
         voiceId = store.get(ttsGrokVoiceAtom) || "sal";
-        if (!apiKey) {
+        if (!(await ensureApiKey("grok"))) {
             showTtsNotification(
                 "Grok TTS: API key required",
                 "Go to Settings > Sound and enter your xAI API key.",
@@ -1324,10 +1402,9 @@ export async function speakText(text: string): Promise<void> {
             return;
         }
     } else if (provider === "google") {
-        apiKey = store.get(ttsGoogleApiKeyAtom);
         gender = store.get(ttsGoogleGenderAtom) || "MALE";
         voiceId = getGoogleVoice(lang, gender).name;
-        if (!apiKey) {
+        if (!(await ensureApiKey("google"))) {
             showTtsNotification(
                 "Google Cloud TTS: API key required",
                 "Go to Settings > Sound and enter your Google Cloud API key.",
@@ -1335,9 +1412,8 @@ export async function speakText(text: string): Promise<void> {
             return;
         }
     } else {
-        apiKey = store.get(ttsApiKeyAtom);
         voiceId = store.get(ttsVoiceIdAtom) || DEFAULT_VOICE_ID;
-        if (!apiKey) {
+        if (!(await ensureApiKey("elevenlabs"))) {
             showTtsNotification(
                 "ElevenLabs: API key required",
                 "Go to Settings > Sound and enter your ElevenLabs API key.",
@@ -1359,8 +1435,12 @@ export async function speakText(text: string): Promise<void> {
     const isCloudProvider =
         provider === "elevenlabs" || provider === "grok" || provider === "google";
     let timeout: ReturnType<typeof setTimeout> | null = null;
+    let timedOut = false;
     if (isCloudProvider) {
-        timeout = setTimeout(() => abort.abort(), 15000);
+        timeout = setTimeout(() => {
+            timedOut = true;
+            abort.abort();
+        }, 15000);
     }
     const maxAttempts = isLocalServer ? 2 : 1;
 
@@ -1374,26 +1454,32 @@ export async function speakText(text: string): Promise<void> {
                 let audioData: ArrayBuffer;
                 let mimeType = "audio/mpeg";
                 if (provider === "kittentts") {
-                    audioData = await generateSpeechKittenTTS(text, kittenTTSUrl, voiceId);
+                    audioData = await generateSpeechKittenTTS(
+                        text,
+                        kittenTTSUrl,
+                        voiceId,
+                        SPEAK_REQUEST_ID,
+                    );
                     mimeType = "audio/wav";
                 } else if (provider === "opentts") {
-                    audioData = await generateSpeechOpenTTS(text, openTTSUrl, voiceId, lang);
-                    mimeType = "audio/wav";
-                } else if (provider === "grok") {
-                    audioData = await generateSpeechGrok(text, apiKey, voiceId, lang, abort.signal);
-                } else if (provider === "google") {
-                    audioData = await generateSpeechGoogle(
+                    audioData = await generateSpeechOpenTTS(
                         text,
-                        apiKey,
+                        openTTSUrl,
+                        voiceId,
                         lang,
-                        gender,
-                        abort.signal,
+                        SPEAK_REQUEST_ID,
                     );
+                    mimeType = "audio/wav";
+                } else if (provider === "grok") {
+                    audioData = await generateSpeechGrok(text, voiceId, lang, abort.signal);
+                } else if (provider === "google") {
+                    audioData = await generateSpeechGoogle(text, lang, gender, abort.signal);
                 } else {
-                    audioData = await generateSpeech(text, apiKey, voiceId, lang, abort.signal);
+                    audioData = await generateSpeech(text, voiceId, lang, abort.signal);
                 }
                 if (thisGeneration !== requestGeneration) return;
 
+                if (isWav(audioData)) mimeType = "audio/wav";
                 const blob = new Blob([audioData], { type: mimeType });
                 blobUrl = URL.createObjectURL(blob);
                 audioCache.set(cacheKey, blobUrl);
@@ -1409,8 +1495,13 @@ export async function speakText(text: string): Promise<void> {
             return; // success — exit retry loop
         } catch (e) {
             if (timeout) clearTimeout(timeout);
-            if (e instanceof DOMException && e.name === "AbortError") return;
-            if (e instanceof TypeError && /cancel|abort/i.test(e.message)) return;
+            const kind = (e as TtsFetchError | null)?.kind;
+            if (!timedOut) {
+                if (e instanceof DOMException && e.name === "AbortError") return;
+                if (e instanceof TypeError && /cancel|abort/i.test(e.message)) return;
+                // Superseded or stopped request (see tts_synthesize's TtsFetchError)
+                if (kind === "cancelled") return;
+            }
             if (attempt < maxAttempts) {
                 // Server may still be starting — wait and retry
                 console.log(`TTS attempt ${attempt} failed, retrying in 2s...`);
@@ -1432,7 +1523,6 @@ export async function speakText(text: string): Promise<void> {
                         : `System TTS error: ${e}`,
                 );
             } else if (isCloudProvider) {
-                const msg = String(e);
                 const providerName =
                     provider === "grok"
                         ? "Grok"
@@ -1441,9 +1531,9 @@ export async function speakText(text: string): Promise<void> {
                           : "ElevenLabs";
                 showTtsNotification(
                     `${providerName} TTS failed`,
-                    msg.includes("abort") || msg.includes("timeout")
+                    timedOut || kind === "timeout"
                         ? "Request timed out. Check your internet connection."
-                        : msg,
+                        : ttsErrorText(e),
                 );
             }
         }
@@ -1528,6 +1618,9 @@ function narrationForNode(node: TreeNode, lang: string): string | null {
     );
 }
 
+let precacheJobId: string | null = null;
+let precacheJobCount = 0;
+
 // Precache all narrations for a game tree in the background
 // Fires API calls sequentially to avoid rate limiting
 export async function precacheGame(root: TreeNode): Promise<number> {
@@ -1540,7 +1633,6 @@ export async function precacheGame(root: TreeNode): Promise<number> {
     // System TTS doesn't support precaching (speaks directly via OS)
     if (provider === "system") return 0;
 
-    let apiKey = "";
     let voiceId: string;
     let gender = "MALE";
     let openTTSUrl = "";
@@ -1554,18 +1646,44 @@ export async function precacheGame(root: TreeNode): Promise<number> {
         voiceId = store.get(ttsOpenTTSVoiceAtom) || "";
         if (!voiceId) return 0;
     } else if (provider === "grok") {
-        apiKey = store.get(ttsGrokApiKeyAtom);
         voiceId = store.get(ttsGrokVoiceAtom) || "sal";
-        if (!apiKey) return 0;
+        if (!(await ensureApiKey("grok"))) return 0;
     } else if (provider === "google") {
-        apiKey = store.get(ttsGoogleApiKeyAtom);
         gender = store.get(ttsGoogleGenderAtom) || "MALE";
         voiceId = getGoogleVoice(lang, gender).name;
-        if (!apiKey) return 0;
+        if (!(await ensureApiKey("google"))) return 0;
     } else {
-        apiKey = store.get(ttsApiKeyAtom);
         voiceId = store.get(ttsVoiceIdAtom) || DEFAULT_VOICE_ID;
-        if (!apiKey) return 0;
+        if (!(await ensureApiKey("elevenlabs"))) return 0;
+    }
+
+    // Local servers are precached by a backend job that keeps running when the view closes
+    if (provider === "kittentts" || provider === "opentts") {
+        const pgn = getPGN(root, {
+            headers: null,
+            glyphs: true,
+            comments: true,
+            variations: true,
+            extraMarkups: false,
+        });
+        // Only one game is precached at a time
+        if (precacheJobId) {
+            invoke("cancel_tts_job", { id: precacheJobId }).catch(() => {});
+        }
+        const id = `tts_precache_${++precacheJobCount}`;
+        precacheJobId = id;
+        const summary = await invoke<{ total: number; cached: number }>("precache_game", {
+            id,
+            pgn,
+            options: {
+                provider,
+                voice: voiceId,
+                lang,
+                serverUrl: provider === "kittentts" ? kittenTTSUrl : openTTSUrl,
+            },
+        });
+        if (precacheJobId === id) precacheJobId = null;
+        return summary.cached;
     }
 
     // Collect all narration texts
@@ -1604,12 +1722,13 @@ export async function precacheGame(root: TreeNode): Promise<number> {
                 audioData = await generateSpeechOpenTTS(text, openTTSUrl, voiceId, lang);
                 mimeType = "audio/wav";
             } else if (provider === "grok") {
-                audioData = await generateSpeechGrok(text, apiKey, voiceId, lang);
+                audioData = await generateSpeechGrok(text, voiceId, lang);
             } else if (provider === "google") {
-                audioData = await generateSpeechGoogle(text, apiKey, lang, gender);
+                audioData = await generateSpeechGoogle(text, lang, gender);
             } else {
-                audioData = await generateSpeech(text, apiKey, voiceId, lang);
+                audioData = await generateSpeech(text, voiceId, lang);
             }
+            if (isWav(audioData)) mimeType = "audio/wav";
             const blob = new Blob([audioData], { type: mimeType });
             const blobUrl = URL.createObjectURL(blob);
             audioCache.set(cacheKey, blobUrl);
@@ -1632,20 +1751,16 @@ export interface ElevenLabsVoice {
     category: string;
 }
 
-export async function listVoices(apiKey: string): Promise<ElevenLabsVoice[]> {
-    const response = await fetch("https://api.elevenlabs.io/v1/voices", {
-        headers: { "xi-api-key": apiKey },
+export async function listVoices(): Promise<ElevenLabsVoice[]> {
+    await ensureApiKey("elevenlabs");
+    const voices: { id: string; name: string }[] = await invoke("tts_list_voices", {
+        provider: "elevenlabs",
+        options: {},
     });
-
-    if (!response.ok) {
-        throw new Error(`Failed to list voices: ${response.status}`);
-    }
-
-    const data = await response.json();
-    return (data.voices || []).map((v: any) => ({
-        voice_id: v.voice_id,
+    return voices.map((v) => ({
+        voice_id: v.id,
         name: v.name,
-        category: v.category || "premade",
+        category: "premade",
     }));
 }
 